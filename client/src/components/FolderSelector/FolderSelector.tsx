@@ -33,13 +33,13 @@ const getMenuItemsFromPath = (path: string[]) => {
 };
 
 /**
- * - Mac: "/f1/f2" <-> ["/", "f1", "f2"]
+ * - Mac/Linux: "/f1/f2" <-> ["/", "f1", "f2"]
  * - Windows: "D:/f1/f2" <-> ["", "D:", "f1", "f2"]
  * */
 function normalizeFolderPath<P extends string[] | string, R = P extends string[] ? string : string[]>(path: P): R {
   if (Array.isArray(path)) {
     const trimmedPath = path.filter((p) => p); // remove empty string for windows root dir
-    // Macos home_dir will be '/'
+    // Macos/Linux home_dir will be '/'
     return trimmedPath.join('/').replaceAll('//', '/') as R;
   }
 
@@ -48,7 +48,7 @@ function normalizeFolderPath<P extends string[] | string, R = P extends string[]
     trimmedPath = path.slice(0, -1);
   }
 
-  // Macos
+  // Macos/Linux
   if (trimmedPath.startsWith('/')) {
     return ['/', ...trimmedPath.split('/').slice(1)] as R;
   }
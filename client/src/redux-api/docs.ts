@@ -4,6 +4,7 @@ import { createApi, fetchBaseQuery } from '@reduxjs/toolkit/query/react';
 import {
   Article,
   UpdateDocPayload,
+  UpdateDocResponse,
   CreateDocPayload,
   DeleteDocPayload,
   CopyCutDocPayload,
@@ -32,7 +33,11 @@ export const docsApi = createApi({
       transformErrorResponse,
       transformResponse,
     }),
-    getDocSubItems: builder.query<DocTreeNode[], { folderDocPath?: string; homeRootDir?: boolean } | void>({
+    getDocSubItems: builder.query<
+      DocTreeNode[],
+      // withMetadata adds the size, times, counts and git status of each item
+      { folderDocPath?: string; homeRootDir?: boolean; withMetadata?: boolean } | void
+    >({
       query: (params = {}) => ({
         url: '/docs/sub-items',
         method: 'GET',
@@ -121,7 +126,7 @@ export const docsApi = createApi({
     /**
      * update the content of a single doc
      */
-    updateDoc: builder.mutation<unknown, UpdateDocPayload>({
+    updateDoc: builder.mutation<UpdateDocResponse, UpdateDocPayload>({
       query: (updateDoc) => ({
         url: '/docs/update',
         method: 'PATCH',
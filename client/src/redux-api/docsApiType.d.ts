@@ -1,25 +1,66 @@
+import { Heading } from '@/redux-feature/curDocSlice';
+
+import type { StatusType } from './git';
+
 export interface DocTreeNode {
   id: string;
   name: string;
   isFile: boolean;
   path: string[];
+  frontMatter: Record<string, unknown> | null;
+  /** size in bytes */
+  size?: number | null;
+  /** milliseconds since the Unix epoch */
+  modifiedAt?: number | null;
+  createdAt?: number | null;
+  /** words of a markdown file */
+  wordCount?: number | null;
+  /** listed items of a folder */
+  childCount?: number | null;
+  /** uncommitted change, MODIFIED for a folder with mixed changes */
+  gitStatus?: StatusType | null;
 }
 
 export interface Article {
   content: string;
   filePath: string;
-  headings: string[];
+  headings: Heading[];
   keywords: string[];
+  frontMatter: Record<string, unknown> | null;
+  /** content version, send it back when saving to detect conflicting edits */
+  version: string;
+}
+
+export interface UpdateFrontMatterPayload {
+  filePath: string;
+  fields: Record<string, unknown>;
 }
 
 export interface UpdateDocPayload {
   filePath: string;
   content: string;
+  version?: string;
+}
+
+export interface UpdateDocResponse {
+  version: string;
+}
+
+/** error data of a 409 response when the doc changed on disk since it was loaded */
+export interface ArticleConflict {
+  filePath: string;
+  expectedVersion: string;
+  content: string | null;
+  version: string | null;
 }
 
 export interface CreateDocPayload {
   filePath: string;
   isFile: boolean;
+  /** template name relative to the templates folder */
+  template?: string;
+  /** values of the template placeholders */
+  variables?: Record<string, string>;
 }
 
 export type DeleteDocPayload = CreateDocPayload[];
@@ -29,14 +70,200 @@ export type CopyCutDocPayload = {
   pastePath: string;
   isCopy: boolean;
   isFile: boolean;
+  updateLinks?: boolean;
 }[];
 
 export interface ModifyDocNamePayload {
   filePath: string;
   name: string;
   isFile: boolean;
+  /** rewrite links to the renamed doc in other docs */
+  updateLinks?: boolean;
+}
+
+export interface LinkChange {
+  line: number;
+  from: string;
+  to: string;
+}
+
+/** links rewritten (or to be rewritten, for a preview) in a doc when renaming/moving docs */
+export interface LinkUpdate {
+  filePath: string;
+  changes: LinkChange[];
+}
+
+export interface PreviewLinkUpdatesPayload {
+  fromPath: string;
+  toPath: string;
+  isFile: boolean;
 }
 
 export interface CheckServerRes {
   version: string;
 }
+
+/** a doc linking to the current doc, with the line containing the link */
+export interface Backlink {
+  filePath: string;
+  name: string;
+  line: number;
+  context: string;
+}
+
+export type WikiLinkStatus = 'RESOLVED' | 'AMBIGUOUS' | 'UNRESOLVED';
+
+/** a [[Note#Heading|alias]] link resolved against the workspace docs */
+export interface WikiLinkResolution {
+  status: WikiLinkStatus;
+  filePath: string | null;
+  headingId: string | null;
+  /** matching docs when the link is ambiguous */
+  candidates: string[];
+}
+
+export interface WikiLinkReport extends WikiLinkResolution {
+  raw: string;
+  target: string;
+  heading: string | null;
+  alias: string | null;
+  embed: boolean;
+  line: number;
+}
+
+/** a template of the workspace templates folder */
+export interface DocTemplate {
+  name: string;
+  filePath: string;
+  /** custom placeholders to ask values for */
+  variables: string[];
+}
+
+/** a daily note, date is YYYY-MM-DD */
+export interface JournalEntry {
+  date: string;
+  filePath: string;
+  name: string;
+}
+
+export interface OpenJournalResponse extends JournalEntry {
+  created: boolean;
+}
+
+/** a version of the local doc history, createdAt is a unix timestamp in milliseconds */
+export interface HistoryVersion {
+  id: string;
+  createdAt: number;
+  version: string;
+}
+
+export interface HistoryVersionContent extends HistoryVersion {
+  content: string;
+}
+
+export type FsChangeKind = 'create' | 'modify' | 'rename' | 'delete';
+
+/** data of a `change` event of /api/watch/events, paths are relative to the workspace */
+export interface FsChangeEvent {
+  kind: FsChangeKind;
+  path: string;
+  fromPath: string | null;
+}
+
+/** a selection in the shared text of /api/collab/ws, as UTF-16 offsets */
+export interface CollabCursor {
+  anchor: number;
+  head: number;
+}
+
+export interface CollabPeer {
+  peerId: number;
+  name: string;
+  cursor: CollabCursor | null;
+}
+
+/** text frames of /api/collab/ws, binary frames are automerge sync messages */
+export type CollabClientMessage = { type: 'cursor'; cursor: CollabCursor | null };
+
+export type CollabServerMessage =
+  | { type: 'welcome'; peerId: number }
+  | { type: 'peers'; peers: CollabPeer[] }
+  | { type: 'saved'; version: string }
+  | { type: 'error'; message: string }
+  /** the workspace was switched, the session is over */
+  | { type: 'closed' };
+
+export type BatchOperation =
+  | { op: 'create'; filePath: string; isFile: boolean }
+  | { op: 'move'; fromPath: string; toPath: string; isFile: boolean; updateLinks?: boolean }
+  | { op: 'copy'; fromPath: string; toPath: string; isFile: boolean }
+  | { op: 'rename'; filePath: string; name: string; isFile: boolean; updateLinks?: boolean }
+  | { op: 'delete'; filePath: string; isFile: boolean };
+
+export interface BatchPayload {
+  operations: BatchOperation[];
+  /** stop at the first failure and undo the operations done so far */
+  atomic?: boolean;
+}
+
+export type BatchItemStatus = 'done' | 'failed' | 'rolledBack' | 'skipped';
+
+export interface BatchItemResult {
+  status: BatchItemStatus;
+  error: string | null;
+  linkUpdates: LinkUpdate[];
+}
+
+export interface BatchResponse {
+  results: BatchItemResult[];
+  rolledBack: boolean;
+}
+
+/** manual order of a folder's items, used by the `manual` sort mode */
+export interface ReorderDocItemsPayload {
+  /** empty for the workspace root */
+  folderPath: string;
+  /** item names in order, an empty list removes the manual order */
+  order: string[];
+}
+
+export interface WorkspaceStatsQuery {
+  /** docs not modified for this many months are stale, 6 by default */
+  staleMonths?: number;
+  /** weeks of activity to report, 12 by default */
+  weeks?: number;
+}
+
+export interface DocSizeStat {
+  name: string;
+  path: string[];
+  size: number;
+  wordCount: number;
+}
+
+export interface WeekActivity {
+  /** Monday of the week, YYYY-MM-DD */
+  weekStart: string;
+  count: number;
+}
+
+export interface StaleDoc {
+  name: string;
+  path: string[];
+  /** milliseconds since the Unix epoch */
+  modifiedAt: number;
+}
+
+export interface WorkspaceStats {
+  docCount: number;
+  folderCount: number;
+  totalWords: number;
+  largestDocs: DocSizeStat[];
+  imageCount: number;
+  /** bytes of the _assets folder */
+  assetsSize: number;
+  /** oldest week first */
+  modifiedPerWeek: WeekActivity[];
+  /** least recently modified first */
+  staleDocs: StaleDoc[];
+}
@@ -4,6 +4,39 @@ import { transformErrorResponse, transformResponse } from './interceptor';
 export interface Settings {
   docRootPath?: string;
   ignoreDirs?: string[];
+  trashRetentionDays?: number;
+  /** doc file extensions without the dot, the first one is used for new docs */
+  docExtensions?: string[];
+  templatesDir?: string;
+  journalDir?: string;
+  /** strftime pattern of a daily note path inside journalDir, e.g. %Y/%m/%Y-%m-%d */
+  journalFilePattern?: string;
+  journalTemplate?: string;
+  /** versions kept per doc in the local history, 0 keeps all */
+  historyMaxVersions?: number;
+  /** days versions are kept in the local history, 0 keeps them forever */
+  historyRetentionDays?: number;
+  /** order of the sidebar items, folders always come first */
+  sortMode?: 'name' | 'modified' | 'created' | 'manual';
+}
+
+/** a registered workspace, stored in the editor settings */
+export interface Workspace {
+  name: string;
+  path: string;
+  /** milliseconds since the Unix epoch, null if never opened */
+  lastOpened: number | null;
+}
+
+export interface AddWorkspacePayload {
+  /** absolute or relative to the home folder */
+  path: string;
+  /** defaults to the folder name */
+  name?: string;
+}
+
+export interface WorkspacePathPayload {
+  path: string;
 }
 
 const settingsApi = docsApi.injectEndpoints({
@@ -1,4 +1,4 @@
-import { useEffect } from 'react';
+import { createElement, useEffect } from 'react';
 import { useSelector, useDispatch } from 'react-redux';
 
 import { useCurPath } from './docHooks';
@@ -12,10 +12,12 @@ import {
   updateLocationHash,
 } from '../utils';
 
-import type { RootState } from '@/store';
+import type { ArticleConflict } from '@/redux-api/docsApiType';
+import type { AppDispatch, RootState } from '@/store';
+import type { UnifyResponse } from '@/type';
 
 import { APP_VERSION } from '@/constants';
-import { useCheckServerQuery, useUpdateDocMutation } from '@/redux-api/docs';
+import { docsApi, useCheckServerQuery, useGetDocQuery, useUpdateDocMutation } from '@/redux-api/docs';
 import { useGetSettingsQuery } from '@/redux-api/settings';
 import { selectCurDoc, selectCurTabs, updateIsDirty, updateTabs } from '@/redux-feature/curDocSlice';
 import { clearDraft, clearDrafts, DraftsState, selectHasDraft, setDraft } from '@/redux-feature/draftsSlice';
@@ -32,24 +34,67 @@ import Toast from '@/utils/Toast';
 export const useSaveDoc = () => {
   const { isDirty, content, contentIdent, type } = useSelector(selectCurDoc);
   const { data: settings } = useGetSettingsQuery();
-  const dispatch = useDispatch();
+  const dispatch = useDispatch<AppDispatch>();
   const [updateDoc] = useUpdateDocMutation();
+  // the version the current edit is based on, the server rejects the save if the file changed since
+  const { data: savedDoc } = useGetDocQuery(contentIdent, { skip: type !== 'workspace' || !contentIdent });
+
+  const save = async (version?: string) => {
+    const res = await updateDoc({ filePath: contentIdent, content, version }).unwrap();
+    // the invalidation refetch may not be back before the next save
+    dispatch(
+      docsApi.util.updateQueryData('getDoc', contentIdent, (doc) => {
+        if (doc) doc.version = res.version;
+      }),
+    );
+    Toast('saved successfully!');
+    dispatch(updateIsDirty({ isDirty: false }));
+    dispatch(clearDraft(getDraftKey(settings?.docRootPath, contentIdent)));
+  };
 
   return async () => {
     if (!isDirty) return;
 
     try {
       if (type === 'workspace') {
-        await updateDoc({
-          filePath: contentIdent,
-          content,
-        }).unwrap();
-        Toast('saved successfully!');
-        dispatch(updateIsDirty({ isDirty: false }));
-        dispatch(clearDraft(getDraftKey(settings?.docRootPath, contentIdent)));
+        await save(savedDoc?.version);
       }
     } catch (err) {
-      Toast.error((err as Error).message);
+      const { data: conflict, message } = err as UnifyResponse<ArticleConflict | null>;
+      if (!conflict?.expectedVersion) {
+        Toast.error(message);
+        return;
+      }
+
+      const overwrite = await confirm({
+        header: 'Conflict',
+        acceptLabel: 'Overwrite',
+        rejectLabel: 'Keep editing',
+        message: createElement(
+          'div',
+          null,
+          createElement(
+            'p',
+            null,
+            conflict.content === null
+              ? 'This document was deleted outside the editor. Save your version again?'
+              : 'This document was changed outside the editor since it was loaded. Overwrite it with your version?',
+          ),
+          conflict.content !== null &&
+            createElement(
+              'pre',
+              { style: { maxHeight: '40vh', maxWidth: '60vw', overflow: 'auto', whiteSpace: 'pre-wrap' } },
+              conflict.content,
+            ),
+        ),
+      });
+      if (!overwrite) return;
+
+      try {
+        await save(conflict.version ?? undefined);
+      } catch (e) {
+        Toast.error((e as UnifyResponse).message);
+      }
     }
   };
 };
@@ -0,0 +1,90 @@
+use std::{fs, io, path::PathBuf, sync::Arc};
+
+use anyhow::{Context, Result};
+use server::{
+  services::{
+    doc::DocService, export::ExportService, history::HistoryService, img::ImgService,
+    search::SearchService, settings::Settings, settings::SettingsService, trash::TrashService,
+  },
+  utils::path_encoding::encode_path_string,
+};
+
+use crate::constants::default_editor_settings_file;
+
+/// Export a folder of the workspace as a static HTML site
+pub fn cmd_export_html(
+  folder: Option<String>,
+  out: PathBuf,
+  workspace: Option<PathBuf>,
+) -> Result<()> {
+  let export_service = export_service(workspace)?;
+  let folder = encode_path_string(folder.unwrap_or_default().trim_matches('/'));
+
+  let summary = export_service.export_html(&folder, &out)?;
+  println!(
+    "Exported {} docs and {} assets to {}",
+    summary.docs,
+    summary.assets,
+    summary.out_path.display()
+  );
+
+  Ok(())
+}
+
+/// Export a folder of the workspace as an EPUB book
+pub fn cmd_export_epub(
+  folder: Option<String>,
+  out: Option<PathBuf>,
+  workspace: Option<PathBuf>,
+) -> Result<()> {
+  let export_service = export_service(workspace)?;
+  let folder = encode_path_string(folder.unwrap_or_default().trim_matches('/'));
+
+  let mut book = export_service.export_epub(&folder)?;
+  // write into the current directory by default
+  let out = match out {
+    Some(out) if out.is_dir() => out.join(&book.file_name),
+    Some(out) => out,
+    None => PathBuf::from(&book.file_name),
+  };
+  fs::File::create(&out)
+    .and_then(|mut file| io::copy(&mut book.file, &mut file))
+    .with_context(|| format!("Failed to write {}", out.display()))?;
+  println!("Exported the book to {}", out.display());
+
+  Ok(())
+}
+
+/// Builds the export service for `workspace`, or the current workspace of the editor.
+/// Exports only read the settings, missing settings files are not created.
+fn export_service(workspace: Option<PathBuf>) -> Result<ExportService> {
+  let editor_settings_file = default_editor_settings_file();
+  let settings = match workspace {
+    Some(workspace) => {
+      let doc_root_path = fs::canonicalize(&workspace)
+        .with_context(|| format!("Workspace does not exist: {}", workspace.display()))?;
+      let workspace_settings = Settings::read_workspace_settings(&doc_root_path)?;
+      Settings::from_workspace_settings(doc_root_path, workspace_settings)
+    }
+    None => Settings::read_from_file(&editor_settings_file)?,
+  };
+  let settings_service = Arc::new(SettingsService::from_settings(
+    settings,
+    editor_settings_file,
+  ));
+
+  let trash_service = Arc::new(TrashService::new(settings_service.clone()));
+  let history_service = Arc::new(HistoryService::new(settings_service.clone()));
+  let search_service = Arc::new(SearchService::new(settings_service.clone()));
+  let doc_service = Arc::new(DocService::new(
+    settings_service.clone(),
+    trash_service,
+    history_service,
+  ));
+  let img_service = Arc::new(ImgService::new(settings_service.clone(), search_service));
+  Ok(ExportService::new(
+    settings_service,
+    doc_service,
+    img_service,
+  ))
+}
@@ -1,4 +1,4 @@
-#[cfg(target_os = "macos")]
+#[cfg(unix)]
 use std::io::Write;
 use std::path::Path;
 
@@ -7,7 +7,7 @@ use anyhow::{Context, Result};
 use crate::utils::get_real_executable_path;
 
 /// Add the binary's current directory to PATH so `mds` can be used as a CLI command.
-/// On macOS: creates a symlink at `~/.local/bin/mds` (user-writable, no sudo needed),
+/// On macOS/Linux: creates a symlink at `~/.local/bin/mds` (user-writable, no sudo needed),
 ///          and ensures `~/.local/bin` is in PATH. Falls back to adding `~/.local/bin`
 ///          to PATH if symlink creation fails.
 /// On Windows: adds the exe directory to the user's PATH registry entry.
@@ -17,7 +17,7 @@ pub fn add_to_path() -> Result<()> {
   add_to_path_inner(&exe_path)
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(unix)]
 fn add_to_path_inner(exe_path: &Path) -> Result<()> {
   let home = dirs::home_dir().context("Could not find home directory")?;
   let local_bin_dir = home.join(".local").join("bin");
@@ -52,7 +52,7 @@ fn add_to_path_inner(exe_path: &Path) -> Result<()> {
 
 /// Try to create or update a symlink, handling existing symlinks gracefully.
 /// Returns Ok(()) on success, Err on failure (including permission errors).
-#[cfg(target_os = "macos")]
+#[cfg(unix)]
 fn try_create_symlink(exe_path: &Path, symlink_path: &Path) -> Result<()> {
   if symlink_path.exists() || symlink_path.is_symlink() {
     // Check if existing symlink points to the correct target
@@ -88,12 +88,12 @@ fn try_create_symlink(exe_path: &Path, symlink_path: &Path) -> Result<()> {
 }
 
 /// Ensure ~/.local/bin is in PATH by adding it to shell config files.
-#[cfg(target_os = "macos")]
+#[cfg(unix)]
 fn ensure_local_bin_in_path(local_bin_dir: &Path) -> Result<()> {
   let home = dirs::home_dir().context("Could not find home directory")?;
-  let export_line = format!(
+  let export_line =
     "\n# Add ~/.local/bin to PATH if not already present\nexport PATH=\"$HOME/.local/bin:$PATH\"\n"
-  );
+      .to_string();
 
   let zshrc = home.join(".zshrc");
   add_export_to_file(&zshrc, &export_line)?;
@@ -109,7 +109,7 @@ fn ensure_local_bin_in_path(local_bin_dir: &Path) -> Result<()> {
   Ok(())
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(unix)]
 fn add_export_to_file(file_path: &Path, export_line: &str) -> Result<()> {
   let content = std::fs::read_to_string(file_path).unwrap_or_default();
 
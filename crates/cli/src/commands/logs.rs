@@ -38,7 +38,7 @@ pub fn cmd_logs_view(tail: usize, follow: bool) -> Result<()> {
   let reader = BufReader::new(file);
 
   // Read all lines and show the last N
-  let lines: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
+  let lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
   let start = lines.len().saturating_sub(tail);
 
   for line in &lines[start..] {
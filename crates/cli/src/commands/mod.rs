@@ -1,3 +1,4 @@
+mod export;
 mod install;
 mod location;
 mod logs;
@@ -5,6 +6,7 @@ mod start;
 mod status;
 mod stop;
 
+pub use export::{cmd_export_epub, cmd_export_html};
 pub use install::add_to_path;
 pub use location::cmd_location;
 pub use logs::{cmd_logs_clear, cmd_logs_view};
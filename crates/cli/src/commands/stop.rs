@@ -48,7 +48,7 @@ pub fn cmd_stop() -> Result<()> {
   let pid = match read_pid_file(&pid_file) {
     Some(p) => p,
     None => {
-      #[cfg(target_os = "macos")]
+      #[cfg(unix)]
       {
         println!("No PID file found. Server may not be running.");
         return Ok(());
@@ -3,13 +3,17 @@ mod commands;
 mod constants;
 mod utils;
 
-use std::net::{TcpListener, TcpStream};
+use std::{
+  net::{TcpListener, TcpStream},
+  path::PathBuf,
+};
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
 use commands::{
-  add_to_path, cmd_location, cmd_logs_clear, cmd_logs_view, cmd_start, cmd_status, cmd_stop,
+  add_to_path, cmd_export_epub, cmd_export_html, cmd_location, cmd_logs_clear, cmd_logs_view,
+  cmd_start, cmd_status, cmd_stop,
 };
 use constants::{DEFAULT_HOST, DEFAULT_PORT};
 
@@ -151,6 +155,43 @@ enum Commands {
     #[arg(long, short)]
     follow: bool,
   },
+
+  /// Export docs to other formats
+  Export {
+    #[command(subcommand)]
+    cmd: ExportCmd,
+  },
+}
+
+#[derive(Subcommand)]
+enum ExportCmd {
+  /// Render the docs of a folder to a static HTML site
+  Html {
+    /// Folder of the workspace to export, e.g. `guides/setup` (the whole workspace by default)
+    folder: Option<String>,
+
+    /// Directory to write the site to
+    #[arg(long, short)]
+    out: PathBuf,
+
+    /// Workspace to export from (the current workspace of the editor by default)
+    #[arg(long, short)]
+    workspace: Option<PathBuf>,
+  },
+
+  /// Turn the docs of a folder into an EPUB book
+  Epub {
+    /// Folder of the workspace to export, e.g. `guides/setup` (the whole workspace by default)
+    folder: Option<String>,
+
+    /// File or directory to write the book to (the current directory by default)
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+
+    /// Workspace to export from (the current workspace of the editor by default)
+    #[arg(long, short)]
+    workspace: Option<PathBuf>,
+  },
 }
 
 #[derive(Subcommand)]
@@ -203,15 +244,15 @@ fn main() -> Result<()> {
       let is_matched_server = check_server()?;
       if is_matched_server {
         let pid_file = default_pid_file();
-        if let Some(pid) = read_pid_file(&pid_file) {
-          if is_process_running(pid) {
-            println!("Server is already running with PID {}", pid);
-            let url = format!("http://{}:{}/", DEFAULT_HOST, DEFAULT_PORT);
-            if open::that(&url).is_err() {
-              println!("Open {} in your browser", url);
-            }
-            return Ok(());
+        if let Some(pid) = read_pid_file(&pid_file)
+          && is_process_running(pid)
+        {
+          println!("Server is already running with PID {}", pid);
+          let url = format!("http://{}:{}/", DEFAULT_HOST, DEFAULT_PORT);
+          if open::that(&url).is_err() {
+            println!("Open {} in your browser", url);
           }
+          return Ok(());
         }
       }
 
@@ -269,6 +310,22 @@ fn main() -> Result<()> {
         cmd_logs_view(tail, follow)?;
       }
     },
+    Some(Commands::Export { cmd }) => match cmd {
+      ExportCmd::Html {
+        folder,
+        out,
+        workspace,
+      } => {
+        cmd_export_html(folder, out, workspace)?;
+      }
+      ExportCmd::Epub {
+        folder,
+        out,
+        workspace,
+      } => {
+        cmd_export_epub(folder, out, workspace)?;
+      }
+    },
   }
 
   Ok(())
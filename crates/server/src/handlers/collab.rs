@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use automerge::sync;
+use axum::{
+  extract::{
+    Query, State, WebSocketUpgrade,
+    ws::{Message, WebSocket},
+  },
+  response::Response,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+  responses::app::{ApiRes, AppError},
+  services::collab::{
+    CollabClientMessage, CollabPeer, CollabQuery, CollabRoom, CollabServerMessage,
+    GetCollabPeersQuery, RoomEvent,
+  },
+  state::app::AppState,
+  utils::path_encoding::encode_path_string,
+};
+
+/// Joins the collaborative session of a doc. Binary frames carry automerge sync messages
+/// both ways, text frames carry [`CollabClientMessage`] / [`CollabServerMessage`] JSON.
+pub async fn collab_handler(
+  ws: WebSocketUpgrade,
+  State(state): State<AppState>,
+  Query(params): Query<CollabQuery>,
+) -> Result<Response, AppError> {
+  let normalized_path = encode_path_string(&params.file_path);
+  tracing::info!("[CollabHandler] collab: {}", normalized_path);
+  if !state
+    .services
+    .doc_service
+    .doc_exists(&normalized_path, true)?
+  {
+    return Err(anyhow::anyhow!("The file {} does not exist.", normalized_path).into());
+  }
+
+  let name = params.name.unwrap_or_else(|| "Anonymous".to_string());
+  Ok(ws.on_upgrade(move |socket| collab_session(socket, state, normalized_path, name)))
+}
+
+pub async fn get_collab_peers_handler(
+  State(state): State<AppState>,
+  Query(params): Query<GetCollabPeersQuery>,
+) -> Result<ApiRes<Vec<CollabPeer>>, AppError> {
+  let normalized_path = encode_path_string(&params.file_path);
+  tracing::info!("[CollabHandler] getCollabPeers: {}", normalized_path);
+  Ok(ApiRes::success(
+    state.services.collab_service.peers(&normalized_path),
+  ))
+}
+
+async fn collab_session(mut socket: WebSocket, state: AppState, file_path: String, name: String) {
+  let collab_service = state.services.collab_service.clone();
+  let (room, peer_id) = match collab_service.join(&file_path, &name) {
+    Ok(joined) => joined,
+    Err(e) => {
+      let message = CollabServerMessage::Error {
+        message: e.to_string(),
+      };
+      let _ = send_json(&mut socket, &message).await;
+      return;
+    }
+  };
+  let mut events = room.subscribe();
+  let mut sync_state = sync::State::new();
+
+  let mut connected = send_json(&mut socket, &CollabServerMessage::Welcome { peer_id }).await
+    && send_peers(&mut socket, &room).await
+    && send_sync(&mut socket, &room, &mut sync_state).await;
+
+  while connected {
+    connected = tokio::select! {
+      message = socket.recv() => match message {
+        Some(Ok(Message::Binary(bytes))) => {
+          match room.receive_sync_message(&mut sync_state, &bytes) {
+            Ok(()) => send_sync(&mut socket, &room, &mut sync_state).await,
+            Err(e) => send_error(&mut socket, e).await,
+          }
+        }
+        Some(Ok(Message::Text(text))) => {
+          match serde_json::from_str::<CollabClientMessage>(&text) {
+            Ok(CollabClientMessage::Cursor { cursor }) => match room.set_cursor(peer_id, cursor) {
+              Ok(()) => true,
+              Err(e) => send_error(&mut socket, e).await,
+            },
+            Err(e) => send_error(&mut socket, e.into()).await,
+          }
+        }
+        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => false,
+        // pings are answered by axum
+        Some(Ok(_)) => true,
+      },
+      event = events.recv() => match event {
+        Ok(RoomEvent::DocChanged) => send_sync(&mut socket, &room, &mut sync_state).await,
+        Ok(RoomEvent::PeersChanged) => send_peers(&mut socket, &room).await,
+        Ok(RoomEvent::Saved(version)) => {
+          send_json(&mut socket, &CollabServerMessage::Saved { version }).await
+        }
+        Ok(RoomEvent::Closed) => {
+          send_json(&mut socket, &CollabServerMessage::Closed).await;
+          false
+        }
+        // missed events, catch up on everything
+        Err(RecvError::Lagged(_)) => {
+          send_sync(&mut socket, &room, &mut sync_state).await
+            && send_peers(&mut socket, &room).await
+        }
+        Err(RecvError::Closed) => false,
+      },
+    };
+  }
+
+  // the last peer leaving saves the doc
+  let _ = tokio::task::spawn_blocking(move || collab_service.leave(&room, peer_id)).await;
+}
+
+/// Each send returns whether the socket is still usable.
+async fn send_json(socket: &mut WebSocket, message: &CollabServerMessage) -> bool {
+  match serde_json::to_string(message) {
+    Ok(text) => socket.send(Message::Text(text.into())).await.is_ok(),
+    Err(_) => false,
+  }
+}
+
+async fn send_sync(
+  socket: &mut WebSocket,
+  room: &Arc<CollabRoom>,
+  sync_state: &mut sync::State,
+) -> bool {
+  match room.generate_sync_message(sync_state) {
+    Some(message) => socket.send(Message::Binary(message.into())).await.is_ok(),
+    None => true,
+  }
+}
+
+async fn send_peers(socket: &mut WebSocket, room: &Arc<CollabRoom>) -> bool {
+  let peers = room.peers();
+  send_json(socket, &CollabServerMessage::Peers { peers }).await
+}
+
+async fn send_error(socket: &mut WebSocket, e: anyhow::Error) -> bool {
+  let message = CollabServerMessage::Error {
+    message: e.to_string(),
+  };
+  send_json(socket, &message).await
+}
@@ -1,10 +1,17 @@
-use axum::extract::{Query, State};
+use axum::{
+  extract::{Query, State},
+  http::{HeaderMap, header},
+};
 
 use crate::{
   responses::app::{ApiRes, AppError, AppJson},
   services::doc::{
-    CopyCutDocRequest, CreateDocRequest, CreateFolderRequest, DeleteDocRequest, DocService,
-    GetArticleQuery, UpdateArticleRequest, UpdateDocNameRequest, structs::GetDocSubTreeQueryPatch,
+    Backlink, BatchOperation, BatchRequest, BatchResponse, CopyCutDocRequest, CreateDocRequest,
+    CreateFolderRequest, DeleteDocRequest, DocService, DocTemplate, GetArticleQuery,
+    GetBacklinksQuery, GetDocTreeQuery, GetWikiLinksQuery, LinkUpdate, NormalizedDocMap,
+    PreviewLinkUpdatesRequest, ReorderDocItemsRequest, UpdateArticleRequest, UpdateArticleResponse,
+    UpdateDocNameRequest, UpdateFrontMatterRequest, WikiLinkReport, WikiLinkResolveQuery,
+    structs::GetDocSubTreeQueryPatch, wiki_links::WikiLinkResolution,
   },
   state::app::AppState,
   utils::path_encoding::encode_path_string,
@@ -16,14 +23,26 @@ pub async fn get_sub_doc_items_handler(
 ) -> Result<ApiRes<Vec<crate::services::doc::DocItem>>, AppError> {
   let folder_doc_path = params.folder_doc_path.unwrap_or_default();
   let home_root_dir = params.home_root_dir.unwrap_or(false);
+  let with_metadata = params.with_metadata.unwrap_or(false);
   tracing::info!("[DocHandler] getDocSubTree. {}.", folder_doc_path);
-  let doc_items = state
-    .services
-    .doc_service
-    .get_sub_doc_items(&folder_doc_path, home_root_dir)?;
+  let doc_items =
+    state
+      .services
+      .doc_service
+      .get_sub_doc_items(&folder_doc_path, home_root_dir, with_metadata)?;
   Ok(ApiRes::success(doc_items))
 }
 
+pub async fn get_doc_tree_handler(
+  State(state): State<AppState>,
+  Query(params): Query<GetDocTreeQuery>,
+) -> Result<ApiRes<NormalizedDocMap>, AppError> {
+  let force = params.force.unwrap_or(false);
+  tracing::info!("[DocHandler] getDocTree. force: {}.", force);
+  let doc_tree = state.services.doc_service.get_doc_tree(force)?;
+  Ok(ApiRes::success(doc_tree))
+}
+
 pub async fn get_article_handler(
   State(state): State<AppState>,
   Query(params): Query<GetArticleQuery>,
@@ -39,6 +58,43 @@ pub async fn get_article_handler(
   Ok(ApiRes::success(article))
 }
 
+pub async fn get_backlinks_handler(
+  State(state): State<AppState>,
+  Query(params): Query<GetBacklinksQuery>,
+) -> Result<ApiRes<Vec<Backlink>>, AppError> {
+  // Normalize the path to ensure it matches the format used in nor_docs
+  let normalized_path = encode_path_string(&params.file_path);
+  tracing::info!(
+    "[DocHandler] getBacklinks: {} (normalized: {})",
+    params.file_path,
+    normalized_path
+  );
+  let backlinks = state.services.doc_service.get_backlinks(&normalized_path)?;
+  Ok(ApiRes::success(backlinks))
+}
+
+pub async fn get_wiki_links_handler(
+  State(state): State<AppState>,
+  Query(params): Query<GetWikiLinksQuery>,
+) -> Result<ApiRes<Vec<WikiLinkReport>>, AppError> {
+  let normalized_path = encode_path_string(&params.file_path);
+  tracing::info!("[DocHandler] getWikiLinks: {}", normalized_path);
+  let wiki_links = state
+    .services
+    .doc_service
+    .get_wiki_links(&normalized_path)?;
+  Ok(ApiRes::success(wiki_links))
+}
+
+pub async fn resolve_wiki_link_handler(
+  State(state): State<AppState>,
+  Query(params): Query<WikiLinkResolveQuery>,
+) -> Result<ApiRes<WikiLinkResolution>, AppError> {
+  tracing::info!("[DocHandler] resolveWikiLink: {}", params.link);
+  let resolution = state.services.doc_service.resolve_wiki_link(&params.link)?;
+  Ok(ApiRes::success(resolution))
+}
+
 pub async fn create_doc_handler(
   State(state): State<AppState>,
   AppJson(request): AppJson<CreateDocRequest>,
@@ -51,13 +107,28 @@ pub async fn create_doc_handler(
     request.file_path,
     normalized_path
   );
-  let doc = state
-    .services
-    .doc_service
-    .create_doc(&normalized_path, request.is_file)?;
+  let doc = match request.template.as_deref() {
+    Some(template) if request.is_file => state.services.doc_service.create_doc_from_template(
+      &normalized_path,
+      template,
+      &request.variables,
+    )?,
+    _ => state
+      .services
+      .doc_service
+      .create_doc(&normalized_path, request.is_file)?,
+  };
   Ok(ApiRes::success(doc))
 }
 
+pub async fn list_templates_handler(
+  State(state): State<AppState>,
+) -> Result<ApiRes<Vec<DocTemplate>>, AppError> {
+  tracing::info!("[DocHandler] listTemplates.");
+  let templates = state.services.doc_service.list_templates()?;
+  Ok(ApiRes::success(templates))
+}
+
 pub async fn create_folder_handler(
   AppJson(request): AppJson<CreateFolderRequest>,
 ) -> Result<ApiRes<()>, AppError> {
@@ -68,26 +139,61 @@ pub async fn create_folder_handler(
 
 pub async fn update_article_handler(
   State(state): State<AppState>,
+  headers: HeaderMap,
   AppJson(request): AppJson<UpdateArticleRequest>,
-) -> Result<ApiRes<()>, AppError> {
+) -> Result<ApiRes<UpdateArticleResponse>, AppError> {
   // Normalize the path to ensure it matches the format used in nor_docs
   let normalized_path = encode_path_string(&request.file_path);
+  // accept the version as an `If-Match` entity tag as well, e.g. `"<version>"`
+  let if_match = headers
+    .get(header::IF_MATCH)
+    .and_then(|value| value.to_str().ok())
+    .map(|value| {
+      value
+        .trim()
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .to_string()
+    })
+    .filter(|value| !value.is_empty() && value != "*");
+  let expected_version = if_match.or(request.version);
   tracing::info!(
-    "[DocHandler] updateArticle: {} (normalized: {})",
+    "[DocHandler] updateArticle: {} (normalized: {}), version: {:?}",
     request.file_path,
-    normalized_path
+    normalized_path,
+    expected_version
+  );
+  let version = state.services.doc_service.update_article_if_match(
+    &normalized_path,
+    &request.content,
+    expected_version.as_deref(),
+  )?;
+  Ok(ApiRes::success(UpdateArticleResponse { version }))
+}
+
+pub async fn update_front_matter_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<UpdateFrontMatterRequest>,
+) -> Result<ApiRes<crate::services::doc::Article>, AppError> {
+  // Normalize the path to ensure it matches the format used in nor_docs
+  let normalized_path = encode_path_string(&request.file_path);
+  tracing::info!(
+    "[DocHandler] updateFrontMatter: {} (normalized: {}), fields: {:?}",
+    request.file_path,
+    normalized_path,
+    request.fields.keys().collect::<Vec<_>>()
   );
-  state
+  let article = state
     .services
     .doc_service
-    .update_article(&normalized_path, &request.content)?;
-  Ok(ApiRes::success(()))
+    .update_front_matter(&normalized_path, &request.fields)?;
+  Ok(ApiRes::success(article))
 }
 
 pub async fn update_doc_name_handler(
   State(state): State<AppState>,
   AppJson(request): AppJson<UpdateDocNameRequest>,
-) -> Result<ApiRes<()>, AppError> {
+) -> Result<ApiRes<Vec<LinkUpdate>>, AppError> {
   // Normalize the path to ensure it matches the format used in nor_docs
   let normalized_path = encode_path_string(&request.file_path);
   tracing::info!(
@@ -96,17 +202,20 @@ pub async fn update_doc_name_handler(
     request.file_path,
     normalized_path
   );
-  state
-    .services
-    .doc_service
-    .modify_name(&normalized_path, &request.name, request.is_file)?;
-  Ok(ApiRes::success(()))
+  let link_updates = state.services.doc_service.modify_name(
+    &normalized_path,
+    &request.name,
+    request.is_file,
+    request.update_links,
+  )?;
+  Ok(ApiRes::success(link_updates))
 }
 
 pub async fn copy_cut_doc_handler(
   State(state): State<AppState>,
   AppJson(requests): AppJson<Vec<CopyCutDocRequest>>,
-) -> Result<ApiRes<()>, AppError> {
+) -> Result<ApiRes<Vec<LinkUpdate>>, AppError> {
+  let mut link_updates = Vec::new();
   for request in requests {
     // Normalize the paths to ensure they match the format used in nor_docs
     let normalized_copy_cut_path = encode_path_string(&request.copy_cut_path);
@@ -120,14 +229,37 @@ pub async fn copy_cut_doc_handler(
       normalized_copy_cut_path,
       normalized_paste_path
     );
-    state.services.doc_service.copy_cut_doc(
+    link_updates.extend(state.services.doc_service.copy_cut_doc(
       &normalized_copy_cut_path,
       &normalized_paste_path,
       request.is_copy,
       request.is_file,
-    )?;
+      request.update_links,
+    )?);
   }
-  Ok(ApiRes::success(()))
+  Ok(ApiRes::success(link_updates))
+}
+
+pub async fn preview_link_updates_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<PreviewLinkUpdatesRequest>,
+) -> Result<ApiRes<Vec<LinkUpdate>>, AppError> {
+  // Normalize the paths to ensure they match the format used in nor_docs
+  let normalized_from_path = encode_path_string(&request.from_path);
+  let normalized_to_path = encode_path_string(&request.to_path);
+  tracing::info!(
+    "[DocHandler] previewLinkUpdates: {} -> {} (normalized: {} -> {})",
+    request.from_path,
+    request.to_path,
+    normalized_from_path,
+    normalized_to_path
+  );
+  let link_updates = state.services.doc_service.preview_link_updates(
+    &normalized_from_path,
+    &normalized_to_path,
+    request.is_file,
+  )?;
+  Ok(ApiRes::success(link_updates))
 }
 
 pub async fn delete_doc_handler(
@@ -150,3 +282,75 @@ pub async fn delete_doc_handler(
   }
   Ok(ApiRes::success(()))
 }
+
+pub async fn batch_docs_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<BatchRequest>,
+) -> Result<ApiRes<BatchResponse>, AppError> {
+  tracing::info!(
+    "[DocHandler] batch: {} operation(s), atomic: {}",
+    request.operations.len(),
+    request.atomic
+  );
+  // Normalize the paths to ensure they match the format used in nor_docs
+  let operations = request
+    .operations
+    .into_iter()
+    .map(|operation| match operation {
+      BatchOperation::Create { file_path, is_file } => BatchOperation::Create {
+        file_path: encode_path_string(&file_path),
+        is_file,
+      },
+      BatchOperation::Move {
+        from_path,
+        to_path,
+        is_file,
+        update_links,
+      } => BatchOperation::Move {
+        from_path: encode_path_string(&from_path),
+        to_path: encode_path_string(&to_path),
+        is_file,
+        update_links,
+      },
+      BatchOperation::Copy {
+        from_path,
+        to_path,
+        is_file,
+      } => BatchOperation::Copy {
+        from_path: encode_path_string(&from_path),
+        to_path: encode_path_string(&to_path),
+        is_file,
+      },
+      BatchOperation::Rename {
+        file_path,
+        name,
+        is_file,
+        update_links,
+      } => BatchOperation::Rename {
+        file_path: encode_path_string(&file_path),
+        name,
+        is_file,
+        update_links,
+      },
+      BatchOperation::Delete { file_path, is_file } => BatchOperation::Delete {
+        file_path: encode_path_string(&file_path),
+        is_file,
+      },
+    })
+    .collect();
+  let response = state.services.doc_service.batch(operations, request.atomic);
+  Ok(ApiRes::success(response))
+}
+
+pub async fn reorder_doc_items_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<ReorderDocItemsRequest>,
+) -> Result<ApiRes<Vec<crate::services::doc::DocItem>>, AppError> {
+  let normalized_path = encode_path_string(&request.folder_path);
+  tracing::info!("[DocHandler] reorderDocItems: {}", normalized_path);
+  let doc_items = state
+    .services
+    .doc_service
+    .reorder_doc_items(&normalized_path, request.order)?;
+  Ok(ApiRes::success(doc_items))
+}
@@ -0,0 +1,96 @@
+use axum::{
+  body::Body,
+  extract::{Query, State},
+  http::{HeaderValue, Response, StatusCode, header},
+};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use serde::Deserialize;
+use tokio_util::io::ReaderStream;
+
+use crate::{
+  responses::app::AppError, services::export::ExportFile, state::app::AppState,
+  utils::path_encoding::encode_path_string,
+};
+
+/// Characters encoded in the `filename*` of a download, all but the RFC 5987 `attr-char`s.
+const FILENAME_ENCODE: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'!')
+  .remove(b'#')
+  .remove(b'$')
+  .remove(b'&')
+  .remove(b'+')
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'^')
+  .remove(b'_')
+  .remove(b'`')
+  .remove(b'|')
+  .remove(b'~');
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportEpubQuery {
+  /// Folder to export, the whole workspace when empty.
+  #[serde(default)]
+  pub folder_path: String,
+}
+
+pub async fn export_epub_handler(
+  State(state): State<AppState>,
+  Query(params): Query<ExportEpubQuery>,
+) -> Result<Response<Body>, AppError> {
+  let normalized_path = encode_path_string(&params.folder_path);
+  tracing::info!("[ExportHandler] exportEpub: {}", normalized_path);
+
+  let export_service = state.services.export_service.clone();
+  let file = tokio::task::spawn_blocking(move || export_service.export_epub(&normalized_path))
+    .await
+    .map_err(anyhow::Error::from)??;
+  Ok(download_response(file)?)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDocxQuery {
+  pub file_path: String,
+}
+
+pub async fn export_docx_handler(
+  State(state): State<AppState>,
+  Query(params): Query<ExportDocxQuery>,
+) -> Result<Response<Body>, AppError> {
+  let normalized_path = encode_path_string(&params.file_path);
+  tracing::info!("[ExportHandler] exportDocx: {}", normalized_path);
+
+  let export_service = state.services.export_service.clone();
+  let file = tokio::task::spawn_blocking(move || export_service.export_docx(&normalized_path))
+    .await
+    .map_err(anyhow::Error::from)??;
+  Ok(download_response(file)?)
+}
+
+/// Streams an exported file as an attachment.
+fn download_response(file: ExportFile) -> Result<Response<Body>, anyhow::Error> {
+  let size = file.file.metadata()?.len();
+  // the name may not be ascii
+  let disposition = format!(
+    "attachment; filename*=UTF-8''{}",
+    utf8_percent_encode(&file.file_name, FILENAME_ENCODE)
+  );
+
+  let response = Response::builder()
+    .status(StatusCode::OK)
+    .header(
+      header::CONTENT_TYPE,
+      HeaderValue::from_str(&file.mime).unwrap(),
+    )
+    .header(
+      header::CONTENT_DISPOSITION,
+      HeaderValue::from_str(&disposition).unwrap(),
+    )
+    .header(header::CONTENT_LENGTH, size)
+    .body(Body::from_stream(ReaderStream::new(
+      tokio::fs::File::from_std(file.file),
+    )))?;
+  Ok(response)
+}
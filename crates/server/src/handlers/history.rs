@@ -0,0 +1,58 @@
+use axum::extract::{Query, State};
+
+use crate::{
+  responses::app::{ApiRes, AppError, AppJson},
+  services::{
+    doc::UpdateArticleResponse,
+    history::{
+      GetHistoryVersionQuery, HistoryVersion, HistoryVersionContent, ListHistoryQuery,
+      RestoreHistoryRequest,
+    },
+  },
+  state::app::AppState,
+  utils::path_encoding::encode_path_string,
+};
+
+pub async fn list_history_handler(
+  State(state): State<AppState>,
+  Query(params): Query<ListHistoryQuery>,
+) -> Result<ApiRes<Vec<HistoryVersion>>, AppError> {
+  let normalized_path = encode_path_string(&params.file_path);
+  tracing::info!("[HistoryHandler] listHistory: {}", normalized_path);
+  let versions = state.services.doc_service.list_versions(&normalized_path)?;
+  Ok(ApiRes::success(versions))
+}
+
+pub async fn get_history_version_handler(
+  State(state): State<AppState>,
+  Query(params): Query<GetHistoryVersionQuery>,
+) -> Result<ApiRes<HistoryVersionContent>, AppError> {
+  let normalized_path = encode_path_string(&params.file_path);
+  tracing::info!(
+    "[HistoryHandler] getHistoryVersion: {}, {}",
+    normalized_path,
+    params.id
+  );
+  let version = state
+    .services
+    .doc_service
+    .get_version(&normalized_path, &params.id)?;
+  Ok(ApiRes::success(version))
+}
+
+pub async fn restore_history_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<RestoreHistoryRequest>,
+) -> Result<ApiRes<UpdateArticleResponse>, AppError> {
+  let normalized_path = encode_path_string(&request.file_path);
+  tracing::info!(
+    "[HistoryHandler] restoreHistory: {}, {}",
+    normalized_path,
+    request.id
+  );
+  let version = state
+    .services
+    .doc_service
+    .restore_version(&normalized_path, &request.id)?;
+  Ok(ApiRes::success(UpdateArticleResponse { version }))
+}
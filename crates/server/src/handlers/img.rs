@@ -75,7 +75,7 @@ pub async fn upload_image_handler(
   State(state): State<AppState>,
   mut multipart: Multipart,
 ) -> Result<ApiRes<String>, AppError> {
-  while let Some(field) = multipart
+  if let Some(field) = multipart
     .next_field()
     .await
     .map_err(|e| anyhow::anyhow!("Failed to read multipart field: {}", e))?
@@ -0,0 +1,39 @@
+use axum::extract::{Query, State};
+
+use crate::{
+  responses::app::{ApiRes, AppError, AppJson},
+  services::journal::{JournalEntry, ListJournalQuery, OpenJournalRequest, OpenJournalResponse},
+  state::app::AppState,
+};
+
+pub async fn open_journal_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<OpenJournalRequest>,
+) -> Result<ApiRes<OpenJournalResponse>, AppError> {
+  tracing::info!(
+    "[JournalHandler] openJournal: {:?}, template: {:?}",
+    request.date,
+    request.template
+  );
+  let note = state
+    .services
+    .journal_service
+    .open(request.date, request.template.as_deref())?;
+  Ok(ApiRes::success(note))
+}
+
+pub async fn list_journal_handler(
+  State(state): State<AppState>,
+  Query(params): Query<ListJournalQuery>,
+) -> Result<ApiRes<Vec<JournalEntry>>, AppError> {
+  tracing::info!(
+    "[JournalHandler] listJournal: {:?} - {:?}",
+    params.from,
+    params.to
+  );
+  let entries = state
+    .services
+    .journal_service
+    .list(params.from, params.to)?;
+  Ok(ApiRes::success(entries))
+}
@@ -2,11 +2,18 @@ use serde::{Deserialize, Serialize};
 
 use crate::responses::app::{ApiRes, AppError};
 
+pub mod collab;
 pub mod doc;
+pub mod export;
 pub mod git;
+pub mod history;
 pub mod img;
+pub mod journal;
 pub mod search;
 pub mod settings;
+pub mod trash;
+pub mod watcher;
+pub mod workspace;
 
 #[derive(Serialize, Deserialize)]
 pub struct CheckServerRes {
@@ -2,7 +2,7 @@ use axum::extract::State;
 
 use crate::{
   responses::app::{ApiRes, AppError, AppJson},
-  services::settings::{Settings, SettingsPatch},
+  services::settings::{Settings, SettingsPatch, SettingsService},
   state::app::AppState,
 };
 
@@ -18,14 +18,36 @@ pub async fn update_settings_handler(
   State(state): State<AppState>,
   AppJson(new_settings): AppJson<SettingsPatch>,
 ) -> Result<ApiRes<Settings>, AppError> {
-  let updated_settings = state
-    .services
-    .settings_service
-    .update_settings(new_settings)?;
+  let settings_service = &state.services.settings_service;
+  let previous_root = settings_service
+    .settings
+    .lock()
+    .unwrap()
+    .doc_root_path
+    .clone();
+  let switching = new_settings
+    .doc_root_path
+    .as_ref()
+    .is_some_and(|path| SettingsService::resolve_doc_root(path) != previous_root);
+  if switching {
+    // a missing workspace would fail the update after the sessions were closed
+    SettingsService::check_workspace(new_settings.doc_root_path.clone().unwrap_or_default())?;
+    // sessions save through the paths of the current workspace
+    let collab_service = state.services.collab_service.clone();
+    tokio::task::spawn_blocking(move || collab_service.close_all())
+      .await
+      .map_err(anyhow::Error::from)?;
+  }
 
-  // Reinitialize git repository if doc_root_path changed
-  // This is necessary because the git repository needs to be reopened at the new path
-  state.services.git_service.sync_git(&updated_settings);
+  let updated_settings = settings_service.update_settings(new_settings)?;
+
+  if switching {
+    state.services.reload_workspace(&updated_settings);
+  } else {
+    // ignored folders and doc extensions shape the tree and what is watched
+    state.services.doc_service.invalidate_doc_tree();
+    state.services.watcher_service.rearm();
+  }
 
   Ok(ApiRes::success(updated_settings))
 }
@@ -0,0 +1,42 @@
+use axum::extract::State;
+
+use crate::{
+  responses::app::{ApiRes, AppError, AppJson},
+  services::trash::{PurgeTrashRequest, RestoreTrashRequest, RestoreTrashResponse, TrashItem},
+  state::app::AppState,
+};
+
+pub async fn list_trash_handler(
+  State(state): State<AppState>,
+) -> Result<ApiRes<Vec<TrashItem>>, AppError> {
+  tracing::info!("[TrashHandler] listTrash.");
+  let items = state.services.trash_service.list()?;
+  Ok(ApiRes::success(items))
+}
+
+pub async fn restore_trash_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<RestoreTrashRequest>,
+) -> Result<ApiRes<RestoreTrashResponse>, AppError> {
+  tracing::info!(
+    "[TrashHandler] restoreTrash: {}, strategy: {:?}",
+    request.id,
+    request.conflict_strategy
+  );
+  let restored = state
+    .services
+    .trash_service
+    .restore(&request.id, request.conflict_strategy)?;
+  // The restored doc shows up in the tree again
+  state.services.doc_service.invalidate_doc_tree();
+  Ok(ApiRes::success(restored))
+}
+
+pub async fn purge_trash_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<PurgeTrashRequest>,
+) -> Result<ApiRes<usize>, AppError> {
+  tracing::info!("[TrashHandler] purgeTrash: {:?}", request.ids);
+  let purged = state.services.trash_service.purge(request.ids.as_deref())?;
+  Ok(ApiRes::success(purged))
+}
@@ -0,0 +1,24 @@
+use std::convert::Infallible;
+
+use axum::{
+  extract::State,
+  response::sse::{Event, KeepAlive, Sse},
+};
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+
+use crate::state::app::AppState;
+
+/// Streams the changes of the workspace as `change` server-sent events with a JSON
+/// `FsChangeEvent` payload.
+pub async fn watch_events_handler(
+  State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+  tracing::info!("[WatcherHandler] watchEvents.");
+  let stream = BroadcastStream::new(state.services.watcher_service.subscribe())
+    // a lagging client misses some events, it should refresh on its own
+    .filter_map(|change| change.ok())
+    .filter_map(|change| Event::default().event("change").json_data(change).ok())
+    .map(Ok);
+
+  Sse::new(stream).keep_alive(KeepAlive::default())
+}
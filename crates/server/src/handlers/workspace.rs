@@ -0,0 +1,101 @@
+use axum::extract::{Query, State};
+use serde::Deserialize;
+
+use crate::{
+  responses::app::{ApiRes, AppError, AppJson},
+  services::{
+    settings::{AddWorkspaceRequest, Settings, SettingsService, Workspace, WorkspacePathRequest},
+    stats::{DEFAULT_ACTIVITY_WEEKS, DEFAULT_STALE_MONTHS, MAX_ACTIVITY_WEEKS, WorkspaceStats},
+  },
+  state::app::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStatsQuery {
+  /// Docs not modified for this many months are stale.
+  pub stale_months: Option<u32>,
+  /// Weeks of modification activity to report, at most [`MAX_ACTIVITY_WEEKS`].
+  pub weeks: Option<u32>,
+}
+
+pub async fn workspace_stats_handler(
+  State(state): State<AppState>,
+  Query(params): Query<WorkspaceStatsQuery>,
+) -> Result<ApiRes<WorkspaceStats>, AppError> {
+  let stale_months = params.stale_months.unwrap_or(DEFAULT_STALE_MONTHS);
+  let weeks = params
+    .weeks
+    .unwrap_or(DEFAULT_ACTIVITY_WEEKS)
+    .min(MAX_ACTIVITY_WEEKS);
+  tracing::info!(
+    "[WorkspaceHandler] workspaceStats: stale_months {}, weeks {}",
+    stale_months,
+    weeks
+  );
+
+  // reads every doc of the workspace
+  let stats_service = state.services.stats_service.clone();
+  let stats =
+    tokio::task::spawn_blocking(move || stats_service.workspace_stats(stale_months, weeks))
+      .await
+      .map_err(anyhow::Error::from)??;
+  Ok(ApiRes::success(stats))
+}
+
+pub async fn list_workspaces_handler(
+  State(state): State<AppState>,
+) -> Result<ApiRes<Vec<Workspace>>, AppError> {
+  tracing::info!("[WorkspaceHandler] listWorkspaces");
+  let workspaces = state.services.settings_service.list_workspaces()?;
+  Ok(ApiRes::success(workspaces))
+}
+
+pub async fn add_workspace_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<AddWorkspaceRequest>,
+) -> Result<ApiRes<Workspace>, AppError> {
+  tracing::info!("[WorkspaceHandler] addWorkspace: {}", request.path);
+  let workspace = state
+    .services
+    .settings_service
+    .add_workspace(&request.path, request.name)?;
+  Ok(ApiRes::success(workspace))
+}
+
+pub async fn remove_workspace_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<WorkspacePathRequest>,
+) -> Result<ApiRes<()>, AppError> {
+  tracing::info!("[WorkspaceHandler] removeWorkspace: {}", request.path);
+  state
+    .services
+    .settings_service
+    .remove_workspace(&request.path)?;
+  Ok(ApiRes::success(()))
+}
+
+/// Switches to a registered workspace, returning its settings.
+pub async fn switch_workspace_handler(
+  State(state): State<AppState>,
+  AppJson(request): AppJson<WorkspacePathRequest>,
+) -> Result<ApiRes<Settings>, AppError> {
+  tracing::info!("[WorkspaceHandler] switchWorkspace: {}", request.path);
+  let settings_service = &state.services.settings_service;
+  let target = SettingsService::resolve_doc_root(&request.path);
+  if settings_service.settings.lock().unwrap().doc_root_path == target {
+    return Ok(ApiRes::success(settings_service.get_settings()));
+  }
+  // an unknown or missing workspace would fail the switch after the sessions were closed
+  settings_service.check_registered_workspace(&request.path)?;
+
+  // sessions save through the paths of the current workspace
+  let collab_service = state.services.collab_service.clone();
+  tokio::task::spawn_blocking(move || collab_service.close_all())
+    .await
+    .map_err(anyhow::Error::from)?;
+  let settings = settings_service.switch_workspace(&request.path)?;
+  state.services.reload_workspace(&settings);
+
+  Ok(ApiRes::success(settings))
+}
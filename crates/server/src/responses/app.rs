@@ -7,6 +7,14 @@ use axum::{
 };
 use serde::Serialize;
 
+use crate::{
+  services::{
+    doc::{ArticleConflict, DocExistsConflict},
+    trash::TrashRestoreConflict,
+  },
+  utils::workspace_path::WorkspacePathError,
+};
+
 // Create our own JSON extractor by wrapping `axum::Json`. This makes it easy to override the
 // rejection and provide our own which formats errors to match our application.
 //
@@ -19,7 +27,7 @@ pub struct AppJson<T>(pub T);
 pub struct ApiRes<T> {
   data: Option<T>,
   code: u8,
-  message: Option<T>,
+  message: Option<String>,
 }
 
 impl<T> ApiRes<T> {
@@ -31,13 +39,21 @@ impl<T> ApiRes<T> {
     }
   }
 
-  pub fn error(message: T) -> Self {
+  pub fn error(message: String) -> Self {
     Self {
       data: None,
       code: 1,
       message: Some(message),
     }
   }
+
+  pub fn error_with_data(data: T, message: String) -> Self {
+    Self {
+      data: Some(data),
+      code: 1,
+      message: Some(message),
+    }
+  }
 }
 
 impl<T> IntoResponse for ApiRes<T>
@@ -55,6 +71,14 @@ where
 pub enum AppError {
   // The request body contained invalid JSON
   JsonRejection(JsonRejection),
+  // The request referenced a path outside of the workspace
+  InvalidPath(WorkspacePathError),
+  // The request conflicts with the current state of the workspace,
+  // e.g. a save based on a stale version of a document
+  Conflict {
+    message: String,
+    data: serde_json::Value,
+  },
   Unknown(anyhow::Error),
 }
 
@@ -66,6 +90,18 @@ impl IntoResponse for AppError {
         // This error is caused by bad user input so don't log it
         (rejection.status(), rejection.body_text(), None)
       }
+      AppError::InvalidPath(err) => {
+        // Also bad user input, no need to log it as an unexpected error
+        (StatusCode::BAD_REQUEST, err.to_string(), None)
+      }
+      AppError::Conflict { message, data } => {
+        // The client needs the details to resolve the conflict
+        return (
+          StatusCode::CONFLICT,
+          ApiRes::error_with_data(data.clone(), message.clone()),
+        )
+          .into_response();
+      }
       AppError::Unknown(err) => (
         StatusCode::INTERNAL_SERVER_ERROR,
         err.to_string(),
@@ -73,7 +109,7 @@ impl IntoResponse for AppError {
       ),
     };
 
-    let mut response = (status, ApiRes::error(message)).into_response();
+    let mut response = (status, ApiRes::<()>::error(message)).into_response();
     if let Some(err) = err {
       // Insert our error into the response, our logging middleware will use this.
       // By wrapping the error in an Arc we can use it as an Extension regardless of any inner types not deriving Clone.
@@ -84,6 +120,15 @@ impl IntoResponse for AppError {
   }
 }
 
+impl AppError {
+  fn conflict<E: Serialize + std::fmt::Display>(conflict: &E) -> Self {
+    Self::Conflict {
+      message: conflict.to_string(),
+      data: serde_json::to_value(conflict).unwrap_or_default(),
+    }
+  }
+}
+
 impl From<JsonRejection> for AppError {
   fn from(rejection: JsonRejection) -> Self {
     Self::JsonRejection(rejection)
@@ -95,6 +140,21 @@ impl From<JsonRejection> for AppError {
 // see the get_all_users_handler and get_all_users service
 impl From<anyhow::Error> for AppError {
   fn from(error: anyhow::Error) -> Self {
-    Self::Unknown(error)
+    let error = match error.downcast::<WorkspacePathError>() {
+      Ok(err) => return Self::InvalidPath(err),
+      Err(error) => error,
+    };
+    let error = match error.downcast::<ArticleConflict>() {
+      Ok(conflict) => return Self::conflict(&conflict),
+      Err(error) => error,
+    };
+    let error = match error.downcast::<DocExistsConflict>() {
+      Ok(conflict) => return Self::conflict(&conflict),
+      Err(error) => error,
+    };
+    match error.downcast::<TrashRestoreConflict>() {
+      Ok(conflict) => Self::conflict(&conflict),
+      Err(error) => Self::Unknown(error),
+    }
   }
 }
@@ -0,0 +1,15 @@
+use axum::{Router, routing};
+
+use crate::{
+  handlers::collab::{collab_handler, get_collab_peers_handler},
+  state::app::AppState,
+};
+
+pub fn collab_routes() -> Router<AppState> {
+  Router::new().nest(
+    "/collab",
+    Router::new()
+      .route("/ws", routing::get(collab_handler))
+      .route("/peers", routing::get(get_collab_peers_handler)),
+  )
+}
@@ -2,9 +2,11 @@ use axum::{Router, routing};
 
 use crate::{
   handlers::doc::{
-    copy_cut_doc_handler, create_doc_handler, create_folder_handler, delete_doc_handler,
-    get_article_handler, get_sub_doc_items_handler, update_article_handler,
-    update_doc_name_handler,
+    batch_docs_handler, copy_cut_doc_handler, create_doc_handler, create_folder_handler,
+    delete_doc_handler, get_article_handler, get_backlinks_handler, get_doc_tree_handler,
+    get_sub_doc_items_handler, get_wiki_links_handler, list_templates_handler,
+    preview_link_updates_handler, reorder_doc_items_handler, resolve_wiki_link_handler,
+    update_article_handler, update_doc_name_handler, update_front_matter_handler,
   },
   state::app::AppState,
 };
@@ -14,12 +16,24 @@ pub fn doc_routes() -> Router<AppState> {
     "/docs",
     Router::new()
       .route("/sub-items", routing::get(get_sub_doc_items_handler))
+      .route("/tree", routing::get(get_doc_tree_handler))
+      .route("/order", routing::put(reorder_doc_items_handler))
       .route("/article", routing::get(get_article_handler))
       .route("/create", routing::post(create_doc_handler))
+      .route("/templates", routing::get(list_templates_handler))
       .route("/create-folder", routing::post(create_folder_handler))
       .route("/update", routing::patch(update_article_handler))
+      .route("/front-matter", routing::patch(update_front_matter_handler))
+      .route("/link-updates", routing::post(preview_link_updates_handler))
+      .route("/backlinks", routing::get(get_backlinks_handler))
+      .route("/wiki-links", routing::get(get_wiki_links_handler))
+      .route(
+        "/wiki-links/resolve",
+        routing::get(resolve_wiki_link_handler),
+      )
       .route("/update-name", routing::patch(update_doc_name_handler))
       .route("/copy-cut", routing::patch(copy_cut_doc_handler))
-      .route("/delete", routing::delete(delete_doc_handler)),
+      .route("/delete", routing::delete(delete_doc_handler))
+      .route("/batch", routing::post(batch_docs_handler)),
   )
 }
@@ -0,0 +1,15 @@
+use axum::{Router, routing};
+
+use crate::{
+  handlers::export::{export_docx_handler, export_epub_handler},
+  state::app::AppState,
+};
+
+pub fn export_routes() -> Router<AppState> {
+  Router::new().nest(
+    "/export",
+    Router::new()
+      .route("/epub", routing::get(export_epub_handler))
+      .route("/docx", routing::get(export_docx_handler)),
+  )
+}
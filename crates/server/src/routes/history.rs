@@ -0,0 +1,16 @@
+use axum::{Router, routing};
+
+use crate::{
+  handlers::history::{get_history_version_handler, list_history_handler, restore_history_handler},
+  state::app::AppState,
+};
+
+pub fn history_routes() -> Router<AppState> {
+  Router::new().nest(
+    "/history",
+    Router::new()
+      .route("/list", routing::get(list_history_handler))
+      .route("/version", routing::get(get_history_version_handler))
+      .route("/restore", routing::post(restore_history_handler)),
+  )
+}
@@ -0,0 +1,15 @@
+use axum::{Router, routing};
+
+use crate::{
+  handlers::journal::{list_journal_handler, open_journal_handler},
+  state::app::AppState,
+};
+
+pub fn journal_routes() -> Router<AppState> {
+  Router::new().nest(
+    "/journal",
+    Router::new()
+      .route("/open", routing::post(open_journal_handler))
+      .route("/list", routing::get(list_journal_handler)),
+  )
+}
@@ -1,6 +1,13 @@
+mod collab;
 mod doc;
+mod export;
 mod git;
+mod history;
 mod img;
+mod journal;
 pub mod root;
 mod search;
 mod settings;
+mod trash;
+mod watcher;
+mod workspace;
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use axum::{
   Router, ServiceExt,
   extract::{MatchedPath, Request},
-  http::{HeaderName, HeaderValue},
+  http::{HeaderName, HeaderValue, header},
   middleware::from_fn,
   routing,
   routing::IntoMakeService,
@@ -23,8 +23,10 @@ use crate::{
   handlers::check_server_handler,
   middlewares::logs::log_app_errors,
   routes::{
-    doc::doc_routes, git::git_routes, img::img_routes, search::search_routes,
-    settings::settings_routes,
+    collab::collab_routes, doc::doc_routes, export::export_routes, git::git_routes,
+    history::history_routes, img::img_routes, journal::journal_routes, search::search_routes,
+    settings::settings_routes, trash::trash_routes, watcher::watcher_routes,
+    workspace::workspace_routes,
   },
   state::app::AppState,
 };
@@ -44,7 +46,9 @@ pub fn init_routes(
         .map(|s| HeaderValue::from_static(s)),
     )) // Open access to selected route
     .allow_methods(Any)
-    .allow_headers(Any);
+    .allow_headers(Any)
+    // file names of the exports
+    .expose_headers([header::CONTENT_DISPOSITION]);
 
   let middleware = ServiceBuilder::new()
     .layer(SetRequestIdLayer::new(
@@ -93,9 +97,16 @@ pub fn init_routes(
       .with_state(app_state.clone())
       .merge(settings_routes().with_state(app_state.clone()))
       .merge(doc_routes().with_state(app_state.clone()))
+      .merge(export_routes().with_state(app_state.clone()))
       .merge(git_routes().with_state(app_state.clone()))
       .merge(img_routes().with_state(app_state.clone()))
       .merge(search_routes().with_state(app_state.clone()))
+      .merge(trash_routes().with_state(app_state.clone()))
+      .merge(journal_routes().with_state(app_state.clone()))
+      .merge(history_routes().with_state(app_state.clone()))
+      .merge(watcher_routes().with_state(app_state.clone()))
+      .merge(workspace_routes().with_state(app_state.clone()))
+      .merge(collab_routes().with_state(app_state.clone()))
       .layer(cors_layer)
       .layer(middleware),
   );
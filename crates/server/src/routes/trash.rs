@@ -0,0 +1,16 @@
+use axum::{Router, routing};
+
+use crate::{
+  handlers::trash::{list_trash_handler, purge_trash_handler, restore_trash_handler},
+  state::app::AppState,
+};
+
+pub fn trash_routes() -> Router<AppState> {
+  Router::new().nest(
+    "/trash",
+    Router::new()
+      .route("/list", routing::get(list_trash_handler))
+      .route("/restore", routing::post(restore_trash_handler))
+      .route("/purge", routing::delete(purge_trash_handler)),
+  )
+}
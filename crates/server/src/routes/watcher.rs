@@ -0,0 +1,10 @@
+use axum::{Router, routing};
+
+use crate::{handlers::watcher::watch_events_handler, state::app::AppState};
+
+pub fn watcher_routes() -> Router<AppState> {
+  Router::new().nest(
+    "/watch",
+    Router::new().route("/events", routing::get(watch_events_handler)),
+  )
+}
@@ -0,0 +1,21 @@
+use axum::{Router, routing};
+
+use crate::{
+  handlers::workspace::{
+    add_workspace_handler, list_workspaces_handler, remove_workspace_handler,
+    switch_workspace_handler, workspace_stats_handler,
+  },
+  state::app::AppState,
+};
+
+pub fn workspace_routes() -> Router<AppState> {
+  Router::new().nest(
+    "/workspace",
+    Router::new()
+      .route("/list", routing::get(list_workspaces_handler))
+      .route("/add", routing::post(add_workspace_handler))
+      .route("/remove", routing::delete(remove_workspace_handler))
+      .route("/switch", routing::post(switch_workspace_handler))
+      .route("/stats", routing::get(workspace_stats_handler)),
+  )
+}
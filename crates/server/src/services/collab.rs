@@ -0,0 +1,702 @@
+use std::{
+  collections::HashMap,
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+  },
+  time::Duration,
+};
+
+use automerge::{
+  AutoCommit, ChangeHash, Cursor, CursorPosition, ObjId, ObjType, ROOT, ReadDoc, TextEncoding,
+  sync::{self, SyncDoc},
+  transaction::Transactable,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Notify, broadcast};
+
+use crate::services::doc::{ArticleConflict, DocService};
+
+/// Merged edits are written to disk once the session has been quiet for this long.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+const ROOM_EVENT_CAPACITY: usize = 64;
+
+pub type PeerId = u64;
+
+#[derive(Debug, Deserialize)]
+pub struct CollabQuery {
+  #[serde(rename = "filePath")]
+  pub file_path: String,
+  /// Display name of the peer.
+  pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetCollabPeersQuery {
+  #[serde(rename = "filePath")]
+  pub file_path: String,
+}
+
+/// A selection in the shared text, as UTF-16 offsets like the editors use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollabCursor {
+  pub anchor: usize,
+  pub head: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollabPeer {
+  pub peer_id: PeerId,
+  pub name: String,
+  pub cursor: Option<CollabCursor>,
+}
+
+/// JSON (text frame) messages sent by a client, CRDT updates are binary frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CollabClientMessage {
+  /// Moves the cursor of the peer, `null` when the editor lost focus.
+  Cursor { cursor: Option<CollabCursor> },
+}
+
+/// JSON (text frame) messages sent to a client, CRDT updates are binary frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CollabServerMessage {
+  #[serde(rename_all = "camelCase")]
+  Welcome {
+    peer_id: PeerId,
+  },
+  Peers {
+    peers: Vec<CollabPeer>,
+  },
+  /// The merged content has been written to disk.
+  Saved {
+    version: String,
+  },
+  Error {
+    message: String,
+  },
+  /// The session ended because the workspace was switched.
+  Closed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoomEvent {
+  /// The shared doc changed, peers should get a sync message.
+  DocChanged,
+  PeersChanged,
+  Saved(String),
+  /// The session was closed by the server, peers should disconnect.
+  Closed,
+}
+
+struct PeerState {
+  name: String,
+  /// Stable CRDT positions of the anchor and head, so they follow concurrent edits.
+  cursor: Option<(Cursor, Cursor)>,
+}
+
+struct RoomState {
+  doc: AutoCommit,
+  text: ObjId,
+  /// Heads and version of the content last read from or written to disk.
+  saved_heads: Vec<ChangeHash>,
+  saved_version: String,
+  peers: HashMap<PeerId, PeerState>,
+  closed: bool,
+}
+
+/// The shared CRDT doc of an article being edited together.
+pub struct CollabRoom {
+  file_path: String,
+  state: Mutex<RoomState>,
+  events: broadcast::Sender<RoomEvent>,
+  save_requested: Notify,
+  /// Held while saving so concurrent saves don't both write the same changes.
+  save_lock: Mutex<()>,
+}
+
+impl CollabRoom {
+  fn open(file_path: &str, content: &str, version: String) -> Result<Self, anyhow::Error> {
+    let mut doc = AutoCommit::new_with_encoding(TextEncoding::Utf16CodeUnit);
+    let text = doc.put_object(ROOT, "content", ObjType::Text)?;
+    doc.splice_text(&text, 0, 0, content)?;
+    let saved_heads = doc.get_heads();
+
+    let (events, _) = broadcast::channel(ROOM_EVENT_CAPACITY);
+    Ok(Self {
+      file_path: file_path.to_string(),
+      state: Mutex::new(RoomState {
+        doc,
+        text,
+        saved_heads,
+        saved_version: version,
+        peers: HashMap::new(),
+        closed: false,
+      }),
+      events,
+      save_requested: Notify::new(),
+      save_lock: Mutex::new(()),
+    })
+  }
+
+  pub fn subscribe(&self) -> broadcast::Receiver<RoomEvent> {
+    self.events.subscribe()
+  }
+
+  /// Current merged markdown of the room.
+  pub fn content(&self) -> Result<String, anyhow::Error> {
+    let state = self.state.lock().unwrap();
+    Ok(state.doc.text(&state.text)?)
+  }
+
+  /// Applies an automerge sync message of a peer, `sync_state` being the state of the
+  /// connection to that peer.
+  pub fn receive_sync_message(
+    &self,
+    sync_state: &mut sync::State,
+    message: &[u8],
+  ) -> Result<(), anyhow::Error> {
+    let message = sync::Message::decode(message)?;
+    let changed = {
+      let mut state = self.state.lock().unwrap();
+      let heads = state.doc.get_heads();
+      state.doc.sync().receive_sync_message(sync_state, message)?;
+      state.doc.get_heads() != heads
+    };
+
+    if changed {
+      let _ = self.events.send(RoomEvent::DocChanged);
+      self.save_requested.notify_one();
+    }
+    Ok(())
+  }
+
+  /// The next automerge sync message for a peer, if it is missing anything.
+  pub fn generate_sync_message(&self, sync_state: &mut sync::State) -> Option<Vec<u8>> {
+    let mut state = self.state.lock().unwrap();
+    state
+      .doc
+      .sync()
+      .generate_sync_message(sync_state)
+      .map(|message| message.encode())
+  }
+
+  pub fn set_cursor(
+    &self,
+    peer_id: PeerId,
+    cursor: Option<CollabCursor>,
+  ) -> Result<(), anyhow::Error> {
+    {
+      let mut state = self.state.lock().unwrap();
+      let cursor = match cursor {
+        Some(cursor) => Some((
+          state.stable_cursor(cursor.anchor)?,
+          state.stable_cursor(cursor.head)?,
+        )),
+        None => None,
+      };
+      let Some(peer) = state.peers.get_mut(&peer_id) else {
+        return Err(anyhow::anyhow!("Peer {} is not in the session.", peer_id));
+      };
+      peer.cursor = cursor;
+    }
+
+    let _ = self.events.send(RoomEvent::PeersChanged);
+    Ok(())
+  }
+
+  /// Connected peers with their cursors at the current positions, ordered by join time.
+  pub fn peers(&self) -> Vec<CollabPeer> {
+    let state = self.state.lock().unwrap();
+    let mut peers: Vec<CollabPeer> = state
+      .peers
+      .iter()
+      .map(|(peer_id, peer)| CollabPeer {
+        peer_id: *peer_id,
+        name: peer.name.clone(),
+        cursor: peer.cursor.as_ref().and_then(|(anchor, head)| {
+          Some(CollabCursor {
+            anchor: state
+              .doc
+              .get_cursor_position(&state.text, anchor, None)
+              .ok()?,
+            head: state
+              .doc
+              .get_cursor_position(&state.text, head, None)
+              .ok()?,
+          })
+        }),
+      })
+      .collect();
+    peers.sort_by_key(|peer| peer.peer_id);
+    peers
+  }
+
+  /// Writes the merged content through the doc service if it changed since the last save.
+  /// Edits made to the file outside of the session are merged in as a concurrent change
+  /// from the last saved state instead of being overwritten.
+  ///
+  /// Blocks on disk I/O, the room stays unlocked meanwhile so peers keep editing.
+  pub fn persist(&self, doc_service: &DocService) -> Result<Option<String>, anyhow::Error> {
+    let _saving = self.save_lock.lock().unwrap();
+    let mut merged_external = false;
+
+    loop {
+      let (heads, content, saved_version) = {
+        let mut state = self.state.lock().unwrap();
+        let heads = state.doc.get_heads();
+        if heads == state.saved_heads && !merged_external {
+          return Ok(None);
+        }
+        (
+          heads,
+          state.doc.text(&state.text)?,
+          state.saved_version.clone(),
+        )
+      };
+
+      match doc_service.update_article_if_match(&self.file_path, &content, Some(&saved_version)) {
+        Ok(version) => {
+          {
+            let mut state = self.state.lock().unwrap();
+            state.saved_heads = heads;
+            state.saved_version = version.clone();
+          }
+
+          if merged_external {
+            let _ = self.events.send(RoomEvent::DocChanged);
+          }
+          let _ = self.events.send(RoomEvent::Saved(version.clone()));
+          tracing::info!("[CollabService] saved {}", self.file_path);
+          return Ok(Some(version));
+        }
+        Err(e) => {
+          let Some(conflict) = e.downcast_ref::<ArticleConflict>() else {
+            return Err(e);
+          };
+          let (Some(external), Some(version)) = (&conflict.content, &conflict.version) else {
+            return Err(anyhow::anyhow!(
+              "The file {} has been removed.",
+              self.file_path
+            ));
+          };
+          if merged_external {
+            // changed again while merging, try on the next save
+            return Err(e);
+          }
+
+          tracing::info!(
+            "[CollabService] merging outside changes of {}",
+            self.file_path
+          );
+          let mut state = self.state.lock().unwrap();
+          let saved_heads = state.saved_heads.clone();
+          let text = state.text.clone();
+          let mut fork = state.doc.fork_at(&saved_heads)?;
+          fork.update_text(&text, external)?;
+          state.doc.merge(&mut fork)?;
+          state.saved_version = version.clone();
+          merged_external = true;
+        }
+      }
+    }
+  }
+
+  fn save(&self, doc_service: &DocService) {
+    if let Err(e) = self.persist(doc_service) {
+      tracing::error!("[CollabService] failed to save {}: {}", self.file_path, e);
+    }
+  }
+
+  fn is_closed(&self) -> bool {
+    self.state.lock().unwrap().closed
+  }
+}
+
+impl RoomState {
+  fn stable_cursor(&self, position: usize) -> Result<Cursor, anyhow::Error> {
+    let position = if position >= self.doc.length(&self.text) {
+      CursorPosition::End
+    } else {
+      CursorPosition::Index(position)
+    };
+    Ok(self.doc.get_cursor(&self.text, position, None)?)
+  }
+}
+
+pub struct CollabService {
+  doc_service: Arc<DocService>,
+  rooms: Mutex<HashMap<String, Arc<CollabRoom>>>,
+  next_peer_id: AtomicU64,
+}
+
+impl CollabService {
+  pub fn new(doc_service: Arc<DocService>) -> Self {
+    Self {
+      doc_service,
+      rooms: Mutex::new(HashMap::new()),
+      next_peer_id: AtomicU64::new(1),
+    }
+  }
+
+  /// Adds a peer to the session of an article, opening the session from the file on disk
+  /// if nobody is editing it yet. Must be called within the tokio runtime.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let (room, peer_id) = collab_service.join("meetings%2Fweekly", "Ada")?;
+  /// let mut sync_state = automerge::sync::State::new();
+  /// let message = room.generate_sync_message(&mut sync_state);
+  /// ```
+  pub fn join(
+    &self,
+    file_path: &str,
+    name: &str,
+  ) -> Result<(Arc<CollabRoom>, PeerId), anyhow::Error> {
+    let existing = self.rooms.lock().unwrap().get(file_path).cloned();
+    let room = match existing {
+      Some(room) => room,
+      None => {
+        // the doc is read without holding the sessions lock
+        let Some(article) = self.doc_service.get_article(file_path)? else {
+          return Err(anyhow::anyhow!("The file {} does not exist.", file_path));
+        };
+        let opened = Arc::new(CollabRoom::open(
+          file_path,
+          &article.content,
+          article.version,
+        )?);
+        let mut rooms = self.rooms.lock().unwrap();
+        match rooms.get(file_path) {
+          // another peer opened the session meanwhile
+          Some(room) => room.clone(),
+          None => {
+            self.spawn_saver(opened.clone());
+            rooms.insert(file_path.to_string(), opened.clone());
+            opened
+          }
+        }
+      }
+    };
+
+    let peer_id = self.next_peer_id.fetch_add(1, Ordering::Relaxed);
+    room.state.lock().unwrap().peers.insert(
+      peer_id,
+      PeerState {
+        name: name.to_string(),
+        cursor: None,
+      },
+    );
+    let _ = room.events.send(RoomEvent::PeersChanged);
+
+    tracing::info!("[CollabService] {} joined {}", name, file_path);
+    Ok((room, peer_id))
+  }
+
+  /// Removes a peer, the last one to leave saves and closes the session.
+  pub fn leave(&self, room: &Arc<CollabRoom>, peer_id: PeerId) {
+    let mut rooms = self.rooms.lock().unwrap();
+    let remaining = {
+      let mut state = room.state.lock().unwrap();
+      state.peers.remove(&peer_id);
+      if state.closed {
+        // already saved by `close_all`
+        return;
+      }
+      state.peers.len()
+    };
+
+    if remaining > 0 {
+      let _ = room.events.send(RoomEvent::PeersChanged);
+      return;
+    }
+
+    if rooms
+      .get(&room.file_path)
+      .is_some_and(|current| Arc::ptr_eq(current, room))
+    {
+      rooms.remove(&room.file_path);
+    }
+    drop(rooms);
+
+    room.save(&self.doc_service);
+    room.state.lock().unwrap().closed = true;
+    room.save_requested.notify_one();
+    tracing::info!("[CollabService] closed the session of {}", room.file_path);
+  }
+
+  /// Saves and closes every session, disconnecting their peers. Called before switching
+  /// workspaces since the sessions save through the paths of the current one.
+  pub fn close_all(&self) {
+    let rooms: Vec<Arc<CollabRoom>> = self
+      .rooms
+      .lock()
+      .unwrap()
+      .drain()
+      .map(|(_, room)| room)
+      .collect();
+    for room in rooms {
+      room.save(&self.doc_service);
+      room.state.lock().unwrap().closed = true;
+      room.save_requested.notify_one();
+      let _ = room.events.send(RoomEvent::Closed);
+      tracing::info!("[CollabService] closed the session of {}", room.file_path);
+    }
+  }
+
+  /// Peers editing an article, empty when there is no session.
+  pub fn peers(&self, file_path: &str) -> Vec<CollabPeer> {
+    self
+      .rooms
+      .lock()
+      .unwrap()
+      .get(file_path)
+      .map(|room| room.peers())
+      .unwrap_or_default()
+  }
+
+  /// Saves a room once its edits settle, until the room is closed. Every change restarts
+  /// the wait, the save itself runs on the blocking pool.
+  fn spawn_saver(&self, room: Arc<CollabRoom>) {
+    let doc_service = self.doc_service.clone();
+    tokio::spawn(async move {
+      loop {
+        room.save_requested.notified().await;
+        loop {
+          tokio::select! {
+            _ = tokio::time::sleep(SAVE_DELAY) => break,
+            _ = room.save_requested.notified() => {}
+          }
+        }
+        if room.is_closed() {
+          break;
+        }
+
+        let (saving_room, saving_service) = (room.clone(), doc_service.clone());
+        if tokio::task::spawn_blocking(move || saving_room.save(&saving_service))
+          .await
+          .is_err()
+        {
+          break;
+        }
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use super::*;
+  use crate::services::settings::SettingsService;
+
+  fn setup_test_service() -> (CollabService, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let settings_service = Arc::new(SettingsService::for_test(
+      temp_dir.path().to_path_buf(),
+      &[],
+      temp_dir.path().join("editor-settings.json"),
+    ));
+    let doc_service = Arc::new(DocService::for_test(settings_service));
+    (CollabService::new(doc_service), temp_dir)
+  }
+
+  /// A client replica synced with the room until neither side has anything to send.
+  struct Client {
+    doc: AutoCommit,
+    sync_state: sync::State,
+    room_sync_state: sync::State,
+  }
+
+  impl Client {
+    fn new() -> Self {
+      Self {
+        doc: AutoCommit::new_with_encoding(TextEncoding::Utf16CodeUnit),
+        sync_state: sync::State::new(),
+        room_sync_state: sync::State::new(),
+      }
+    }
+
+    fn sync(&mut self, room: &CollabRoom) {
+      loop {
+        let to_room = self.doc.sync().generate_sync_message(&mut self.sync_state);
+        let to_client = room.generate_sync_message(&mut self.room_sync_state);
+        if to_room.is_none() && to_client.is_none() {
+          break;
+        }
+        if let Some(message) = to_room {
+          room
+            .receive_sync_message(&mut self.room_sync_state, &message.encode())
+            .unwrap();
+        }
+        if let Some(message) = to_client {
+          let message = sync::Message::decode(&message).unwrap();
+          self
+            .doc
+            .sync()
+            .receive_sync_message(&mut self.sync_state, message)
+            .unwrap();
+        }
+      }
+    }
+
+    fn text(&self) -> (ObjId, String) {
+      let (_, text) = self.doc.get(ROOT, "content").unwrap().unwrap();
+      let content = self.doc.text(&text).unwrap();
+      (text, content)
+    }
+
+    fn insert(&mut self, position: usize, value: &str) {
+      let (text, _) = self.text();
+      self.doc.splice_text(&text, position, 0, value).unwrap();
+    }
+  }
+
+  #[tokio::test]
+  async fn test_saves_once_edits_settle() {
+    let (service, temp_dir) = setup_test_service();
+    let note = temp_dir.path().join("weekly.md");
+    fs::write(&note, "# Weekly\n").unwrap();
+
+    let (room, ada) = service.join("weekly", "Ada").unwrap();
+    let mut client = Client::new();
+    client.sync(&room);
+    client.insert(9, "- budget\n");
+    client.sync(&room);
+    tokio::time::sleep(SAVE_DELAY * 7 / 10).await;
+    client.insert(9, "- goals\n");
+    client.sync(&room);
+
+    // the second edit restarted the wait
+    tokio::time::sleep(SAVE_DELAY * 7 / 10).await;
+    assert_eq!(fs::read_to_string(&note).unwrap(), "# Weekly\n");
+
+    tokio::time::sleep(SAVE_DELAY).await;
+    assert_eq!(
+      fs::read_to_string(&note).unwrap(),
+      "# Weekly\n- goals\n- budget\n"
+    );
+    service.leave(&room, ada);
+  }
+
+  #[tokio::test]
+  async fn test_concurrent_edits_merge_and_persist() {
+    let (service, temp_dir) = setup_test_service();
+    let note = temp_dir.path().join("weekly.md");
+    fs::write(&note, "# Weekly\n").unwrap();
+
+    let (room, ada) = service.join("weekly", "Ada").unwrap();
+    let (same_room, bob) = service.join("weekly", "Bob").unwrap();
+    assert!(Arc::ptr_eq(&room, &same_room));
+
+    let (mut ada_client, mut bob_client) = (Client::new(), Client::new());
+    ada_client.sync(&room);
+    bob_client.sync(&room);
+    assert_eq!(bob_client.text().1, "# Weekly\n");
+
+    // concurrent edits without seeing each other's
+    ada_client.insert(9, "- budget\n");
+    bob_client.insert(0, "<!-- draft -->\n");
+    ada_client.sync(&room);
+    bob_client.sync(&room);
+    ada_client.sync(&room);
+
+    let merged = "<!-- draft -->\n# Weekly\n- budget\n";
+    assert_eq!(room.content().unwrap(), merged);
+    assert_eq!(ada_client.text().1, merged);
+    assert_eq!(bob_client.text().1, merged);
+
+    room
+      .set_cursor(
+        ada,
+        Some(CollabCursor {
+          anchor: 24,
+          head: 24,
+        }),
+      )
+      .unwrap();
+    bob_client.insert(0, "> ");
+    bob_client.sync(&room);
+    let peers = service.peers("weekly");
+    assert_eq!(peers.len(), 2);
+    assert_eq!(peers[0].name, "Ada");
+    // the cursor follows the text before it
+    assert_eq!(
+      peers[0].cursor,
+      Some(CollabCursor {
+        anchor: 26,
+        head: 26
+      })
+    );
+    assert_eq!(peers[1].cursor, None);
+
+    service.leave(&room, bob);
+    service.leave(&room, ada);
+    assert!(service.peers("weekly").is_empty());
+    assert_eq!(
+      fs::read_to_string(&note).unwrap(),
+      "> <!-- draft -->\n# Weekly\n- budget\n"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_close_all_saves_and_disconnects() {
+    let (service, temp_dir) = setup_test_service();
+    let note = temp_dir.path().join("weekly.md");
+    fs::write(&note, "# Weekly\n").unwrap();
+
+    let (room, ada) = service.join("weekly", "Ada").unwrap();
+    let mut events = room.subscribe();
+    let mut client = Client::new();
+    client.sync(&room);
+    client.insert(9, "- budget\n");
+    client.sync(&room);
+
+    service.close_all();
+    assert_eq!(fs::read_to_string(&note).unwrap(), "# Weekly\n- budget\n");
+    assert!(service.peers("weekly").is_empty());
+    let mut closed = false;
+    while let Ok(event) = events.try_recv() {
+      closed |= event == RoomEvent::Closed;
+    }
+    assert!(closed);
+
+    // the socket leaving afterwards doesn't save again
+    fs::write(&note, "# Moved on\n").unwrap();
+    service.leave(&room, ada);
+    assert_eq!(fs::read_to_string(&note).unwrap(), "# Moved on\n");
+  }
+
+  #[tokio::test]
+  async fn test_outside_edits_are_merged() {
+    let (service, temp_dir) = setup_test_service();
+    let note = temp_dir.path().join("weekly.md");
+    fs::write(&note, "# Weekly\n").unwrap();
+
+    let (room, ada) = service.join("weekly", "Ada").unwrap();
+    let mut client = Client::new();
+    client.sync(&room);
+    client.insert(9, "- budget\n");
+    client.sync(&room);
+
+    // saved by someone not in the session
+    fs::write(&note, "# Weekly sync\n").unwrap();
+    let version = room.persist(&service.doc_service).unwrap();
+    assert!(version.is_some());
+    assert_eq!(
+      fs::read_to_string(&note).unwrap(),
+      "# Weekly sync\n- budget\n"
+    );
+
+    client.sync(&room);
+    assert_eq!(client.text().1, "# Weekly sync\n- budget\n");
+    // nothing new to save
+    assert_eq!(room.persist(&service.doc_service).unwrap(), None);
+    service.leave(&room, ada);
+  }
+}
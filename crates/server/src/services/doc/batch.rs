@@ -0,0 +1,259 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use super::{
+  BatchItemResult, BatchItemStatus, BatchOperation, BatchResponse, DocService, LinkUpdate,
+};
+use crate::services::trash::RestoreConflictStrategy;
+
+/// Reverts one effect of a batch operation.
+enum Undo {
+  /// Remove a created or copied file or folder.
+  Remove(PathBuf),
+  /// Move `from` back to `to`.
+  MoveBack { from: PathBuf, to: PathBuf },
+  /// Rename `from` back to `to`, restoring its place in the manual order.
+  RenameBack { from: PathBuf, to: PathBuf },
+  /// Write back the content of a doc whose links were rewritten.
+  RestoreContent { file_path: String, content: String },
+  /// Restore a deleted item from the trash.
+  Untrash(String),
+}
+
+impl DocService {
+  /// Runs `operations` in order, reporting the outcome of each. In `atomic` mode the
+  /// batch stops at the first failure and undoes the operations done so far, in reverse.
+  ///
+  /// Unlike the single operation endpoints, creating, copying, moving or renaming onto
+  /// an existing path fails instead of overwriting it, so every step can be undone.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let response = doc_service.batch(
+  ///   vec![
+  ///     BatchOperation::Create { file_path: "archive".to_string(), is_file: false },
+  ///     BatchOperation::Move {
+  ///       from_path: "js%2Farray".to_string(),
+  ///       to_path: "archive%2Farray".to_string(),
+  ///       is_file: true,
+  ///       update_links: true,
+  ///     },
+  ///   ],
+  ///   true,
+  /// );
+  /// ```
+  pub fn batch(&self, operations: Vec<BatchOperation>, atomic: bool) -> BatchResponse {
+    let mut results = Vec::with_capacity(operations.len());
+    let mut undo_log: Vec<(usize, Vec<Undo>)> = Vec::new();
+    let mut failed = false;
+
+    for operation in operations {
+      if failed && atomic {
+        results.push(BatchItemResult {
+          status: BatchItemStatus::Skipped,
+          error: None,
+          link_updates: Vec::new(),
+        });
+        continue;
+      }
+
+      let mut undo = Vec::new();
+      match self.run_batch_operation(operation, &mut undo) {
+        Ok(link_updates) => {
+          undo_log.push((results.len(), undo));
+          results.push(BatchItemResult {
+            status: BatchItemStatus::Done,
+            error: None,
+            link_updates,
+          });
+        }
+        Err(e) => {
+          failed = true;
+          // a partly applied operation is undone too
+          undo_log.push((results.len(), undo));
+          results.push(BatchItemResult {
+            status: BatchItemStatus::Failed,
+            error: Some(e.to_string()),
+            link_updates: Vec::new(),
+          });
+        }
+      }
+    }
+
+    let mut rolled_back = false;
+    if failed && atomic {
+      rolled_back = true;
+      for (index, undo) in undo_log.into_iter().rev() {
+        for step in undo.into_iter().rev() {
+          if let Err(e) = self.undo_batch_step(step) {
+            tracing::error!("[DocService] batch rollback failed: {}", e);
+            rolled_back = false;
+            let error = format!("Rollback failed: {}", e);
+            results[index].error = Some(match results[index].error.take() {
+              Some(original) => format!("{} {}", original, error),
+              None => error,
+            });
+          }
+        }
+        if results[index].status == BatchItemStatus::Done {
+          results[index].status = BatchItemStatus::RolledBack;
+          // the rewritten links were restored along with the rest
+          results[index].link_updates.clear();
+        }
+      }
+    }
+    self.invalidate_doc_tree();
+
+    BatchResponse {
+      results,
+      rolled_back,
+    }
+  }
+
+  /// Runs a single operation, pushing the steps that revert it onto `undo` as they apply.
+  fn run_batch_operation(
+    &self,
+    operation: BatchOperation,
+    undo: &mut Vec<Undo>,
+  ) -> Result<Vec<LinkUpdate>, anyhow::Error> {
+    match operation {
+      BatchOperation::Create { file_path, is_file } => {
+        let path = self.path_convertor(&file_path, is_file)?;
+        ensure_free(&path, &file_path)?;
+        let created = first_missing_ancestor(&path);
+        self.create_doc(&file_path, is_file)?;
+        undo.push(Undo::Remove(created));
+        Ok(Vec::new())
+      }
+      BatchOperation::Copy {
+        from_path,
+        to_path,
+        is_file,
+      } => {
+        let dest = self.path_convertor(&to_path, is_file)?;
+        ensure_free(&dest, &to_path)?;
+        // a failed folder copy may leave a partial copy behind
+        undo.push(Undo::Remove(dest));
+        self.copy_cut_doc(&from_path, &to_path, true, is_file, false)
+      }
+      BatchOperation::Move {
+        from_path,
+        to_path,
+        is_file,
+        update_links,
+      } => {
+        self.ensure_paste_parent(&to_path)?;
+        let source = self.path_convertor(&from_path, is_file)?;
+        let dest = self.path_convertor(&to_path, is_file)?;
+        ensure_free(&dest, &to_path)?;
+        let link_updates = if update_links {
+          self.plan_link_updates(&source, &dest)?
+        } else {
+          Vec::new()
+        };
+
+        self.move_item(&source, &dest)?;
+        undo.push(Undo::MoveBack {
+          from: dest,
+          to: source,
+        });
+        self.invalidate_doc_tree();
+        self.apply_batch_link_updates(&link_updates, undo)?;
+        Ok(link_updates)
+      }
+      BatchOperation::Rename {
+        file_path,
+        name,
+        is_file,
+        update_links,
+      } => {
+        let (cur_path, new_path) = self.rename_target(&file_path, &name, is_file)?;
+        if !cur_path.exists() {
+          return Err(anyhow::anyhow!("The path {} does not exist.", file_path));
+        }
+        if new_path == cur_path {
+          return Ok(Vec::new());
+        }
+        ensure_free(&new_path, &name)?;
+        let link_updates = if update_links {
+          self.plan_link_updates(&cur_path, &new_path)?
+        } else {
+          Vec::new()
+        };
+
+        self.rename_item(&cur_path, &new_path)?;
+        undo.push(Undo::RenameBack {
+          from: new_path,
+          to: cur_path,
+        });
+        self.invalidate_doc_tree();
+        self.apply_batch_link_updates(&link_updates, undo)?;
+        Ok(link_updates)
+      }
+      BatchOperation::Delete { file_path, is_file } => {
+        let item = self.delete_doc(&file_path, is_file)?;
+        undo.push(Undo::Untrash(item.id));
+        Ok(Vec::new())
+      }
+    }
+  }
+
+  /// Writes the link updates of a move one by one, so a failure only undoes the written ones.
+  fn apply_batch_link_updates(
+    &self,
+    link_updates: &[LinkUpdate],
+    undo: &mut Vec<Undo>,
+  ) -> Result<(), anyhow::Error> {
+    for update in link_updates {
+      self.apply_link_updates(std::slice::from_ref(update))?;
+      undo.push(Undo::RestoreContent {
+        file_path: update.file_path.clone(),
+        content: update.previous_content.clone(),
+      });
+    }
+    Ok(())
+  }
+
+  fn undo_batch_step(&self, step: Undo) -> Result<(), anyhow::Error> {
+    match step {
+      Undo::Remove(path) => match fs::symlink_metadata(&path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(&path)?,
+        Ok(_) => fs::remove_file(&path)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+      },
+      Undo::MoveBack { from, to } => self.move_item(&from, &to)?,
+      Undo::RenameBack { from, to } => self.rename_item(&from, &to)?,
+      Undo::RestoreContent { file_path, content } => {
+        self.write_article(&file_path, &content)?;
+      }
+      Undo::Untrash(id) => {
+        self
+          .trash_service
+          .restore(&id, RestoreConflictStrategy::Fail)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn ensure_free(path: &Path, doc_path: &str) -> Result<(), anyhow::Error> {
+  if fs::symlink_metadata(path).is_ok() {
+    return Err(anyhow::anyhow!("The path {} already exists.", doc_path));
+  }
+  Ok(())
+}
+
+/// The outermost folder (or the path itself) that creating `path` brings into existence.
+fn first_missing_ancestor(path: &Path) -> PathBuf {
+  let mut created = path.to_path_buf();
+  while let Some(parent) = created.parent() {
+    if parent.exists() {
+      break;
+    }
+    created = parent.to_path_buf();
+  }
+  created
+}
@@ -0,0 +1,408 @@
+use std::{
+  fs::File,
+  io::{BufRead, BufReader},
+  path::Path,
+};
+
+use serde_json::{Map, Value};
+
+/// Stop looking for the closing fence after this many lines when reading a file header.
+const MAX_FRONT_MATTER_LINES: usize = 200;
+
+/// Byte offsets of a `---` front matter block inside a document.
+struct FrontMatterBlock {
+  /// Start of the YAML text, right after the opening fence line.
+  yaml_start: usize,
+  /// End of the YAML text, right before the closing fence line.
+  yaml_end: usize,
+  line_ending: &'static str,
+  /// The parsed YAML mapping.
+  mapping: Value,
+}
+
+/// Parses the YAML front matter of a document into a JSON object.
+/// Returns `None` when there is no front matter or it is not a valid YAML mapping.
+///
+/// # Example
+/// ```
+/// use server::services::doc::front_matter::parse_front_matter;
+///
+/// let content = "---\ntitle: Notes\ntags: [rust]\n---\n# Notes\n";
+/// let front_matter = parse_front_matter(content).unwrap();
+/// assert_eq!(front_matter["title"], "Notes");
+/// assert_eq!(front_matter["tags"][0], "rust");
+/// ```
+pub fn parse_front_matter(content: &str) -> Option<Value> {
+  Some(find_block(content)?.mapping)
+}
+
+/// Reads only the head of a file to parse its front matter, used for listings.
+pub fn read_front_matter(path: &Path) -> Option<Value> {
+  let file = File::open(path).ok()?;
+  let mut head = String::new();
+
+  for (index, line) in BufReader::new(file).lines().enumerate() {
+    let line = line.ok()?;
+    if index == 0 && line.trim_end() != "---" {
+      return None;
+    }
+    if index >= MAX_FRONT_MATTER_LINES {
+      return None;
+    }
+
+    head.push_str(&line);
+    head.push('\n');
+    if index > 0 && matches!(line.trim_end(), "---" | "...") {
+      return parse_front_matter(&head);
+    }
+  }
+
+  None
+}
+
+/// Updates individual front matter fields and returns the new document content.
+/// A `null` value removes the key. Every byte outside of the touched keys (other keys,
+/// comments, their order and the whole body) is kept as is. A front matter block is
+/// created when the document does not have one.
+///
+/// # Example
+/// ```
+/// use server::services::doc::front_matter::update_front_matter;
+/// use serde_json::json;
+///
+/// let content = "---\ntitle: Old\nstatus: draft\n---\nBody\n";
+/// let fields = json!({ "title": "New", "status": null });
+/// let updated = update_front_matter(content, fields.as_object().unwrap()).unwrap();
+/// assert_eq!(updated, "---\ntitle: New\n---\nBody\n");
+/// ```
+pub fn update_front_matter(
+  content: &str,
+  fields: &Map<String, Value>,
+) -> Result<String, anyhow::Error> {
+  let Some(block) = find_block(content) else {
+    let line_ending = if content.contains("\r\n") {
+      "\r\n"
+    } else {
+      "\n"
+    };
+    let mut entries = String::new();
+    for (key, value) in fields.iter().filter(|(_, value)| !value.is_null()) {
+      entries.push_str(&yaml_entry(key, value)?);
+      entries.push_str(line_ending);
+    }
+    if entries.is_empty() {
+      return Ok(content.to_string());
+    }
+    return Ok(format!(
+      "---{le}{}---{le}{}",
+      entries,
+      content,
+      le = line_ending
+    ));
+  };
+
+  let yaml = &content[block.yaml_start..block.yaml_end];
+  let mut lines: Vec<String> = yaml.split_inclusive('\n').map(String::from).collect();
+
+  for (key, value) in fields {
+    match find_key_range(&lines, key) {
+      Some((start, end)) if value.is_null() => {
+        lines.drain(start..end);
+      }
+      Some((start, end)) => {
+        let line_ending = line_ending_of(&lines[end - 1]).unwrap_or(block.line_ending);
+        let entry = format!("{}{}", yaml_entry(key, value)?, line_ending);
+        lines.splice(start..end, [entry]);
+      }
+      None if value.is_null() => {}
+      None => {
+        if let Some(last) = lines.last_mut()
+          && line_ending_of(last).is_none()
+        {
+          last.push_str(block.line_ending);
+        }
+        lines.push(format!("{}{}", yaml_entry(key, value)?, block.line_ending));
+      }
+    }
+  }
+
+  let new_yaml = lines.concat();
+  if parse_yaml_mapping(&new_yaml).is_none() {
+    return Err(anyhow::anyhow!(
+      "The front matter is not a valid YAML mapping after the update."
+    ));
+  }
+
+  Ok(format!(
+    "{}{}{}",
+    &content[..block.yaml_start],
+    new_yaml,
+    &content[block.yaml_end..]
+  ))
+}
+
+/// A leading `---` is front matter only when it is closed and holds a YAML mapping,
+/// otherwise it is a thematic break of the body.
+fn find_block(content: &str) -> Option<FrontMatterBlock> {
+  let (yaml_start, line_ending) = if content.starts_with("---\r\n") {
+    (5, "\r\n")
+  } else if content.starts_with("---\n") {
+    (4, "\n")
+  } else {
+    return None;
+  };
+
+  let mut offset = yaml_start;
+  for line in content[yaml_start..].split_inclusive('\n') {
+    if matches!(line.trim_end_matches(['\r', '\n']), "---" | "...") {
+      return Some(FrontMatterBlock {
+        yaml_start,
+        yaml_end: offset,
+        line_ending,
+        mapping: parse_yaml_mapping(&content[yaml_start..offset])?,
+      });
+    }
+    offset += line.len();
+  }
+
+  None
+}
+
+fn parse_yaml_mapping(yaml: &str) -> Option<Value> {
+  if yaml.trim().is_empty() {
+    return Some(Value::Object(Map::new()));
+  }
+
+  match serde_norway::from_str::<Value>(yaml) {
+    Ok(value @ Value::Object(_)) => Some(value),
+    Ok(Value::Null) => Some(Value::Object(Map::new())),
+    Ok(_) => None,
+    Err(e) => {
+      tracing::warn!("[DocService] invalid front matter: {}", e);
+      None
+    }
+  }
+}
+
+/// Finds the lines `[start, end)` of a top level key, including its nested or list lines.
+fn find_key_range(lines: &[String], key: &str) -> Option<(usize, usize)> {
+  let start = lines.iter().position(|line| line_has_key(line, key))?;
+
+  let mut end = start + 1;
+  let mut candidate_end = end;
+  while candidate_end < lines.len() {
+    let line = lines[candidate_end].trim_end_matches(['\r', '\n']);
+    if line.trim().is_empty() {
+      // blank lines only belong to the value if more of it follows
+      candidate_end += 1;
+      continue;
+    }
+    if line.starts_with([' ', '\t']) || line == "-" || line.starts_with("- ") {
+      candidate_end += 1;
+      end = candidate_end;
+      continue;
+    }
+    break;
+  }
+
+  Some((start, end))
+}
+
+fn line_has_key(line: &str, key: &str) -> bool {
+  [
+    key.to_string(),
+    format!("\"{}\"", key),
+    format!("'{}'", key),
+  ]
+  .iter()
+  .any(|candidate| {
+    line
+      .strip_prefix(candidate.as_str())
+      .map(|rest| rest.trim_start_matches([' ', '\t']).starts_with(':'))
+      .unwrap_or(false)
+  })
+}
+
+fn line_ending_of(line: &str) -> Option<&'static str> {
+  if line.ends_with("\r\n") {
+    Some("\r\n")
+  } else if line.ends_with('\n') {
+    Some("\n")
+  } else {
+    None
+  }
+}
+
+/// Formats `key: value` as a single YAML line. Non scalar values use the JSON flow style,
+/// which is valid YAML as well.
+fn yaml_entry(key: &str, value: &Value) -> Result<String, anyhow::Error> {
+  let key = if !key.is_empty()
+    && key
+      .chars()
+      .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+  {
+    key.to_string()
+  } else {
+    serde_json::to_string(key)?
+  };
+
+  let value = match value {
+    Value::String(s) if is_plain_scalar(s) => s.clone(),
+    _ => serde_json::to_string(value)?,
+  };
+
+  Ok(format!("{}: {}", key, value))
+}
+
+/// Whether a string can be written unquoted and still reads back as the same string.
+fn is_plain_scalar(value: &str) -> bool {
+  if value.is_empty()
+    || value.trim() != value
+    || value.starts_with([
+      '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+      '`',
+    ])
+    || value.contains(": ")
+    || value.contains(" #")
+    || value.chars().any(|c| c.is_control())
+  {
+    return false;
+  }
+
+  matches!(serde_norway::from_str::<Value>(value), Ok(Value::String(s)) if s == value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn update(content: &str, fields: Value) -> String {
+    update_front_matter(content, fields.as_object().unwrap()).unwrap()
+  }
+
+  #[test]
+  fn test_parse_front_matter() {
+    let content =
+      "---\ntitle: Notes\ntags:\n  - rust\n  - web\ncreated: 2026-10-18\nstatus: draft\n---\nBody";
+    let front_matter = parse_front_matter(content).unwrap();
+    assert_eq!(
+      front_matter,
+      json!({
+        "title": "Notes",
+        "tags": ["rust", "web"],
+        "created": "2026-10-18",
+        "status": "draft",
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_without_front_matter() {
+    assert_eq!(parse_front_matter("# Title\n---\n"), None);
+    assert_eq!(parse_front_matter("---\nunclosed: true\n"), None);
+    assert_eq!(parse_front_matter("---\n- not\n- a map\n---\n"), None);
+    assert_eq!(parse_front_matter("---\n---\nBody"), Some(json!({})));
+  }
+
+  #[test]
+  fn test_thematic_break_is_not_front_matter() {
+    let content = "---\n\nIntro paragraph.\n\n---\nBody\n";
+    assert_eq!(parse_front_matter(content), None);
+    assert_eq!(
+      update(content, json!({ "status": "done" })),
+      format!("---\nstatus: done\n---\n{}", content)
+    );
+  }
+
+  #[test]
+  fn test_parse_crlf_front_matter() {
+    let content = "---\r\ntitle: Windows\r\n---\r\nBody\r\n";
+    assert_eq!(
+      parse_front_matter(content),
+      Some(json!({ "title": "Windows" }))
+    );
+  }
+
+  #[test]
+  fn test_read_front_matter_from_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let with = temp_dir.path().join("with.md");
+    let without = temp_dir.path().join("without.md");
+    std::fs::write(&with, "---\nstatus: done\n---\n# Body").unwrap();
+    std::fs::write(&without, "# Body\n---\n").unwrap();
+
+    assert_eq!(read_front_matter(&with), Some(json!({ "status": "done" })));
+    assert_eq!(read_front_matter(&without), None);
+  }
+
+  #[test]
+  fn test_update_preserves_other_keys_and_body() {
+    let content = "---\n# comment\ntitle: Old   \ntags:\n  - a\n  - b\n\nstatus: draft # inline\n---\n\n# Body\n\n---\nnot front matter: true\n";
+    let updated = update(content, json!({ "tags": ["x", "y z"] }));
+    assert_eq!(
+      updated,
+      "---\n# comment\ntitle: Old   \ntags: [\"x\",\"y z\"]\n\nstatus: draft # inline\n---\n\n# Body\n\n---\nnot front matter: true\n"
+    );
+    assert_eq!(
+      parse_front_matter(&updated).unwrap()["tags"],
+      json!(["x", "y z"])
+    );
+  }
+
+  #[test]
+  fn test_update_adds_and_removes_keys() {
+    let content = "---\ntitle: Old\nstatus: draft\n---\nBody";
+    let updated = update(content, json!({ "status": null, "created": "2026-10-18" }));
+    assert_eq!(updated, "---\ntitle: Old\ncreated: 2026-10-18\n---\nBody");
+  }
+
+  #[test]
+  fn test_update_creates_front_matter() {
+    let content = "# Body\r\n\r\ntext";
+    let updated = update(content, json!({ "title": "New" }));
+    assert_eq!(updated, "---\r\ntitle: New\r\n---\r\n# Body\r\n\r\ntext");
+
+    // removing from a document without front matter is a no-op
+    assert_eq!(update(content, json!({ "title": null })), content);
+  }
+
+  #[test]
+  fn test_update_keeps_crlf() {
+    let content = "---\r\ntitle: Old\r\nstatus: draft\r\n---\r\nBody\r\n";
+    let updated = update(content, json!({ "title": "New", "done": true }));
+    assert_eq!(
+      updated,
+      "---\r\ntitle: New\r\nstatus: draft\r\ndone: true\r\n---\r\nBody\r\n"
+    );
+  }
+
+  #[test]
+  fn test_update_quotes_ambiguous_strings() {
+    let content = "---\n---\n";
+    let updated = update(
+      content,
+      json!({ "a": "true", "b": "123", "c": "key: value", "d": "", "e": "#tag", "f": "plain text" }),
+    );
+    let front_matter = parse_front_matter(&updated).unwrap();
+    assert_eq!(
+      front_matter,
+      json!({ "a": "true", "b": "123", "c": "key: value", "d": "", "e": "#tag", "f": "plain text" })
+    );
+    assert!(updated.contains("f: plain text\n"));
+    assert!(updated.contains("a: \"true\"\n"));
+  }
+
+  #[test]
+  fn test_update_nested_values_and_quoted_keys() {
+    let content = "---\n\"my key\": 1\nmeta:\n  nested: true\nnext: 2\n---\n";
+    let updated = update(
+      content,
+      json!({ "my key": 2, "meta": { "nested": false, "list": [1] } }),
+    );
+    assert_eq!(
+      updated,
+      "---\n\"my key\": 2\nmeta: {\"list\":[1],\"nested\":false}\nnext: 2\n---\n"
+    );
+  }
+}
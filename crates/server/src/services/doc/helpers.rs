@@ -1,6 +1,10 @@
 use percent_encoding::{AsciiSet, CONTROLS};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 use std::fs;
+use std::iter::Peekable;
 use std::path::Path;
+use std::str::Chars;
 
 /// ASCII set that matches JavaScript's `encodeURIComponent` behavior.
 /// Encodes everything except unreserved characters: A-Z, a-z, 0-9, -, ., _, ~
@@ -86,6 +90,105 @@ pub fn copy_dir_all(src: &Path, dst: &Path) -> Result<(), anyhow::Error> {
   Ok(())
 }
 
+/// Extension used for new docs and left out of doc paths, the first configured one.
+pub fn default_doc_extension(extensions: &[String]) -> &str {
+  extensions
+    .first()
+    .map(|ext| ext.trim_start_matches('.'))
+    .unwrap_or("md")
+}
+
+/// Name of a doc file in doc paths, `None` if the file isn't a doc. Files with the
+/// default extension are named without it, other doc files keep their extension so
+/// `note.md` and `note.txt` don't collide.
+///
+/// # Example
+/// ```
+/// use server::services::doc::helpers::doc_name;
+///
+/// let extensions = vec!["md".to_string(), ".txt".to_string()];
+/// assert_eq!(doc_name("array.md", &extensions).as_deref(), Some("array"));
+/// assert_eq!(doc_name("todo.TXT", &extensions).as_deref(), Some("todo.TXT"));
+/// assert_eq!(doc_name("logo.png", &extensions), None);
+/// ```
+pub fn doc_name(file_name: &str, extensions: &[String]) -> Option<String> {
+  let (stem, ext) = file_name.rsplit_once('.')?;
+  if stem.is_empty() {
+    return None;
+  }
+
+  let position = extensions
+    .iter()
+    .position(|doc_ext| doc_ext.trim_start_matches('.').eq_ignore_ascii_case(ext))?;
+  if position == 0 {
+    Some(stem.to_string())
+  } else {
+    Some(file_name.to_string())
+  }
+}
+
+/// Compares names the way people read them: case-insensitively, with runs of digits
+/// compared by their numeric value.
+///
+/// # Example
+/// ```
+/// use server::services::doc::helpers::natural_cmp;
+///
+/// let mut names = vec!["10-intro", "2-setup", "Appendix", "1-basics", "appendix 2"];
+/// names.sort_by(|a, b| natural_cmp(a, b));
+/// assert_eq!(names, vec!["1-basics", "2-setup", "10-intro", "Appendix", "appendix 2"]);
+/// ```
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+  let (mut a_chars, mut b_chars) = (a.chars().peekable(), b.chars().peekable());
+  loop {
+    let ordering = match (a_chars.peek().copied(), b_chars.peek().copied()) {
+      (None, None) => break,
+      (None, Some(_)) => return Ordering::Less,
+      (Some(_), None) => return Ordering::Greater,
+      (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+        let x_digits = take_digits(&mut a_chars);
+        let y_digits = take_digits(&mut b_chars);
+        let (x_number, y_number) = (
+          x_digits.trim_start_matches('0'),
+          y_digits.trim_start_matches('0'),
+        );
+        x_number
+          .len()
+          .cmp(&y_number.len())
+          .then_with(|| x_number.cmp(y_number))
+      }
+      (Some(x), Some(y)) => {
+        a_chars.next();
+        b_chars.next();
+        x.to_lowercase().cmp(y.to_lowercase())
+      }
+    };
+    if ordering != Ordering::Equal {
+      return ordering;
+    }
+  }
+  // same apart from case or leading zeros, still a total order
+  a.cmp(b)
+}
+
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+  let mut digits = String::new();
+  while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+    digits.push(c);
+  }
+  digits
+}
+
+/// Version of an article's content, a hex encoded SHA-256 digest. Unlike the mtime it
+/// doesn't change when a file is touched without modification and can't collide within
+/// the timestamp resolution of the filesystem.
+pub fn content_version(content: &[u8]) -> String {
+  Sha256::digest(content)
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -129,6 +232,15 @@ mod tests {
     assert_eq!(original, denormalized);
   }
 
+  #[test]
+  fn test_natural_cmp_numbers() {
+    assert_eq!(natural_cmp("chapter 2", "chapter 10"), Ordering::Less);
+    assert_eq!(natural_cmp("v1.10", "v1.9"), Ordering::Greater);
+    assert_eq!(natural_cmp("007", "7"), Ordering::Less);
+    assert_eq!(natural_cmp("a2b", "a2"), Ordering::Greater);
+    assert_eq!(natural_cmp("Note", "note"), Ordering::Less);
+  }
+
   #[test]
   fn test_normalize_path_matches_encodeuricomponent_behavior() {
     // Test cases that verify we match JavaScript's encodeURIComponent
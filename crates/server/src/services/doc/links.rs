@@ -0,0 +1,365 @@
+use std::{collections::HashMap, ops::Range};
+
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+use pulldown_cmark::{Event, Parser, Tag};
+
+use super::{
+  helpers::{doc_name, normalize_path},
+  markdown::parser_options,
+};
+
+/// Characters that can't appear raw in a markdown link destination.
+const LINK_PATH_ENCODE: &AsciiSet = &CONTROLS
+  .add(b' ')
+  .add(b'"')
+  .add(b'#')
+  .add(b'%')
+  .add(b'(')
+  .add(b')')
+  .add(b'<')
+  .add(b'>')
+  .add(b'?');
+
+/// A link or image destination found in a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownLink {
+  /// Destination as written in the source, e.g. `../js/array.md#map`.
+  pub dest: String,
+  /// Byte range of the raw destination in the source.
+  pub range: Range<usize>,
+  /// 1-based line of the destination.
+  pub line: usize,
+  pub is_image: bool,
+}
+
+/// A link destination resolved against the workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedLink {
+  /// Target path segments relative to the workspace root, e.g. `["js", "array.md"]`.
+  pub target: Vec<String>,
+  /// The link starts with `/`, i.e. it is relative to the workspace root.
+  pub root_relative: bool,
+  /// Trailing `?query` and/or `#fragment`, including the leading character.
+  pub suffix: String,
+}
+
+/// A link from one doc to another, as stored in the backlinks index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLink {
+  /// Normalized path of the linked doc, e.g. `js%2Farray`.
+  pub target: String,
+  pub line: usize,
+  /// The trimmed source line containing the link.
+  pub context: String,
+}
+
+/// Outgoing doc links keyed by the normalized path of the linking doc.
+pub type LinkIndex = HashMap<String, Vec<DocLink>>;
+
+/// Collects the links of the doc at `doc` (path segments relative to the workspace root)
+/// that point to other docs, with one of the doc `extensions` or without extension.
+///
+/// # Example
+/// ```
+/// use server::services::doc::links::doc_links;
+///
+/// let doc = vec!["js".to_string(), "array.md".to_string()];
+/// let extensions = vec!["md".to_string()];
+/// let content = "# Array\n\nSee [map](./map.md#usage) and ![x](x.png)\n";
+/// let links = doc_links(&doc, content, &extensions);
+/// assert_eq!(links.len(), 1);
+/// assert_eq!(links[0].target, "js%2Fmap");
+/// assert_eq!(links[0].context, "See [map](./map.md#usage) and ![x](x.png)");
+/// ```
+pub fn doc_links(doc: &[String], content: &str, extensions: &[String]) -> Vec<DocLink> {
+  let lines: Vec<&str> = content.lines().collect();
+
+  extract_links(content)
+    .into_iter()
+    .filter(|link| !link.is_image)
+    .filter_map(|link| {
+      let mut target = resolve_link(doc, &link.dest)?.target;
+      let last = target.last_mut()?;
+      if let Some(name) = doc_name(last, extensions) {
+        *last = name;
+      } else if last.contains('.') {
+        // not a doc
+        return None;
+      }
+
+      Some(DocLink {
+        target: normalize_path(&target),
+        line: link.line,
+        context: lines
+          .get(link.line - 1)
+          .map(|line| line.trim().to_string())
+          .unwrap_or_default(),
+      })
+    })
+    .collect()
+}
+
+/// Finds inline, reference style and image links of a markdown document. Destinations
+/// that can't be located in the source (e.g. with escapes) are skipped.
+///
+/// # Example
+/// ```
+/// use server::services::doc::links::extract_links;
+///
+/// let links = extract_links("See [array](../js/array.md#map).\n\n[ref]: ./ref.md\n");
+/// assert_eq!(links[0].dest, "../js/array.md#map");
+/// assert_eq!(links[1].dest, "./ref.md");
+/// assert_eq!(links[1].line, 3);
+/// ```
+pub fn extract_links(content: &str) -> Vec<MarkdownLink> {
+  let parser = Parser::new_ext(content, parser_options());
+  let mut links = Vec::new();
+
+  for (_, def) in parser.reference_definitions().iter() {
+    if let Some(range) = locate_dest(content, def.span.clone(), &def.dest) {
+      links.push(new_link(content, range, false));
+    }
+  }
+
+  for (event, range) in parser.into_offset_iter() {
+    let (dest_url, is_image, link_type) = match &event {
+      Event::Start(Tag::Link {
+        dest_url,
+        link_type,
+        ..
+      }) => (dest_url, false, link_type),
+      Event::Start(Tag::Image {
+        dest_url,
+        link_type,
+        ..
+      }) => (dest_url, true, link_type),
+      _ => continue,
+    };
+    // reference links point at a definition, which is collected above
+    if matches!(
+      link_type,
+      pulldown_cmark::LinkType::Reference
+        | pulldown_cmark::LinkType::Collapsed
+        | pulldown_cmark::LinkType::Shortcut
+    ) {
+      continue;
+    }
+    if let Some(range) = locate_dest(content, range, dest_url) {
+      links.push(new_link(content, range, is_image));
+    }
+  }
+
+  links.sort_by_key(|link| link.range.start);
+  links.dedup_by_key(|link| link.range.start);
+  links
+}
+
+/// Resolves a link of the document at `doc` (path segments relative to the workspace
+/// root, e.g. `["js", "array.md"]`). External URLs, pure `#anchor` links and paths
+/// escaping the workspace resolve to `None`.
+///
+/// # Example
+/// ```
+/// use server::services::doc::links::resolve_link;
+///
+/// let doc = vec!["js".to_string(), "array.md".to_string()];
+/// let link = resolve_link(&doc, "../css/flex%20box.md#grow").unwrap();
+/// assert_eq!(link.target, vec!["css", "flex box.md"]);
+/// assert_eq!(link.suffix, "#grow");
+/// assert!(resolve_link(&doc, "https://example.com").is_none());
+/// ```
+pub fn resolve_link(doc: &[String], dest: &str) -> Option<ResolvedLink> {
+  let dest = dest.trim();
+  if dest.is_empty() || dest.starts_with('#') || dest.starts_with("//") || has_scheme(dest) {
+    return None;
+  }
+
+  let suffix_start = dest.find(['?', '#']).unwrap_or(dest.len());
+  let (path, suffix) = dest.split_at(suffix_start);
+  let path = percent_decode_str(path).decode_utf8().ok()?;
+  let root_relative = path.starts_with('/');
+
+  let mut target: Vec<String> = if root_relative {
+    Vec::new()
+  } else {
+    doc[..doc.len().saturating_sub(1)].to_vec()
+  };
+  for segment in path.split('/') {
+    match segment {
+      "" | "." => {}
+      ".." => {
+        target.pop()?;
+      }
+      segment => target.push(segment.to_string()),
+    }
+  }
+  if target.is_empty() {
+    return None;
+  }
+
+  Some(ResolvedLink {
+    target,
+    root_relative,
+    suffix: suffix.to_string(),
+  })
+}
+
+/// Builds the destination pointing from the document at `doc` to `target`, in the same
+/// style (root-relative, `./` prefix, percent encoding) as the `original` destination.
+pub fn format_link(
+  doc: &[String],
+  target: &[String],
+  original: &str,
+  root_relative: bool,
+) -> String {
+  let encode = original.contains('%')
+    || target
+      .iter()
+      .any(|segment| segment.contains([' ', '(', ')', '<', '>']));
+  let segments: Vec<String> = target
+    .iter()
+    .map(|segment| {
+      if encode {
+        utf8_percent_encode(segment, LINK_PATH_ENCODE).to_string()
+      } else {
+        segment.clone()
+      }
+    })
+    .collect();
+
+  if root_relative {
+    return format!("/{}", segments.join("/"));
+  }
+
+  let doc_dir = &doc[..doc.len().saturating_sub(1)];
+  let common = doc_dir
+    .iter()
+    .zip(target.iter())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let mut parts: Vec<String> = vec!["..".to_string(); doc_dir.len() - common];
+  parts.extend(segments[common..].iter().cloned());
+
+  let link = parts.join("/");
+  if original.starts_with("./") && !link.starts_with("..") {
+    format!("./{}", link)
+  } else {
+    link
+  }
+}
+
+/// 1-based line number of a byte offset.
+pub fn line_of(content: &str, offset: usize) -> usize {
+  content[..offset].matches('\n').count() + 1
+}
+
+fn new_link(content: &str, range: Range<usize>, is_image: bool) -> MarkdownLink {
+  MarkdownLink {
+    dest: content[range.clone()].to_string(),
+    line: line_of(content, range.start),
+    range,
+    is_image,
+  }
+}
+
+/// Finds the raw destination inside the source span of a link. The destination follows
+/// the link text, so the last occurrence is taken.
+fn locate_dest(content: &str, span: Range<usize>, dest: &str) -> Option<Range<usize>> {
+  if dest.is_empty() {
+    return None;
+  }
+  let span_text = content.get(span.clone())?;
+  let offset = span_text.rfind(dest)?;
+  let start = span.start + offset;
+  Some(start..start + dest.len())
+}
+
+fn has_scheme(dest: &str) -> bool {
+  let Some(colon) = dest.find(':') else {
+    return false;
+  };
+  let scheme = &dest[..colon];
+  scheme
+    .chars()
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic())
+    && scheme
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn segments(path: &str) -> Vec<String> {
+    path.split('/').map(String::from).collect()
+  }
+
+  #[test]
+  fn test_extract_links_kinds() {
+    let content = "# T\n\n[a](a.md) ![img](../_assets/x.png \"title\")\n\n`[code](no.md)`\n\n[r][ref]\n\n[ref]: <./with space.md>\n";
+    let links = extract_links(content);
+    let dests: Vec<(&str, bool)> = links
+      .iter()
+      .map(|link| (link.dest.as_str(), link.is_image))
+      .collect();
+
+    assert_eq!(
+      dests,
+      vec![
+        ("a.md", false),
+        ("../_assets/x.png", true),
+        ("./with space.md", false),
+      ]
+    );
+    for link in &links {
+      assert_eq!(&content[link.range.clone()], link.dest);
+    }
+  }
+
+  #[test]
+  fn test_link_text_matching_dest() {
+    let content = "[b.md](b.md)";
+    let links = extract_links(content);
+    assert_eq!(links[0].range, 7..11);
+  }
+
+  #[test]
+  fn test_resolve_link() {
+    let doc = segments("js/basic/array.md");
+    assert_eq!(
+      resolve_link(&doc, "./map.md").unwrap().target,
+      segments("js/basic/map.md")
+    );
+    assert_eq!(
+      resolve_link(&doc, "/css/flex.md?x=1#top").unwrap(),
+      ResolvedLink {
+        target: segments("css/flex.md"),
+        root_relative: true,
+        suffix: "?x=1#top".to_string(),
+      }
+    );
+    assert!(resolve_link(&doc, "../../../outside.md").is_none());
+    assert!(resolve_link(&doc, "#anchor").is_none());
+    assert!(resolve_link(&doc, "mailto:me@example.com").is_none());
+    assert!(resolve_link(&doc, "//cdn.example.com/x.png").is_none());
+  }
+
+  #[test]
+  fn test_format_link() {
+    let doc = segments("js/basic/array.md");
+    assert_eq!(
+      format_link(&doc, &segments("js/basic/map.md"), "./old.md", false),
+      "./map.md"
+    );
+    assert_eq!(
+      format_link(&doc, &segments("css/flex box.md"), "x.md", false),
+      "../../css/flex%20box.md"
+    );
+    assert_eq!(
+      format_link(&doc, &segments("css/flex.md"), "/old.md", true),
+      "/css/flex.md"
+    );
+  }
+}
@@ -0,0 +1,277 @@
+use pulldown_cmark::{Event, MetadataBlockKind, Options, Parser, Tag, TagEnd};
+use serde_json::Value;
+
+use super::{front_matter::parse_front_matter, structs::Heading};
+
+/// Front matter keys whose values are used as article keywords, in priority order.
+const KEYWORD_KEYS: &[&str] = &["keywords", "tags"];
+
+/// Structure computed from a markdown document.
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedMarkdown {
+  pub headings: Vec<Heading>,
+  pub keywords: Vec<String>,
+}
+
+/// Parser options shared by every server side markdown pass.
+pub fn parser_options() -> Options {
+  Options::ENABLE_TABLES
+    | Options::ENABLE_STRIKETHROUGH
+    | Options::ENABLE_TASKLISTS
+    | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+}
+
+/// Parses ATX/setext headings and keywords out of markdown content.
+/// Keywords come from the `keywords`/`tags` front matter keys, falling back to
+/// emphasized (`*em*`/`**strong**`) text when the front matter has none.
+///
+/// # Example
+/// ```
+/// use server::services::doc::markdown::parse_markdown;
+///
+/// let parsed = parse_markdown("# Hello World\n\nSome **rust** notes.\n\nSub\n---\n");
+/// assert_eq!(parsed.headings[0].id, "hello-world");
+/// assert_eq!(parsed.headings[1].level, 2);
+/// assert_eq!(parsed.keywords, vec!["rust"]);
+/// ```
+pub fn parse_markdown(content: &str) -> ParsedMarkdown {
+  let mut headings = Vec::new();
+  let mut emphasis_keywords: Vec<String> = Vec::new();
+
+  let mut current_heading: Option<(u8, String)> = None;
+  let mut emphasis_depth = 0;
+  let mut emphasis_text = String::new();
+  let mut in_metadata = false;
+
+  for event in Parser::new_ext(content, parser_options()) {
+    match event {
+      Event::Start(Tag::MetadataBlock(MetadataBlockKind::YamlStyle)) => in_metadata = true,
+      Event::End(TagEnd::MetadataBlock(_)) => in_metadata = false,
+      Event::Start(Tag::Heading { level, .. }) => {
+        current_heading = Some((level as u8, String::new()));
+      }
+      Event::End(TagEnd::Heading(_)) => {
+        if let Some((level, text)) = current_heading.take() {
+          let text = text.trim().to_string();
+          headings.push(Heading {
+            id: slugify(&text),
+            text,
+            level,
+          });
+        }
+      }
+      Event::Start(Tag::Emphasis | Tag::Strong) => emphasis_depth += 1,
+      Event::End(TagEnd::Emphasis | TagEnd::Strong) => {
+        emphasis_depth -= 1;
+        if emphasis_depth == 0 {
+          let keyword = emphasis_text.trim().to_string();
+          if !keyword.is_empty() && !emphasis_keywords.contains(&keyword) {
+            emphasis_keywords.push(keyword);
+          }
+          emphasis_text.clear();
+        }
+      }
+      Event::Text(_) if in_metadata => {}
+      Event::Text(text) | Event::Code(text) => {
+        if let Some((_, heading_text)) = current_heading.as_mut() {
+          heading_text.push_str(&text);
+        }
+        if emphasis_depth > 0 {
+          emphasis_text.push_str(&text);
+        }
+      }
+      Event::SoftBreak | Event::HardBreak => {
+        if let Some((_, heading_text)) = current_heading.as_mut() {
+          heading_text.push(' ');
+        }
+        if emphasis_depth > 0 {
+          emphasis_text.push(' ');
+        }
+      }
+      _ => {}
+    }
+  }
+
+  let front_matter_keywords = parse_front_matter(content).and_then(|front_matter| {
+    KEYWORD_KEYS
+      .iter()
+      .find_map(|key| front_matter_list(&front_matter[key]))
+  });
+
+  ParsedMarkdown {
+    headings,
+    keywords: front_matter_keywords
+      .filter(|keywords| !keywords.is_empty())
+      .unwrap_or(emphasis_keywords),
+  }
+}
+
+/// Counts the words of the text of markdown content, front matter and markup excluded.
+///
+/// # Example
+/// ```
+/// use server::services::doc::markdown::word_count;
+///
+/// assert_eq!(word_count("---\ntitle: Notes\n---\n# Hello World\n\nSome **rust** `code`.\n"), 5);
+/// ```
+pub fn word_count(content: &str) -> usize {
+  let mut count = 0;
+  let mut in_metadata = false;
+  // words split by inline markup, e.g. `un**believ**able`, are counted once
+  let mut in_word = false;
+
+  for event in Parser::new_ext(content, parser_options()) {
+    match event {
+      Event::Start(Tag::MetadataBlock(MetadataBlockKind::YamlStyle)) => in_metadata = true,
+      Event::End(TagEnd::MetadataBlock(_)) => in_metadata = false,
+      Event::Text(_) if in_metadata => {}
+      Event::Text(text) | Event::Code(text) => {
+        for c in text.chars() {
+          if c.is_whitespace() {
+            in_word = false;
+          } else if !in_word {
+            in_word = true;
+            count += 1;
+          }
+        }
+      }
+      Event::Start(Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. })
+      | Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link) => {}
+      _ => in_word = false,
+    }
+  }
+
+  count
+}
+
+/// Builds a heading anchor the same way the editor does: punctuation and symbols are
+/// dropped, whitespace becomes `-` and the result is lowercased.
+///
+/// # Example
+/// ```
+/// use server::services::doc::markdown::slugify;
+///
+/// assert_eq!(slugify("Hello, World!"), "hello-world");
+/// assert_eq!(slugify("Array.prototype.map()"), "arrayprototypemap");
+/// ```
+pub fn slugify(text: &str) -> String {
+  text
+    .trim()
+    .chars()
+    .filter_map(|c| {
+      if c.is_whitespace() {
+        Some('-')
+      } else if c.is_alphanumeric() {
+        Some(c)
+      } else {
+        None
+      }
+    })
+    .collect::<String>()
+    .to_lowercase()
+}
+
+/// Reads a front matter list value, either a YAML sequence (`tags: [a, b]`) or a
+/// comma separated string (`tags: a, b`).
+fn front_matter_list(value: &Value) -> Option<Vec<String>> {
+  let items: Vec<String> = match value {
+    Value::Array(items) => items
+      .iter()
+      .map(|item| match item {
+        Value::String(s) => s.trim().to_string(),
+        other => other.to_string(),
+      })
+      .collect(),
+    Value::String(s) => s.split(',').map(|item| item.trim().to_string()).collect(),
+    _ => return None,
+  };
+
+  Some(items.into_iter().filter(|item| !item.is_empty()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_word_count() {
+    assert_eq!(word_count(""), 0);
+    assert_eq!(word_count("un**believ**able [link text](https://a.b/c)"), 3);
+    assert_eq!(word_count("- one\n- two\n\n```js\nlet a = 1;\n```\n"), 6);
+    assert_eq!(word_count("---\ntags: [a, b]\n---\n"), 0);
+  }
+
+  #[test]
+  fn test_atx_and_setext_headings() {
+    let content = "# Title\n\nIntro\n\n## Section `code`\n\nSetext One\n==========\n\nSetext Two\n----------\n\n###### Deep ######\n";
+    let parsed = parse_markdown(content);
+    let headings: Vec<(&str, u8, &str)> = parsed
+      .headings
+      .iter()
+      .map(|h| (h.text.as_str(), h.level, h.id.as_str()))
+      .collect();
+
+    assert_eq!(
+      headings,
+      vec![
+        ("Title", 1, "title"),
+        ("Section code", 2, "section-code"),
+        ("Setext One", 1, "setext-one"),
+        ("Setext Two", 2, "setext-two"),
+        ("Deep", 6, "deep"),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_headings_ignore_code_blocks() {
+    let content = "```md\n# not a heading\n```\n\n    # indented code\n\n# Real\n";
+    let parsed = parse_markdown(content);
+    assert_eq!(parsed.headings.len(), 1);
+    assert_eq!(parsed.headings[0].text, "Real");
+  }
+
+  #[test]
+  fn test_slugify_unicode() {
+    assert_eq!(slugify("  Trim me  "), "trim-me");
+    assert_eq!(slugify("中文 标题"), "中文-标题");
+    assert_eq!(slugify("C++ & Rust"), "c--rust");
+  }
+
+  #[test]
+  fn test_keywords_from_emphasis() {
+    let content = "Use *ownership* and **borrowing**, not ***ownership***.\n\n**borrowing** again.";
+    let parsed = parse_markdown(content);
+    assert_eq!(parsed.keywords, vec!["ownership", "borrowing"]);
+  }
+
+  #[test]
+  fn test_keywords_from_front_matter_flow_list() {
+    let content =
+      "---\ntitle: Notes\nkeywords: [rust, \"async io\"]\n---\n\n# Notes\n\n**ignored**";
+    let parsed = parse_markdown(content);
+    assert_eq!(parsed.keywords, vec!["rust", "async io"]);
+    assert_eq!(parsed.headings.len(), 1);
+  }
+
+  #[test]
+  fn test_keywords_from_front_matter_block_list() {
+    let content = "---\ntags:\n  - rust\n  - 'web dev'\nstatus: draft\n---\n\ntext";
+    let parsed = parse_markdown(content);
+    assert_eq!(parsed.keywords, vec!["rust", "web dev"]);
+  }
+
+  #[test]
+  fn test_keywords_from_front_matter_string() {
+    let content = "---\nkeywords: rust, web\n---\n\ntext";
+    let parsed = parse_markdown(content);
+    assert_eq!(parsed.keywords, vec!["rust", "web"]);
+  }
+
+  #[test]
+  fn test_empty_front_matter_keywords_fall_back_to_emphasis() {
+    let content = "---\ntags: []\n---\n\n*fallback*";
+    let parsed = parse_markdown(content);
+    assert_eq!(parsed.keywords, vec!["fallback"]);
+  }
+}
@@ -1,37 +1,113 @@
+mod batch;
+pub mod front_matter;
 pub mod helpers;
+pub mod links;
+pub mod markdown;
 pub mod structs;
+pub mod templates;
 mod test;
+pub mod wiki_links;
 
-pub use helpers::{copy_dir_all, denormalize_path, normalize_path};
+pub use helpers::{content_version, copy_dir_all, denormalize_path, normalize_path};
+use helpers::{default_doc_extension, doc_name, natural_cmp};
 
 // Re-export all public types from structs
 pub use structs::{
-  Article, CopyCutDocRequest, CreateDocRequest, CreateFolderRequest, DeleteDocRequest, DocItem,
-  GetArticleQuery, GetDocSubTreeQuery, UpdateArticleRequest, UpdateDocNameRequest,
+  Article, ArticleConflict, Backlink, BatchItemResult, BatchItemStatus, BatchOperation,
+  BatchRequest, BatchResponse, CopyCutDocRequest, CreateDocRequest, CreateFolderRequest,
+  DeleteDocRequest, DocExistsConflict, DocItem, DocItemMetadata, DocTemplate, GetArticleQuery,
+  GetBacklinksQuery, GetDocSubTreeQuery, GetDocTreeQuery, GetWikiLinksQuery, Heading, LinkChange,
+  LinkUpdate, NormalizedDoc, NormalizedDocMap, PreviewLinkUpdatesRequest, ReorderDocItemsRequest,
+  UpdateArticleRequest, UpdateArticleResponse, UpdateDocNameRequest, UpdateFrontMatterRequest,
+  WikiLinkReport, WikiLinkResolveQuery,
 };
 
-use crate::services::settings::SettingsService;
-use std::{fs, path::PathBuf, sync::Arc};
+use crate::{
+  services::{
+    git::StatusType,
+    history::{HistoryService, HistoryVersion, HistoryVersionContent},
+    settings::{DocSortMode, SettingsService},
+    trash::{TrashItem, TrashService},
+  },
+  utils::{
+    atomic_write::{write_atomic, write_text_atomic},
+    workspace_path::WorkspacePath,
+  },
+};
+use front_matter::{parse_front_matter, read_front_matter, update_front_matter};
+use git2::{Repository, StatusOptions};
+use links::{LinkIndex, doc_links, extract_links, format_link, resolve_link};
+use markdown::{ParsedMarkdown, parse_markdown};
+use std::{
+  collections::{BTreeMap, HashMap, HashSet},
+  fs,
+  io::{self, Write},
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+  time::{SystemTime, UNIX_EPOCH},
+};
+use templates::{render_template, template_variables};
+use wiki_links::{WikiLinkResolution, parse_wiki_links, resolve_wiki_target};
 
 pub struct DocService {
   settings_service: Arc<SettingsService>,
+  trash_service: Arc<TrashService>,
+  history_service: Arc<HistoryService>,
+  /// Cached full doc tree of the current workspace, `None` until built or after invalidation.
+  doc_tree: Mutex<Option<NormalizedDocMap>>,
+  /// Cached outgoing links of every doc for backlinks, `None` until built or after invalidation.
+  link_index: Mutex<Option<LinkIndex>>,
+  /// Serializes the version check and the write of versioned saves.
+  write_lock: Mutex<()>,
 }
 
 const INTERNAL_IGNORE_DIRS: &[&str] = &["_assets"];
 
+/// Manual order of the folder items, hidden so the tree and search skip it.
+const DOC_ORDER_FILE: &str = ".doc-order.json";
+
 impl DocService {
   /// Creates a new `DocService` instance.
-  pub fn new(settings_service: Arc<SettingsService>) -> Self {
-    let service = Self { settings_service };
+  pub fn new(
+    settings_service: Arc<SettingsService>,
+    trash_service: Arc<TrashService>,
+    history_service: Arc<HistoryService>,
+  ) -> Self {
+    let service = Self {
+      settings_service,
+      trash_service,
+      history_service,
+      doc_tree: Mutex::new(None),
+      link_index: Mutex::new(None),
+      write_lock: Mutex::new(()),
+    };
 
     tracing::info!("[DocService] Docs initialized.");
     service
   }
 
+  /// A service with its own trash and history, for tests.
+  #[cfg(test)]
+  pub(crate) fn for_test(settings_service: Arc<SettingsService>) -> Self {
+    let trash_service = Arc::new(TrashService::new(settings_service.clone()));
+    let history_service = Arc::new(HistoryService::new(settings_service.clone()));
+    Self::new(settings_service, trash_service, history_service)
+  }
+
+  /// Lists the docs and folders of a folder. `with_metadata` adds the details of each
+  /// item: size, times, word count of files, child count of folders and git status of
+  /// workspace items, which takes a read of every item.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let items = doc_service.get_sub_doc_items("js", false, true)?;
+  /// let modified = items.iter().filter(|item| item.metadata.git_status.is_some());
+  /// ```
   pub fn get_sub_doc_items(
     &self,
     folder_doc_path: &str,
     home_root_dir: bool,
+    with_metadata: bool,
   ) -> Result<Vec<DocItem>, anyhow::Error> {
     tracing::info!(
       "get_sub_doc_items: {:?}, {:?}",
@@ -39,7 +115,6 @@ impl DocService {
       home_root_dir
     );
 
-    let doc_path = self.path_convertor(folder_doc_path, false)?;
     let root_dir = Self::get_root_dir();
 
     #[cfg(target_os = "windows")]
@@ -50,25 +125,22 @@ impl DocService {
       }
     }
 
-    let doc_root = self
-      .settings_service
-      .settings
-      .lock()
-      .unwrap()
-      .doc_root_path
-      .clone();
-
     let ab_doc_path = if !home_root_dir {
-      doc_root.join(doc_path)
+      self.path_convertor(folder_doc_path, false)?
     } else {
-      // recover back to folder_doc_path, since path_convertor will add doc_root_path prefix, we need to remove it and add home dir prefix instead
-      if doc_path.starts_with(&doc_root) {
-        root_dir.join(doc_path.strip_prefix(doc_root).unwrap())
-      } else {
-        // for windows folder_doc_path like "C:/" will be converted to "C:" by path_convertor
-        // and will not add the doc_root_path prefix, so just take folder_doc_path directly
-        root_dir.join(folder_doc_path)
+      // browsing the whole filesystem (folder selector), so resolve from the home root dir
+      // instead of the workspace sandbox. for windows folder_doc_path like "C:/" is kept as is
+      let path_parts = denormalize_path(folder_doc_path)
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<String>>();
+      if path_parts.iter().any(|p| p == "..") {
+        return Err(anyhow::anyhow!(
+          "The folder doc path {} is invalid.",
+          folder_doc_path
+        ));
       }
+      root_dir.join(path_parts.join("/"))
     };
     if !ab_doc_path.exists() {
       tracing::error!("The folder doc path {} does not exist.", folder_doc_path);
@@ -81,7 +153,20 @@ impl DocService {
     tracing::info!("ab_doc_path: {:?}", ab_doc_path,);
 
     let entries = fs::read_dir(&ab_doc_path)?;
-    let mut docs = Vec::new();
+    let (ignore_dirs, doc_extensions) = {
+      let settings = self.settings_service.settings.lock().unwrap();
+      (
+        settings.ignore_dirs.clone(),
+        settings.doc_extensions.clone(),
+      )
+    };
+    let git_statuses = if with_metadata && !home_root_dir {
+      Self::git_statuses(&ab_doc_path)
+    } else {
+      HashMap::new()
+    };
+    // with the metadata, for sorting by time
+    let mut docs: Vec<(DocItem, Option<fs::Metadata>)> = Vec::new();
     for entry in entries {
       let entry = entry?;
       let path = entry.path();
@@ -92,23 +177,14 @@ impl DocService {
       }
 
       let is_file = path.is_file();
-      let ignore_dirs = self
-        .settings_service
-        .settings
-        .lock()
-        .unwrap()
-        .ignore_dirs
-        .clone();
-      let is_valid_dir =
-        !INTERNAL_IGNORE_DIRS.contains(&name.as_str()) && !ignore_dirs.contains(&name);
+      let is_valid_dir = Self::is_listed_dir(&name, &ignore_dirs);
 
       if is_file {
-        if self.is_markdown(&name) {
+        if let Some(file_name) = doc_name(&name, &doc_extensions) {
           let mut file_path_parts = denormalize_path(folder_doc_path)
             .into_iter()
             .filter(|p| !p.is_empty())
             .collect::<Vec<String>>();
-          let file_name = name.strip_suffix(".md").unwrap_or(&name).to_string();
           file_path_parts.push(file_name.clone());
 
           // add home dir prefix to display for UI
@@ -116,14 +192,37 @@ impl DocService {
             file_path_parts.insert(0, root_dir.to_string_lossy().to_string());
           }
 
+          let fs_metadata = fs::metadata(&path).ok();
+          let mut metadata = DocItemMetadata::default();
+          // don't read arbitrary files when browsing outside of the workspace
+          let front_matter = if home_root_dir {
+            None
+          } else if with_metadata {
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            metadata.word_count = Some(markdown::word_count(&content));
+            parse_front_matter(&content)
+          } else if let Some(front_matter) =
+            self.cached_front_matter(&normalize_path(&file_path_parts))
+          {
+            front_matter
+          } else {
+            read_front_matter(&path)
+          };
+          if with_metadata {
+            Self::fill_fs_metadata(&mut metadata, fs_metadata.as_ref());
+            metadata.git_status = git_statuses.get(&name).copied();
+          }
+
           let doc = DocItem {
             id: format!("{}-{}", file_name, file_path_parts.join("-")),
             name: file_name,
             is_file: true,
             path: file_path_parts,
+            front_matter,
+            metadata,
           };
 
-          docs.push(doc);
+          docs.push((doc, fs_metadata));
         }
       } else if is_valid_dir {
         let mut dir_path_parts = denormalize_path(folder_doc_path)
@@ -137,21 +236,368 @@ impl DocService {
           dir_path_parts.insert(0, root_dir.to_string_lossy().to_string());
         }
 
+        let fs_metadata = fs::metadata(&path).ok();
+        let mut metadata = DocItemMetadata::default();
+        if with_metadata {
+          Self::fill_fs_metadata(&mut metadata, fs_metadata.as_ref());
+          metadata.child_count = Self::count_listed_children(&path, &ignore_dirs, &doc_extensions);
+          metadata.git_status = git_statuses.get(&name).copied();
+        }
+
         let doc = DocItem {
           id: format!("{}-{}", name, dir_path_parts.join("-")),
           name: name.clone(),
           is_file: false,
           path: dir_path_parts,
+          front_matter: None,
+          metadata,
         };
 
-        docs.push(doc);
+        docs.push((doc, fs_metadata));
+      }
+    }
+
+    // Sort: directories first, then files, both by the sort mode of the workspace
+    let sort_mode = self.settings_service.get_settings().sort_mode;
+    let manual_order = if !home_root_dir && sort_mode == DocSortMode::Manual {
+      self
+        .read_doc_order()?
+        .remove(&Self::doc_order_key(folder_doc_path))
+    } else {
+      None
+    };
+    Self::sort_doc_items(&mut docs, sort_mode, manual_order.as_deref());
+
+    Ok(docs.into_iter().map(|(doc, _)| doc).collect())
+  }
+
+  /// Saves the manual order of the items of a folder, used by the `manual` sort mode.
+  /// Items missing from `order` follow the ordered ones by name, an empty `order`
+  /// removes the saved order. Returns the items of the folder in their new order.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let order = vec!["intro".to_string(), "setup".to_string(), "appendix".to_string()];
+  /// let items = doc_service.reorder_doc_items("book", order)?;
+  /// ```
+  pub fn reorder_doc_items(
+    &self,
+    folder_doc_path: &str,
+    order: Vec<String>,
+  ) -> Result<Vec<DocItem>, anyhow::Error> {
+    if !self.doc_exists(folder_doc_path, false)? {
+      return Err(anyhow::anyhow!(
+        "The folder doc path {} does not exist.",
+        folder_doc_path
+      ));
+    }
+
+    {
+      let _guard = self.write_lock.lock().unwrap();
+      let mut doc_order = self.read_doc_order()?;
+      let key = Self::doc_order_key(folder_doc_path);
+      if order.is_empty() {
+        doc_order.remove(&key);
+      } else {
+        doc_order.insert(key, order);
+      }
+      self.write_doc_order(&doc_order)?;
+    }
+    self.invalidate_doc_tree();
+
+    self.get_sub_doc_items(folder_doc_path, false, false)
+  }
+
+  /// Manual orders of the workspace folders, keyed by the `/` separated folder path.
+  fn read_doc_order(&self) -> Result<HashMap<String, Vec<String>>, anyhow::Error> {
+    let order_file = self.path_convertor("", false)?.join(DOC_ORDER_FILE);
+    if !order_file.exists() {
+      return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(order_file)?)?)
+  }
+
+  fn write_doc_order(&self, doc_order: &HashMap<String, Vec<String>>) -> Result<(), anyhow::Error> {
+    let order_file = self.path_convertor("", false)?.join(DOC_ORDER_FILE);
+    // sorted keys keep the file diff friendly
+    let sorted: BTreeMap<&String, &Vec<String>> = doc_order.iter().collect();
+    write_atomic(&order_file, serde_json::to_string_pretty(&sorted)?)?;
+    Ok(())
+  }
+
+  fn doc_order_key(folder_doc_path: &str) -> String {
+    denormalize_path(folder_doc_path)
+      .into_iter()
+      .filter(|segment| !segment.is_empty())
+      .collect::<Vec<String>>()
+      .join("/")
+  }
+
+  /// Keeps a renamed item at its place in the manual order of its folder.
+  fn rename_in_doc_order(&self, cur_path: &Path, new_path: &Path) -> Result<(), anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let cur = WorkspacePath::from_absolute(&settings.doc_root_path, cur_path)?
+      .segments()
+      .to_vec();
+    let new = WorkspacePath::from_absolute(&settings.doc_root_path, new_path)?
+      .segments()
+      .to_vec();
+    let (Some((cur_name, parent)), Some(new_name)) = (cur.split_last(), new.last()) else {
+      return Ok(());
+    };
+    let item_name = |name: &String| {
+      if new_path.is_file() {
+        doc_name(name, &settings.doc_extensions).unwrap_or_else(|| name.clone())
+      } else {
+        name.clone()
       }
+    };
+
+    let _guard = self.write_lock.lock().unwrap();
+    let mut doc_order = self.read_doc_order()?;
+    let Some(order) = doc_order.get_mut(&parent.join("/")) else {
+      return Ok(());
+    };
+    let cur_name = item_name(cur_name);
+    let Some(position) = order.iter().position(|name| *name == cur_name) else {
+      return Ok(());
+    };
+    order[position] = item_name(new_name);
+    self.write_doc_order(&doc_order)
+  }
+
+  /// Returns the normalized doc tree of the whole workspace, keyed by normalized path.
+  /// The tree is cached until a create/rename/move/delete invalidates it, or `force` is set.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let tree = doc_service.get_doc_tree(false)?;
+  /// let roots = tree.values().filter(|doc| doc.parent_key.is_none());
+  /// ```
+  pub fn get_doc_tree(&self, force: bool) -> Result<NormalizedDocMap, anyhow::Error> {
+    if !force && let Some(doc_tree) = self.doc_tree.lock().unwrap().as_ref() {
+      return Ok(doc_tree.clone());
     }
 
-    // Sort: directories first, then files, both alphabetically
-    Self::sort_doc_items(&mut docs);
+    tracing::info!("[DocService] building doc tree (force: {}).", force);
+    let mut doc_tree = NormalizedDocMap::new();
+    let mut visited_dirs = HashSet::new();
+    self.collect_doc_tree("", None, &mut doc_tree, &mut visited_dirs)?;
+
+    *self.doc_tree.lock().unwrap() = Some(doc_tree.clone());
+    Ok(doc_tree)
+  }
+
+  /// Front matter of a doc in the cached tree, `None` when the tree or the doc is not cached.
+  fn cached_front_matter(&self, key: &str) -> Option<Option<serde_json::Value>> {
+    let doc_tree = self.doc_tree.lock().unwrap();
+    Some(doc_tree.as_ref()?.get(key)?.front_matter.clone())
+  }
 
-    Ok(docs)
+  /// Drops the cached doc tree and link index so the next `get_doc_tree` or
+  /// `get_backlinks` call rescans the workspace.
+  pub fn invalidate_doc_tree(&self) {
+    *self.doc_tree.lock().unwrap() = None;
+    *self.link_index.lock().unwrap() = None;
+  }
+
+  /// Returns the docs linking to `file_path`, with the line containing each link.
+  ///
+  /// # Arguments
+  /// * `file_path` - Normalized path string (percent-encoded), e.g., `"js%2Fbasic%2Farray"`
+  ///
+  /// # Example
+  /// ```ignore
+  /// // Which docs link to "js/basic/array.md"?
+  /// let backlinks = doc_service.get_backlinks("js%2Fbasic%2Farray")?;
+  /// ```
+  pub fn get_backlinks(&self, file_path: &str) -> Result<Vec<Backlink>, anyhow::Error> {
+    let (_, target) = self.doc_key(&self.path_convertor(file_path, true)?)?;
+    self.ensure_link_index()?;
+
+    let link_index = self.link_index.lock().unwrap();
+    let mut backlinks: Vec<Backlink> = link_index
+      .iter()
+      .flatten()
+      .filter(|(source, _)| **source != target)
+      .flat_map(|(source, links)| {
+        let name = denormalize_path(source).pop().unwrap_or_default();
+        links
+          .iter()
+          .filter(|link| link.target == target)
+          .map(move |link| Backlink {
+            file_path: source.clone(),
+            name: name.clone(),
+            line: link.line,
+            context: link.context.clone(),
+          })
+      })
+      .collect();
+    backlinks.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.line.cmp(&b.line)));
+
+    Ok(backlinks)
+  }
+
+  /// Resolves a wiki link such as `Note`, `folder/Note#Heading` or `[[Note|alias]]` to a
+  /// workspace doc, see [`resolve_wiki_target`] for the matching rules.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let resolution = doc_service.resolve_wiki_link("Array#map")?;
+  /// if resolution.status == WikiLinkStatus::Ambiguous {
+  ///   println!("{:?}", resolution.candidates);
+  /// }
+  /// ```
+  pub fn resolve_wiki_link(&self, link: &str) -> Result<WikiLinkResolution, anyhow::Error> {
+    let inner = link
+      .trim()
+      .trim_start_matches('!')
+      .trim_start_matches("[[")
+      .trim_end_matches("]]");
+    let Some(link) = parse_wiki_links(&format!("[[{}]]", inner)).pop() else {
+      return Err(anyhow::anyhow!("Invalid wiki link: {}", link));
+    };
+
+    Ok(resolve_wiki_target(
+      &link.target,
+      link.heading.as_deref(),
+      &self.wiki_link_docs()?,
+    ))
+  }
+
+  /// Returns the wiki links of `file_path` with their resolution, so ambiguous and
+  /// unresolved links can be reported. `[[#Heading]]` links resolve to the doc itself.
+  ///
+  /// # Arguments
+  /// * `file_path` - Normalized path string (percent-encoded), e.g., `"js%2Fbasic%2Farray"`
+  pub fn get_wiki_links(&self, file_path: &str) -> Result<Vec<WikiLinkReport>, anyhow::Error> {
+    let doc_path = self.path_convertor(file_path, true)?;
+    let content = fs::read_to_string(&doc_path)?;
+    let (_, key) = self.doc_key(&doc_path)?;
+    let docs = self.wiki_link_docs()?;
+
+    Ok(
+      parse_wiki_links(&content)
+        .into_iter()
+        .map(|link| {
+          let resolution = if link.target.is_empty() {
+            resolve_wiki_target(
+              &format!("/{}", denormalize_path(&key).join("/")),
+              link.heading.as_deref(),
+              &docs,
+            )
+          } else {
+            resolve_wiki_target(&link.target, link.heading.as_deref(), &docs)
+          };
+          WikiLinkReport { link, resolution }
+        })
+        .collect(),
+    )
+  }
+
+  /// Path segments (without `.md`) of every doc of the tree, for wiki link resolution.
+  fn wiki_link_docs(&self) -> Result<Vec<Vec<String>>, anyhow::Error> {
+    Ok(
+      self
+        .get_doc_tree(false)?
+        .into_values()
+        .filter(|doc| doc.is_file)
+        .map(|doc| doc.path)
+        .collect(),
+    )
+  }
+
+  /// Builds the link index from every doc of the tree unless it is cached.
+  fn ensure_link_index(&self) -> Result<(), anyhow::Error> {
+    if self.link_index.lock().unwrap().is_some() {
+      return Ok(());
+    }
+
+    tracing::info!("[DocService] building link index.");
+    let mut link_index = LinkIndex::new();
+    let doc_extensions = self.settings_service.get_settings().doc_extensions;
+    for (key, doc) in self.get_doc_tree(false)? {
+      if !doc.is_file {
+        continue;
+      }
+      let doc_path = self.path_convertor(&key, true)?;
+      let Ok(content) = fs::read_to_string(&doc_path) else {
+        continue;
+      };
+      let (segments, key) = self.doc_key(&doc_path)?;
+      link_index.insert(key, doc_links(&segments, &content, &doc_extensions));
+    }
+
+    *self.link_index.lock().unwrap() = Some(link_index);
+    Ok(())
+  }
+
+  /// Workspace relative segments (with extension) and normalized key (doc path) of a doc.
+  fn doc_key(&self, doc_path: &Path) -> Result<(Vec<String>, String), anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let segments = WorkspacePath::from_absolute(&settings.doc_root_path, doc_path)?
+      .segments()
+      .to_vec();
+
+    let mut key_parts = segments.clone();
+    if let Some(last) = key_parts.last_mut()
+      && let Some(name) = doc_name(last, &settings.doc_extensions)
+    {
+      *last = name;
+    }
+    Ok((segments, normalize_path(&key_parts)))
+  }
+
+  /// Recursively adds the sub items of `folder_doc_path` into `doc_tree` and returns their keys.
+  /// `visited_dirs` holds canonical folder paths so symlink cycles are not followed twice.
+  fn collect_doc_tree(
+    &self,
+    folder_doc_path: &str,
+    parent_key: Option<String>,
+    doc_tree: &mut NormalizedDocMap,
+    visited_dirs: &mut HashSet<PathBuf>,
+  ) -> Result<Vec<String>, anyhow::Error> {
+    let folder_path = self.path_convertor(folder_doc_path, false)?;
+    if !visited_dirs.insert(folder_path.canonicalize()?) {
+      return Ok(Vec::new());
+    }
+
+    // templates are not docs, they would show up in search, backlinks and wiki links
+    let templates_key = self.template_key("");
+    let mut children_keys = Vec::new();
+    for item in self.get_sub_doc_items(folder_doc_path, false, false)? {
+      let key = normalize_path(&item.path);
+      if !item.is_file && key == templates_key {
+        continue;
+      }
+      let (item_children_keys, parsed) = if item.is_file {
+        let content = fs::read_to_string(self.path_convertor(&key, true)?).unwrap_or_default();
+        (Vec::new(), parse_markdown(&content))
+      } else {
+        (
+          self.collect_doc_tree(&key, Some(key.clone()), doc_tree, visited_dirs)?,
+          ParsedMarkdown::default(),
+        )
+      };
+
+      doc_tree.insert(
+        key.clone(),
+        NormalizedDoc {
+          name: item.name,
+          id: item.id,
+          is_file: item.is_file,
+          children_keys: item_children_keys,
+          path: item.path,
+          headings: parsed.headings,
+          keywords: parsed.keywords,
+          parent_key: parent_key.clone(),
+          front_matter: item.front_matter,
+        },
+      );
+      children_keys.push(key);
+    }
+
+    Ok(children_keys)
   }
 
   /// Retrieves article content for a file. Returns `None` if the file doesn't exist.
@@ -165,8 +611,7 @@ impl DocService {
   /// let article = doc_service.get_article("js%2Fbasic%2Farray")?;
   /// ```
   pub fn get_article(&self, file_path: &str) -> Result<Option<Article>, anyhow::Error> {
-    let doc_path = self.path_convertor(file_path, true).unwrap();
-    println!("{:?}", doc_path);
+    let doc_path = self.path_convertor(file_path, true)?;
     if !doc_path.exists() {
       tracing::error!("The file path {} does not exist.", file_path);
       return Err(anyhow::anyhow!(
@@ -176,15 +621,57 @@ impl DocService {
     }
 
     let content = fs::read_to_string(&doc_path)?;
+    let parsed = parse_markdown(&content);
 
     Ok(Some(Article {
+      front_matter: parse_front_matter(&content),
+      version: content_version(content.as_bytes()),
       content,
       file_path: file_path.to_string(),
-      headings: Vec::new(),
-      keywords: Vec::new(),
+      headings: parsed.headings,
+      keywords: parsed.keywords,
     }))
   }
 
+  /// Updates individual front matter fields of an article, keeping the body and the
+  /// untouched keys byte-for-byte. A `null` field value removes the key.
+  ///
+  /// # Arguments
+  /// * `file_path` - Normalized path string (percent-encoded), e.g., `"js%2Fbasic%2Farray"`
+  /// * `fields` - Front matter fields to set or remove
+  ///
+  /// # Example
+  /// ```ignore
+  /// let fields = serde_json::json!({ "status": "done", "draft": null });
+  /// let article = doc_service.update_front_matter("js%2Fbasic%2Farray", fields.as_object().unwrap())?;
+  /// ```
+  pub fn update_front_matter(
+    &self,
+    file_path: &str,
+    fields: &serde_json::Map<String, serde_json::Value>,
+  ) -> Result<Article, anyhow::Error> {
+    let doc_path = self.path_convertor(file_path, true)?;
+    if !doc_path.exists() {
+      return Err(anyhow::anyhow!(
+        "The file path {} does not exist.",
+        file_path
+      ));
+    }
+
+    // a save in between is reported as a conflict instead of being overwritten
+    let content = fs::read_to_string(&doc_path)?;
+    let updated_content = update_front_matter(&content, fields)?;
+    self.update_article_if_match(
+      file_path,
+      &updated_content,
+      Some(&content_version(content.as_bytes())),
+    )?;
+
+    self
+      .get_article(file_path)?
+      .ok_or_else(|| anyhow::anyhow!("The file path {} does not exist.", file_path))
+  }
+
   /// Updates the content of an article file. Creates parent directories if needed.
   ///
   /// # Arguments
@@ -197,6 +684,13 @@ impl DocService {
   /// doc_service.update_article("js%2Fbasic%2Farray", "# New Content")?;
   /// ```
   pub fn update_article(&self, update_path: &str, content: &str) -> Result<(), anyhow::Error> {
+    self.write_article(update_path, content)?;
+    Ok(())
+  }
+
+  /// Atomically writes article content, returning the version of what ended up on disk
+  /// (line endings follow the existing file).
+  fn write_article(&self, update_path: &str, content: &str) -> Result<String, anyhow::Error> {
     let converted_path = self.path_convertor(update_path, true)?;
 
     // Ensure parent directory exists
@@ -204,9 +698,173 @@ impl DocService {
       fs::create_dir_all(parent)?;
     }
 
-    fs::write(&converted_path, content)?;
+    // docs saved before the history existed start it with their current content
+    if let Ok(versions) = self.history_service.list(&converted_path)
+      && versions.is_empty()
+      && let Ok(previous) = fs::read_to_string(&converted_path)
+    {
+      self.record_history(&converted_path, &previous);
+    }
+    let written = write_text_atomic(&converted_path, content)?;
+    self.record_history(&converted_path, &written);
+    // a save may also create the doc
+    if !self.refresh_cached_doc(&converted_path, &written)? {
+      self.invalidate_doc_tree();
+    }
 
-    Ok(())
+    Ok(content_version(written.as_bytes()))
+  }
+
+  /// Refreshes the cached headings, keywords and links of a doc changed outside of the
+  /// editor, e.g. `js/array.md`. Returns `false` if the cached tree doesn't know the doc
+  /// yet, i.e. it was just created.
+  ///
+  /// # Example
+  /// ```ignore
+  /// if !doc_service.refresh_changed_doc("js/array.md")? {
+  ///   doc_service.invalidate_doc_tree();
+  /// }
+  /// ```
+  pub fn refresh_changed_doc(&self, relative_path: &str) -> Result<bool, anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let doc_path =
+      WorkspacePath::from_relative(&settings.doc_root_path, relative_path)?.to_path_buf();
+    let is_doc = doc_path
+      .file_name()
+      .and_then(|name| name.to_str())
+      .is_some_and(|name| doc_name(name, &settings.doc_extensions).is_some());
+    if !is_doc || !doc_path.is_file() {
+      return Ok(true);
+    }
+    let content = fs::read_to_string(&doc_path)?;
+    self.refresh_cached_doc(&doc_path, &content)
+  }
+
+  /// Updates the headings, keywords and links of a single doc in the cached tree and
+  /// link index, returning `false` if the cached tree doesn't have it.
+  fn refresh_cached_doc(&self, doc_path: &Path, content: &str) -> Result<bool, anyhow::Error> {
+    let (segments, key) = self.doc_key(doc_path)?;
+    if let Some(doc_tree) = self.doc_tree.lock().unwrap().as_mut() {
+      let Some(doc) = doc_tree.get_mut(&key) else {
+        // docs outside of the tree, e.g. templates, are not new ones
+        let parent_key = normalize_path(&segments[..segments.len() - 1]);
+        return Ok(segments.len() > 1 && !doc_tree.contains_key(&parent_key));
+      };
+      let parsed = parse_markdown(content);
+      doc.headings = parsed.headings;
+      doc.keywords = parsed.keywords;
+      doc.front_matter = parse_front_matter(content);
+    }
+    if let Some(link_index) = self.link_index.lock().unwrap().as_mut() {
+      let doc_extensions = self.settings_service.get_settings().doc_extensions;
+      link_index.insert(key, doc_links(&segments, content, &doc_extensions));
+    }
+    Ok(true)
+  }
+
+  /// History failures must not fail the save, so they are only logged.
+  fn record_history(&self, doc_path: &Path, content: &str) {
+    if let Err(e) = self.history_service.record(doc_path, content) {
+      tracing::warn!(
+        "[DocService] failed to record the history of {}: {}",
+        doc_path.display(),
+        e
+      );
+    }
+  }
+
+  /// Lists the local history versions of a doc, most recent first.
+  ///
+  /// # Arguments
+  /// * `file_path` - Normalized path string (percent-encoded), e.g., `"js%2Fbasic%2Farray"`
+  pub fn list_versions(&self, file_path: &str) -> Result<Vec<HistoryVersion>, anyhow::Error> {
+    self
+      .history_service
+      .list(&self.path_convertor(file_path, true)?)
+  }
+
+  /// Returns the content of a local history version of a doc.
+  pub fn get_version(
+    &self,
+    file_path: &str,
+    id: &str,
+  ) -> Result<HistoryVersionContent, anyhow::Error> {
+    self
+      .history_service
+      .get(&self.path_convertor(file_path, true)?, id)
+  }
+
+  /// Writes a local history version back to the doc, returning the new content version.
+  /// The restore is itself recorded, so it can be undone from the history.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let versions = doc_service.list_versions("js%2Fbasic%2Farray")?;
+  /// doc_service.restore_version("js%2Fbasic%2Farray", &versions[1].id)?;
+  /// ```
+  pub fn restore_version(&self, file_path: &str, id: &str) -> Result<String, anyhow::Error> {
+    let snapshot = self.get_version(file_path, id)?;
+    let _guard = self.write_lock.lock().unwrap();
+    self.write_article(file_path, &snapshot.content)
+  }
+
+  /// Updates article content only if the file still has the `expected_version` returned by
+  /// [`DocService::get_article`], returning the new version. A stale version fails with an
+  /// [`ArticleConflict`] carrying the current on-disk content. Without an expected version
+  /// this behaves like [`DocService::update_article`].
+  ///
+  /// # Example
+  /// ```ignore
+  /// let article = doc_service.get_article("js%2Fbasic%2Farray")?.unwrap();
+  /// let version = doc_service.update_article_if_match(
+  ///   "js%2Fbasic%2Farray",
+  ///   "# New content",
+  ///   Some(&article.version),
+  /// )?;
+  /// ```
+  pub fn update_article_if_match(
+    &self,
+    update_path: &str,
+    content: &str,
+    expected_version: Option<&str>,
+  ) -> Result<String, anyhow::Error> {
+    let _guard = self.write_lock.lock().unwrap();
+
+    if let Some(expected_version) = expected_version {
+      let converted_path = self.path_convertor(update_path, true)?;
+      let current_content = match fs::read_to_string(&converted_path) {
+        Ok(current_content) => Some(current_content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+      };
+      let current_version = current_content
+        .as_ref()
+        .map(|current_content| content_version(current_content.as_bytes()));
+
+      if current_version.as_deref() != Some(expected_version) {
+        return Err(
+          ArticleConflict {
+            file_path: update_path.to_string(),
+            expected_version: expected_version.to_string(),
+            content: current_content,
+            version: current_version,
+          }
+          .into(),
+        );
+      }
+    }
+
+    self.write_article(update_path, content)
+  }
+
+  /// Checks whether a document (`is_file`) or directory exists at the normalized path.
+  pub fn doc_exists(&self, doc_path: &str, is_file: bool) -> Result<bool, anyhow::Error> {
+    let path = self.path_convertor(doc_path, is_file)?;
+    Ok(if is_file {
+      path.is_file()
+    } else {
+      path.is_dir()
+    })
   }
 
   /// Creates a new document or directory at the specified path.
@@ -237,6 +895,8 @@ impl DocService {
       fs::create_dir_all(&created_path)?;
     }
 
+    self.invalidate_doc_tree();
+
     let path_parts = denormalize_path(doc_path);
     let name = path_parts.last().unwrap().to_string();
 
@@ -245,9 +905,137 @@ impl DocService {
       name,
       is_file,
       path: path_parts,
+      front_matter: None,
+      metadata: DocItemMetadata::default(),
     })
   }
 
+  /// Creates a document from a template of the templates folder, substituting the
+  /// `{{title}}`, `{{date}}`, `{{time}}` and `{{path}}` placeholders and the custom ones
+  /// given in `variables`. Fails if the document already exists.
+  ///
+  /// # Example
+  /// ```ignore
+  /// // Create "work/standup.md" from "_templates/meeting.md"
+  /// let variables = HashMap::from([("project".to_string(), "Editor".to_string())]);
+  /// let doc = doc_service.create_doc_from_template("work%2Fstandup", "meeting", &variables)?;
+  /// ```
+  pub fn create_doc_from_template(
+    &self,
+    doc_path: &str,
+    template: &str,
+    variables: &HashMap<String, String>,
+  ) -> Result<DocItem, anyhow::Error> {
+    let template_path = self.path_convertor(&self.template_key(template), true)?;
+    if !template_path.is_file() {
+      return Err(anyhow::anyhow!("The template {} does not exist.", template));
+    }
+    let created_path = self.path_convertor(doc_path, true)?;
+
+    tracing::info!("create_doc_from_template: {:?}, {:?}", doc_path, template);
+
+    let path_parts = denormalize_path(doc_path);
+    let name = path_parts.last().unwrap().to_string();
+    let now = chrono::Local::now();
+    let mut all_variables = HashMap::from([
+      ("title".to_string(), name.clone()),
+      ("path".to_string(), path_parts.join("/")),
+      ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+      ("time".to_string(), now.format("%H:%M").to_string()),
+    ]);
+    all_variables.extend(variables.clone());
+
+    let content = render_template(&fs::read_to_string(&template_path)?, &all_variables);
+    if let Some(parent) = created_path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    // created only if missing, a doc created meanwhile is not overwritten
+    let mut file = match fs::File::options()
+      .write(true)
+      .create_new(true)
+      .open(&created_path)
+    {
+      Ok(file) => file,
+      Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+        return Err(
+          DocExistsConflict {
+            file_path: doc_path.to_string(),
+          }
+          .into(),
+        );
+      }
+      Err(e) => return Err(e.into()),
+    };
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    self.record_history(&created_path, &content);
+    self.invalidate_doc_tree();
+
+    Ok(DocItem {
+      id: format!("{}-{}", name, path_parts.join("-")),
+      name,
+      is_file: true,
+      path: path_parts,
+      front_matter: parse_front_matter(&content),
+      metadata: DocItemMetadata::default(),
+    })
+  }
+
+  /// Lists the templates of the templates folder with their custom placeholders.
+  pub fn list_templates(&self) -> Result<Vec<DocTemplate>, anyhow::Error> {
+    let templates_root = self.path_convertor(&self.template_key(""), false)?;
+    let mut templates = Vec::new();
+    if templates_root.is_dir() {
+      self.collect_templates(&templates_root, &mut Vec::new(), &mut templates)?;
+    }
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    Ok(templates)
+  }
+
+  fn collect_templates(
+    &self,
+    dir: &Path,
+    prefix: &mut Vec<String>,
+    templates: &mut Vec<DocTemplate>,
+  ) -> Result<(), anyhow::Error> {
+    let doc_extensions = self.settings_service.get_settings().doc_extensions;
+    for entry in fs::read_dir(dir)? {
+      let entry = entry?;
+      let file_name = entry.file_name().to_string_lossy().to_string();
+      if file_name.starts_with('.') {
+        continue;
+      }
+
+      let path = entry.path();
+      if path.is_dir() {
+        prefix.push(file_name);
+        self.collect_templates(&path, prefix, templates)?;
+        prefix.pop();
+      } else if let Some(name) = doc_name(&file_name, &doc_extensions) {
+        let name = [&prefix[..], &[name]].concat().join("/");
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        templates.push(DocTemplate {
+          file_path: self.template_key(&name),
+          name,
+          variables: template_variables(&content),
+        });
+      }
+    }
+    Ok(())
+  }
+
+  /// Normalized path of a template (or of the templates folder for an empty name).
+  fn template_key(&self, template: &str) -> String {
+    let templates_dir = self.settings_service.get_settings().templates_dir;
+    let segments: Vec<String> = templates_dir
+      .split(['/', '\\'])
+      .chain(template.split('/'))
+      .filter(|segment| !segment.is_empty())
+      .map(String::from)
+      .collect();
+    normalize_path(&segments)
+  }
+
   pub fn create_folder(ab_path: &str) -> Result<(), anyhow::Error> {
     tracing::info!("create_folder: {:?}", ab_path);
 
@@ -258,7 +1046,8 @@ impl DocService {
     Ok(())
   }
 
-  /// Deletes a document or directory.
+  /// Deletes a document or directory by moving it to the workspace trash, returns the
+  /// trashed item.
   ///
   /// # Arguments
   /// * `doc_path` - Normalized path string (percent-encoded), e.g., `"js%2Fbasic%2Fold-doc"`
@@ -272,12 +1061,37 @@ impl DocService {
   /// // Delete a directory at "js/basic/old-folder"
   /// doc_service.delete_doc("js%2Fbasic%2Fold-folder", false)?;
   /// ```
-  pub fn delete_doc(&self, doc_path: &str, is_file: bool) -> Result<(), anyhow::Error> {
-    let delete_path = self.path_convertor(doc_path, is_file)?;
+  pub fn delete_doc(&self, doc_path: &str, is_file: bool) -> Result<TrashItem, anyhow::Error> {
+    let delete_path = match self.path_convertor(doc_path, is_file) {
+      Ok(delete_path) => delete_path,
+      // a symlink pointing outside of the workspace is deleted as the link itself
+      Err(e) => self.symlink_path(doc_path, is_file).ok_or(e)?,
+    };
 
-    fs::remove_dir_all(&delete_path).or_else(|_| fs::remove_file(&delete_path))?;
+    let item = self.trash_service.move_to_trash(&delete_path)?;
+    self.invalidate_doc_tree();
 
-    Ok(())
+    Ok(item)
+  }
+
+  /// The symlink at a normalized path, resolved from its folder without following it.
+  fn symlink_path(&self, doc_path: &str, is_file: bool) -> Option<PathBuf> {
+    let mut segments = denormalize_path(doc_path);
+    let name = segments.pop()?;
+    let folder = self
+      .path_convertor(&normalize_path(&segments), false)
+      .ok()?;
+    let default_extension =
+      default_doc_extension(&self.settings_service.get_settings().doc_extensions).to_string();
+    let names = if is_file {
+      vec![format!("{}.{}", name, default_extension), name]
+    } else {
+      vec![name]
+    };
+    names
+      .into_iter()
+      .map(|name| folder.join(name))
+      .find(|path| fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink()))
   }
 
   /// Copies or moves a document/directory from `copy_cut_path` to `paste_path`.
@@ -288,14 +1102,15 @@ impl DocService {
   /// * `paste_path` - Destination normalized path, e.g., `"js%2Fadvanced%2Fdestination"`
   /// * `is_copy` - `true` to copy, `false` to move
   /// * `is_file` - `true` for markdown files, `false` for directories
+  /// * `update_links` - rewrite links to the moved docs, returns the applied updates
   ///
   /// # Example
   /// ```ignore
   /// // Copy a file from "js/basic/source.md" to "js/advanced/destination.md"
-  /// doc_service.copy_cut_doc("js%2Fbasic%2Fsource", "js%2Fadvanced%2Fdestination", true, true)?;
+  /// doc_service.copy_cut_doc("js%2Fbasic%2Fsource", "js%2Fadvanced%2Fdestination", true, true, false)?;
   ///
   /// // Move a directory from "js/basic/folder" to "js/advanced/folder"
-  /// doc_service.copy_cut_doc("js%2Fbasic%2Ffolder", "js%2Fadvanced%2Ffolder", false, false)?;
+  /// doc_service.copy_cut_doc("js%2Fbasic%2Ffolder", "js%2Fadvanced%2Ffolder", false, false, true)?;
   /// ```
   pub fn copy_cut_doc(
     &self,
@@ -303,7 +1118,37 @@ impl DocService {
     paste_path: &str,
     is_copy: bool,
     is_file: bool,
-  ) -> Result<(), anyhow::Error> {
+    update_links: bool,
+  ) -> Result<Vec<LinkUpdate>, anyhow::Error> {
+    self.ensure_paste_parent(paste_path)?;
+
+    let source_path = self.path_convertor(copy_cut_path, is_file)?;
+    let dest_path = self.path_convertor(paste_path, is_file)?;
+
+    // copies leave the originals in place, so links to them stay valid
+    let link_updates = if update_links && !is_copy {
+      self.plan_link_updates(&source_path, &dest_path)?
+    } else {
+      Vec::new()
+    };
+
+    if is_copy {
+      if is_file {
+        fs::copy(&source_path, &dest_path)?;
+      } else {
+        copy_dir_all(&source_path, &dest_path)?;
+      }
+    } else {
+      self.move_item(&source_path, &dest_path)?;
+    }
+    self.invalidate_doc_tree();
+    self.apply_link_updates(&link_updates)?;
+
+    Ok(link_updates)
+  }
+
+  /// Fails unless the folder to paste `paste_path` into exists.
+  fn ensure_paste_parent(&self, paste_path: &str) -> Result<(), anyhow::Error> {
     let paste_parent_path = {
       let mut path_parts = denormalize_path(paste_path);
       path_parts.pop();
@@ -322,20 +1167,6 @@ impl DocService {
         paste_path
       ));
     }
-
-    let source_path = self.path_convertor(copy_cut_path, is_file)?;
-    let dest_path = self.path_convertor(paste_path, is_file)?;
-
-    if is_copy {
-      if is_file {
-        fs::copy(&source_path, &dest_path)?;
-      } else {
-        copy_dir_all(&source_path, &dest_path)?;
-      }
-    } else {
-      fs::rename(&source_path, &dest_path)?;
-    }
-
     Ok(())
   }
 
@@ -345,56 +1176,242 @@ impl DocService {
   /// * `modify_path` - Current normalized path, e.g., `"js%2Fbasic%2Fold-name"`
   /// * `name` - New name (without path), e.g., `"new-name"`
   /// * `is_file` - `true` for markdown files, `false` for directories
+  /// * `update_links` - rewrite links to the renamed docs, returns the applied updates
   ///
   /// # Example
   /// ```ignore
   /// // Rename file "js/basic/old-name.md" to "js/basic/new-name.md"
-  /// doc_service.modify_name("js%2Fbasic%2Fold-name", "new-name", true)?;
+  /// doc_service.modify_name("js%2Fbasic%2Fold-name", "new-name", true, false)?;
   ///
-  /// // Rename directory "js/basic/old-folder" to "js/basic/new-folder"
-  /// doc_service.modify_name("js%2Fbasic%2Fold-folder", "new-folder", false)?;
+  /// // Rename directory "js/basic/old-folder" to "js/basic/new-folder", fixing links to it
+  /// doc_service.modify_name("js%2Fbasic%2Fold-folder", "new-folder", false, true)?;
   /// ```
   pub fn modify_name(
     &self,
     modify_path: &str,
     name: &str,
     is_file: bool,
-  ) -> Result<(), anyhow::Error> {
-    let cur_path = self.path_convertor(modify_path, is_file)?;
+    update_links: bool,
+  ) -> Result<Vec<LinkUpdate>, anyhow::Error> {
+    let (cur_path, new_path) = self.rename_target(modify_path, name, is_file)?;
 
     if !cur_path.exists() {
-      return Ok(());
+      return Ok(Vec::new());
     }
 
-    let new_path = self.path_convertor_with_name(modify_path, is_file, Some(name))?;
     if new_path.to_str() == cur_path.to_str() {
       tracing::info!(
         "The new path is the same as the current path, so skip the rename. {}",
         cur_path.display()
       );
-      return Ok(());
+      return Ok(Vec::new());
+    }
+
+    let link_updates = if update_links {
+      self.plan_link_updates(&cur_path, &new_path)?
+    } else {
+      Vec::new()
+    };
+
+    self.rename_item(&cur_path, &new_path)?;
+    self.invalidate_doc_tree();
+    self.apply_link_updates(&link_updates)?;
+
+    Ok(link_updates)
+  }
+
+  /// Moves a doc or folder on disk, taking the version history of its docs along.
+  fn move_item(&self, from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+    fs::rename(from, to)?;
+    if let Err(e) = self.history_service.move_history(from, to) {
+      tracing::warn!("[DocService] failed to move the history: {}", e);
     }
-    println!("{:?}, {:?}", cur_path, new_path);
-    fs::rename(&cur_path, &new_path)?;
+    Ok(())
+  }
 
+  /// Renames a doc or folder in place, keeping its position in the manual order.
+  fn rename_item(&self, cur_path: &Path, new_path: &Path) -> Result<(), anyhow::Error> {
+    self.move_item(cur_path, new_path)?;
+    if let Err(e) = self.rename_in_doc_order(cur_path, new_path) {
+      tracing::warn!("[DocService] failed to update the manual order: {}", e);
+    }
     Ok(())
   }
 
-  /// Checks if a file name has a markdown extension.
-  fn is_markdown(&self, file_name: &str) -> bool {
-    file_name.ends_with(".md")
+  /// Current and renamed filesystem paths of a doc renamed to `name`.
+  fn rename_target(
+    &self,
+    modify_path: &str,
+    name: &str,
+    is_file: bool,
+  ) -> Result<(PathBuf, PathBuf), anyhow::Error> {
+    let cur_path = self.path_convertor(modify_path, is_file)?;
+
+    // a doc keeps its non-default extension unless the new name has a doc extension
+    let doc_extensions = self.settings_service.get_settings().doc_extensions;
+    let name = match (
+      is_file,
+      doc_name(name, &doc_extensions),
+      cur_path.extension().and_then(|ext| ext.to_str()),
+    ) {
+      (true, Some(new_name), _) => new_name,
+      (true, None, Some(ext))
+        if !ext.eq_ignore_ascii_case(default_doc_extension(&doc_extensions)) =>
+      {
+        format!("{}.{}", name, ext)
+      }
+      _ => name.to_string(),
+    };
+    let new_path = self.path_convertor_with_name(modify_path, is_file, Some(&name))?;
+    Ok((cur_path, new_path))
+  }
+
+  /// Previews the links that a rename or move from `from_path` to `to_path` would
+  /// rewrite, without touching any file.
+  ///
+  /// # Example
+  /// ```ignore
+  /// // Which docs link to "js/array.md", or are linked from it, if it moves to "ts/"?
+  /// let updates = doc_service.preview_link_updates("js%2Farray", "ts%2Farray", true)?;
+  /// ```
+  pub fn preview_link_updates(
+    &self,
+    from_path: &str,
+    to_path: &str,
+    is_file: bool,
+  ) -> Result<Vec<LinkUpdate>, anyhow::Error> {
+    let from = self.path_convertor(from_path, is_file)?;
+    let to = self.path_convertor(to_path, is_file)?;
+    if !from.exists() {
+      return Err(anyhow::anyhow!("The path {} does not exist.", from_path));
+    }
+    self.plan_link_updates(&from, &to)
+  }
+
+  /// Computes the rewritten content of every doc with a link affected by moving `from`
+  /// to `to`: links to the moved docs, and relative links inside the moved docs.
+  fn plan_link_updates(&self, from: &Path, to: &Path) -> Result<Vec<LinkUpdate>, anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let root = settings.doc_root_path;
+    let doc_extension = format!(".{}", default_doc_extension(&settings.doc_extensions));
+    let from = WorkspacePath::from_absolute(&root, from)?
+      .segments()
+      .to_vec();
+    let to = WorkspacePath::from_absolute(&root, to)?.segments().to_vec();
+    let moved = |path: &[String]| -> Option<Vec<String>> {
+      path
+        .starts_with(&from)
+        .then(|| [&to[..], &path[from.len()..]].concat())
+    };
+
+    let tree = self.get_doc_tree(false)?;
+    let mut doc_keys: Vec<&String> = tree
+      .iter()
+      .filter(|(_, doc)| doc.is_file)
+      .map(|(key, _)| key)
+      .collect();
+    doc_keys.sort();
+
+    let mut updates = Vec::new();
+    for key in doc_keys {
+      let doc_path = self.path_convertor(key, true)?;
+      let old_doc = WorkspacePath::from_absolute(&root, &doc_path)?
+        .segments()
+        .to_vec();
+      let new_doc = moved(&old_doc).unwrap_or_else(|| old_doc.clone());
+      let Ok(content) = fs::read_to_string(&doc_path) else {
+        continue;
+      };
+
+      let mut new_content = content.clone();
+      let mut changes = Vec::new();
+      // replace from the end so the earlier ranges stay valid
+      for link in extract_links(&content).into_iter().rev() {
+        let Some(resolved) = resolve_link(&old_doc, &link.dest) else {
+          continue;
+        };
+
+        let mut new_target = moved(&resolved.target);
+        // extensionless links to docs, e.g. `./array` for `./array.md`
+        if new_target.is_none()
+          && let Some(last) = resolved.target.last()
+          && !last.contains('.')
+        {
+          let mut with_ext = resolved.target.clone();
+          with_ext.last_mut().unwrap().push_str(&doc_extension);
+          new_target = moved(&with_ext).map(|mut target| {
+            let last = target.last_mut().unwrap();
+            last.truncate(last.len() - doc_extension.len());
+            target
+          });
+        }
+        if new_target.is_none() && (new_doc == old_doc || resolved.root_relative) {
+          continue;
+        }
+
+        let target = new_target.unwrap_or(resolved.target);
+        let path_part = &link.dest[..link.dest.len() - resolved.suffix.len()];
+        let new_dest = format!(
+          "{}{}",
+          format_link(&new_doc, &target, path_part, resolved.root_relative),
+          resolved.suffix
+        );
+        if new_dest == link.dest {
+          continue;
+        }
+
+        new_content.replace_range(link.range.clone(), &new_dest);
+        changes.push(LinkChange {
+          line: link.line,
+          from: link.dest,
+          to: new_dest,
+        });
+      }
+
+      if !changes.is_empty() {
+        changes.reverse();
+        let mut doc_parts = new_doc;
+        if let Some(last) = doc_parts.last_mut()
+          && let Some(name) = doc_name(last, &settings.doc_extensions)
+        {
+          *last = name;
+        }
+        updates.push(LinkUpdate {
+          file_path: normalize_path(&doc_parts),
+          changes,
+          content: new_content,
+          previous_content: content,
+        });
+      }
+    }
+
+    Ok(updates)
+  }
+
+  /// Writes the planned link updates once the docs are at their new paths.
+  fn apply_link_updates(&self, updates: &[LinkUpdate]) -> Result<(), anyhow::Error> {
+    for update in updates {
+      tracing::info!(
+        "[DocService] rewrite {} link(s) in {}",
+        update.changes.len(),
+        update.file_path
+      );
+      self.write_article(&update.file_path, &update.content)?;
+    }
+    Ok(())
   }
 
   /// Converts a normalized path string to a filesystem path.
   ///
   /// # Arguments
   /// * `str_path` - Normalized path (percent-encoded), e.g., `"js%2Fbasic%2Farray"`
-  /// * `is_file` - `true` to append `.md` extension, `false` for directories
+  /// * `is_file` - `true` to append the default doc extension, `false` for directories
   ///
   /// # Example
   /// ```ignore
   /// // Converts "js%2Fbasic%2Farray" to "js/basic/array.md" (if is_file=true)
-  /// // or "js/basic/array" (if is_file=false)
+  /// // or "js/basic/array" (if is_file=false). "notes%2Ftodo.txt" stays "notes/todo.txt"
+  /// // when `txt` is a non-default doc extension.
   /// let path = doc_service.path_convertor("js%2Fbasic%2Farray", true)?;
   /// ```
   fn path_convertor(&self, str_path: &str, is_file: bool) -> Result<PathBuf, anyhow::Error> {
@@ -410,56 +1427,201 @@ impl DocService {
   ) -> Result<PathBuf, anyhow::Error> {
     let mut path_parts = denormalize_path(str_path);
 
-    if let Some(new_name) = name {
-      if let Some(last) = path_parts.last_mut() {
-        *last = new_name.to_string();
-      }
+    if let Some(new_name) = name
+      && let Some(last) = path_parts.last_mut()
+    {
+      *last = new_name.to_string();
     }
 
-    let doc_root = self
-      .settings_service
-      .settings
-      .lock()
-      .unwrap()
-      .doc_root_path
-      .clone();
+    let (doc_root, doc_extensions) = {
+      let settings = self.settings_service.settings.lock().unwrap();
+      (
+        settings.doc_root_path.clone(),
+        settings.doc_extensions.clone(),
+      )
+    };
 
-    let mut full_path = doc_root;
-    for part in path_parts {
-      full_path.push(part);
-    }
+    // doc paths leave out the default extension only, so a name that keeps its own doc
+    // extension is the file name, unless a file with the default extension exists
+    if is_file && let Some(last) = path_parts.last().cloned() {
+      let mut with_default = path_parts.clone();
+      *with_default.last_mut().unwrap() =
+        format!("{}.{}", last, default_doc_extension(&doc_extensions));
+      let with_default = WorkspacePath::resolve(&doc_root, with_default)?;
 
-    if is_file {
-      full_path.set_extension("md");
+      if doc_name(&last, &doc_extensions).is_none_or(|name| name != last) {
+        return Ok(with_default.to_path_buf());
+      }
+      if with_default.to_path_buf().exists() {
+        let path = WorkspacePath::resolve(&doc_root, path_parts)?.to_path_buf();
+        if path.exists() {
+          return Err(anyhow::anyhow!(
+            "The doc path {} matches both {} and {}.",
+            str_path,
+            with_default.to_path_buf().display(),
+            path.display()
+          ));
+        }
+        return Ok(with_default.to_path_buf());
+      }
     }
 
-    Ok(full_path)
+    Ok(WorkspacePath::resolve(&doc_root, path_parts)?.to_path_buf())
   }
 
-  fn sort_doc_items(doc_items: &mut Vec<DocItem>) {
-    doc_items.sort_by(|a, b| match (a.is_file, b.is_file) {
-      (true, false) => std::cmp::Ordering::Greater,
-      (false, true) => std::cmp::Ordering::Less,
-      _ => {
-        if a.is_file {
-          a.id.to_lowercase().cmp(&b.id.to_lowercase())
-        } else {
-          a.name.to_lowercase().cmp(&b.name.to_lowercase())
-        }
+  fn sort_doc_items(
+    doc_items: &mut [(DocItem, Option<fs::Metadata>)],
+    sort_mode: DocSortMode,
+    manual_order: Option<&[String]>,
+  ) {
+    let time = |metadata: &Option<fs::Metadata>, created: bool| {
+      let metadata = metadata.as_ref()?;
+      if created {
+        // not every filesystem records the creation time
+        metadata.created().or_else(|_| metadata.modified()).ok()
+      } else {
+        metadata.modified().ok()
       }
+    };
+    let position = |doc: &DocItem| {
+      manual_order
+        .and_then(|order| order.iter().position(|name| *name == doc.name))
+        .unwrap_or(usize::MAX)
+    };
+
+    doc_items.sort_by(|(a, a_metadata), (b, b_metadata)| {
+      a.is_file
+        .cmp(&b.is_file)
+        .then_with(|| match sort_mode {
+          DocSortMode::Name => std::cmp::Ordering::Equal,
+          DocSortMode::Modified => time(b_metadata, false).cmp(&time(a_metadata, false)),
+          DocSortMode::Created => time(b_metadata, true).cmp(&time(a_metadata, true)),
+          DocSortMode::Manual => position(a).cmp(&position(b)),
+        })
+        .then_with(|| natural_cmp(&a.name, &b.name))
     });
   }
 
-  fn get_root_dir() -> PathBuf {
-    #[cfg(target_os = "windows")]
-    {
-      // For Windows service, return "" for UI
-      return PathBuf::from("");
+  /// Whether a sub folder is listed, hidden folders are filtered by the caller.
+  fn is_listed_dir(name: &str, ignore_dirs: &[String]) -> bool {
+    !INTERNAL_IGNORE_DIRS.contains(&name) && !ignore_dirs.iter().any(|dir| dir == name)
+  }
+
+  /// Number of items listed in a folder, `None` if it can't be read.
+  fn count_listed_children(
+    folder: &Path,
+    ignore_dirs: &[String],
+    doc_extensions: &[String],
+  ) -> Option<usize> {
+    let count = fs::read_dir(folder)
+      .ok()?
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+          false
+        } else if entry.path().is_file() {
+          doc_name(&name, doc_extensions).is_some()
+        } else {
+          Self::is_listed_dir(&name, ignore_dirs)
+        }
+      })
+      .count();
+    Some(count)
+  }
+
+  fn fill_fs_metadata(metadata: &mut DocItemMetadata, fs_metadata: Option<&fs::Metadata>) {
+    let Some(fs_metadata) = fs_metadata else {
+      return;
+    };
+    let millis = |time: std::io::Result<SystemTime>| {
+      time
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_millis() as u64)
+    };
+    metadata.size = Some(fs_metadata.len());
+    metadata.modified_at = millis(fs_metadata.modified());
+    metadata.created_at = millis(fs_metadata.created());
+  }
+
+  /// Git status of the items of a folder, keyed by their file name. A folder gets the
+  /// status of its changed content, `Modified` when it changed in different ways.
+  /// Empty when the folder isn't in a git repository.
+  fn git_statuses(folder: &Path) -> HashMap<String, StatusType> {
+    let mut statuses = HashMap::new();
+    let Ok(repo) = Repository::discover(folder) else {
+      return statuses;
+    };
+    let (Some(workdir), Ok(folder)) = (repo.workdir(), folder.canonicalize()) else {
+      return statuses;
+    };
+    let Some(prefix) = workdir
+      .canonicalize()
+      .ok()
+      .and_then(|workdir| folder.strip_prefix(workdir).ok().map(Path::to_path_buf))
+    else {
+      return statuses;
+    };
+    // git paths are `/` separated on every platform
+    let prefix = prefix
+      .components()
+      .map(|component| component.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/");
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true);
+    opts.include_ignored(false);
+    opts.recurse_untracked_dirs(false);
+    // a folder named like a glob, e.g. `[draft]`, is matched literally
+    opts.disable_pathspec_match(true);
+    if !prefix.is_empty() {
+      opts.pathspec(&prefix);
     }
+    let Ok(entries) = repo.statuses(Some(&mut opts)) else {
+      return statuses;
+    };
 
-    #[cfg(target_os = "macos")]
-    {
-      return PathBuf::from("/");
+    for entry in entries.iter() {
+      let (Some(path), Some(status)) = (entry.path(), StatusType::from_status(entry.status()))
+      else {
+        continue;
+      };
+      let relative = if prefix.is_empty() {
+        Some(path)
+      } else {
+        path
+          .strip_prefix(prefix.as_str())
+          .and_then(|path| path.strip_prefix('/'))
+      };
+      let Some(name) = relative
+        .and_then(|path| path.split('/').next())
+        .filter(|name| !name.is_empty())
+      else {
+        continue;
+      };
+      statuses
+        .entry(name.to_string())
+        .and_modify(|current: &mut StatusType| {
+          if *current != status {
+            *current = StatusType::Modified;
+          }
+        })
+        .or_insert(status);
+    }
+    statuses
+  }
+
+  fn get_root_dir() -> PathBuf {
+    if cfg!(target_os = "windows") {
+      // For Windows service, return "" for UI
+      PathBuf::from("")
+    } else {
+      // macOS, Linux and other unix-like systems browse from the filesystem root,
+      // so the first path segment "/" is the display prefix the UI expects.
+      PathBuf::from("/")
     }
   }
 
@@ -492,6 +1654,8 @@ impl DocService {
           is_file: false,
           // add a virtual root_dir prefix to display for UI
           path: vec![root_dir.to_string_lossy().to_string(), disk_name],
+          front_matter: None,
+          metadata: DocItemMetadata::default(),
         }
       })
       .collect();
@@ -1,8 +1,21 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use struct_patch::Patch;
 
+use super::wiki_links::{WikiLink, WikiLinkResolution};
+use crate::services::git::StatusType;
+
+/// A markdown heading, `id` is the anchor slug used by the editor outline.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Heading {
+  pub text: String,
+  pub level: u8,
+  pub id: String,
+}
+
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Doc {
@@ -11,7 +24,7 @@ pub struct Doc {
   pub is_file: bool,
   pub children: Vec<Doc>,
   pub path: Vec<String>,
-  pub headings: Vec<String>,
+  pub headings: Vec<Heading>,
   pub keywords: Vec<String>,
 }
 
@@ -22,6 +35,29 @@ pub struct DocItem {
   pub id: String,
   pub is_file: bool,
   pub path: Vec<String>,
+  /// Parsed YAML front matter of a markdown file, `None` for folders or files without it.
+  pub front_matter: Option<Value>,
+  #[serde(flatten)]
+  pub metadata: DocItemMetadata,
+}
+
+/// Details of a listed doc item, each one is `None` when it's unknown or doesn't apply.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocItemMetadata {
+  /// Size in bytes, of the folder entry itself for folders.
+  pub size: Option<u64>,
+  /// Modification time in milliseconds since the Unix epoch.
+  pub modified_at: Option<u64>,
+  /// Creation time in milliseconds since the Unix epoch, where the platform records it.
+  pub created_at: Option<u64>,
+  /// Words of a markdown file, front matter excluded.
+  pub word_count: Option<usize>,
+  /// Docs and folders directly in a folder, as listed in the sidebar.
+  pub child_count: Option<usize>,
+  /// Uncommitted change of the item. A folder takes the change of its content,
+  /// `MODIFIED` when its content changed in different ways.
+  pub git_status: Option<StatusType>,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -33,10 +69,13 @@ pub struct NormalizedDoc {
   /// children keys in the normalized docs HashMap
   pub children_keys: Vec<String>,
   pub path: Vec<String>,
-  pub headings: Vec<String>,
+  pub headings: Vec<Heading>,
   pub keywords: Vec<String>,
   /// Parent key in the normalized docs HashMap. None means it's a root doc.
   pub parent_key: Option<String>,
+  /// Front matter of a markdown file, cached for listings.
+  #[serde(skip)]
+  pub front_matter: Option<Value>,
 }
 
 pub type NormalizedDocMap = HashMap<String, NormalizedDoc>;
@@ -46,8 +85,11 @@ pub type NormalizedDocMap = HashMap<String, NormalizedDoc>;
 pub struct Article {
   pub content: String,
   pub file_path: String,
-  pub headings: Vec<String>,
+  pub headings: Vec<Heading>,
   pub keywords: Vec<String>,
+  pub front_matter: Option<Value>,
+  /// Content version to send back as `version` on save, see [`UpdateArticleRequest`].
+  pub version: String,
 }
 
 #[derive(Patch, Debug, Deserialize)]
@@ -56,6 +98,14 @@ pub struct Article {
 pub struct GetDocSubTreeQuery {
   pub folder_doc_path: String,
   pub home_root_dir: bool,
+  /// Include the size, times, counts and git status of each item.
+  pub with_metadata: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetDocTreeQuery {
+  /// Rescan the workspace instead of returning the cached tree.
+  pub force: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -69,6 +119,24 @@ pub struct GetArticleQuery {
 pub struct CreateDocRequest {
   pub file_path: String,
   pub is_file: bool,
+  /// Template to create the file from, relative to the templates folder, e.g. `meeting`.
+  #[serde(default)]
+  pub template: Option<String>,
+  /// Values of the template placeholders, these take precedence over the built-in ones.
+  #[serde(default)]
+  pub variables: HashMap<String, String>,
+}
+
+/// A template in the workspace templates folder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocTemplate {
+  /// Name to create docs with, relative to the templates folder.
+  pub name: String,
+  /// Normalized path of the template doc, to edit it.
+  pub file_path: String,
+  /// Custom placeholders to ask values for.
+  pub variables: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +150,64 @@ pub struct CreateFolderRequest {
 pub struct UpdateArticleRequest {
   pub file_path: String,
   pub content: String,
+  /// Version the edit is based on. When set, the save is rejected with a conflict if the
+  /// file changed on disk in the meantime. The `If-Match` header takes precedence.
+  #[serde(default)]
+  pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateArticleResponse {
+  /// Version of the content that was just written.
+  pub version: String,
+}
+
+/// Returned when a save is based on a stale version of the article.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleConflict {
+  pub file_path: String,
+  /// Version the client expected to overwrite.
+  pub expected_version: String,
+  /// Current on-disk content and its version, `None` if the file was removed.
+  pub content: Option<String>,
+  pub version: Option<String>,
+}
+
+impl std::fmt::Display for ArticleConflict {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "The file {} has been changed since it was loaded.",
+      self.file_path
+    )
+  }
+}
+
+impl std::error::Error for ArticleConflict {}
+
+/// Returned when a doc is created at the path of an existing one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocExistsConflict {
+  pub file_path: String,
+}
+
+impl std::fmt::Display for DocExistsConflict {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "The file {} already exists.", self.file_path)
+  }
+}
+
+impl std::error::Error for DocExistsConflict {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateFrontMatterRequest {
+  pub file_path: String,
+  /// Front matter fields to set, a `null` value removes the field.
+  pub fields: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +216,9 @@ pub struct UpdateDocNameRequest {
   pub file_path: String,
   pub name: String,
   pub is_file: bool,
+  /// Rewrite links pointing to the renamed doc in other docs.
+  #[serde(default)]
+  pub update_links: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +228,81 @@ pub struct CopyCutDocRequest {
   pub paste_path: String,
   pub is_copy: bool,
   pub is_file: bool,
+  /// Rewrite links pointing to the moved doc in other docs, ignored for copies.
+  #[serde(default)]
+  pub update_links: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewLinkUpdatesRequest {
+  pub from_path: String,
+  pub to_path: String,
+  pub is_file: bool,
+}
+
+/// A link that is rewritten because its target or the doc containing it moves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkChange {
+  pub line: usize,
+  pub from: String,
+  pub to: String,
+}
+
+/// Links to rewrite in a single doc.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkUpdate {
+  /// Normalized path of the doc after the move.
+  pub file_path: String,
+  pub changes: Vec<LinkChange>,
+  /// Content of the doc with the links rewritten.
+  #[serde(skip)]
+  pub content: String,
+  /// Content of the doc before the rewrite.
+  #[serde(skip)]
+  pub previous_content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetBacklinksQuery {
+  #[serde(rename = "filePath")]
+  pub file_path: String,
+}
+
+/// A link to a doc from another doc.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backlink {
+  /// Normalized path of the linking doc.
+  pub file_path: String,
+  pub name: String,
+  pub line: usize,
+  /// The line containing the link.
+  pub context: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetWikiLinksQuery {
+  #[serde(rename = "filePath")]
+  pub file_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WikiLinkResolveQuery {
+  /// Link as written, with or without the brackets, e.g. `Note#Heading|alias`.
+  pub link: String,
+}
+
+/// A wiki link of a doc and the doc it resolves to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WikiLinkReport {
+  #[serde(flatten)]
+  pub link: WikiLink,
+  #[serde(flatten)]
+  pub resolution: WikiLinkResolution,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,3 +311,83 @@ pub struct DeleteDocRequest {
   pub file_path: String,
   pub is_file: bool,
 }
+
+/// A single operation of a batch, paths are normalized like the single operation endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum BatchOperation {
+  #[serde(rename_all = "camelCase")]
+  Create { file_path: String, is_file: bool },
+  #[serde(rename_all = "camelCase")]
+  Move {
+    from_path: String,
+    to_path: String,
+    is_file: bool,
+    #[serde(default)]
+    update_links: bool,
+  },
+  #[serde(rename_all = "camelCase")]
+  Copy {
+    from_path: String,
+    to_path: String,
+    is_file: bool,
+  },
+  #[serde(rename_all = "camelCase")]
+  Rename {
+    file_path: String,
+    name: String,
+    is_file: bool,
+    #[serde(default)]
+    update_links: bool,
+  },
+  #[serde(rename_all = "camelCase")]
+  Delete { file_path: String, is_file: bool },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchRequest {
+  pub operations: Vec<BatchOperation>,
+  /// Stop at the first failure and undo the operations done so far.
+  #[serde(default)]
+  pub atomic: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchItemStatus {
+  Done,
+  Failed,
+  /// Done, then undone because a later operation of an atomic batch failed.
+  RolledBack,
+  /// Not attempted because an earlier operation of an atomic batch failed.
+  Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemResult {
+  pub status: BatchItemStatus,
+  pub error: Option<String>,
+  /// Links rewritten by a move or rename.
+  pub link_updates: Vec<LinkUpdate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResponse {
+  /// One result per operation, in the request order.
+  pub results: Vec<BatchItemResult>,
+  /// An atomic batch failed and all its operations were undone.
+  pub rolled_back: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderDocItemsRequest {
+  /// Folder whose items are ordered, empty for the workspace root.
+  #[serde(default)]
+  pub folder_path: String,
+  /// Item names in their new order, an empty list removes the manual order.
+  pub order: Vec<String>,
+}
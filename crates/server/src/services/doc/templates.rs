@@ -0,0 +1,76 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use regex::{Captures, Regex};
+
+static PLACEHOLDER_RE: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][\w-]*)\s*\}\}").unwrap());
+
+/// Placeholders filled in by the server when a doc is created from a template.
+pub const BUILTIN_VARIABLES: &[&str] = &["title", "date", "time", "path"];
+
+/// Replaces the `{{name}}` placeholders of a template with their values. Placeholders
+/// without a value are kept as written.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use server::services::doc::templates::render_template;
+///
+/// let variables = HashMap::from([("title".to_string(), "Standup".to_string())]);
+/// let content = render_template("# {{ title }}\n\n{{agenda}}", &variables);
+/// assert_eq!(content, "# Standup\n\n{{agenda}}");
+/// ```
+pub fn render_template(template: &str, variables: &HashMap<String, String>) -> String {
+  PLACEHOLDER_RE
+    .replace_all(template, |captures: &Captures| {
+      variables
+        .get(&captures[1])
+        .cloned()
+        .unwrap_or_else(|| captures[0].to_string())
+    })
+    .into_owned()
+}
+
+/// Names of the custom placeholders of a template, i.e. the values to prompt for, in
+/// order of first appearance.
+///
+/// # Example
+/// ```
+/// use server::services::doc::templates::template_variables;
+///
+/// let variables = template_variables("# {{title}}\n{{project}} - {{ owner }} - {{project}}");
+/// assert_eq!(variables, vec!["project", "owner"]);
+/// ```
+pub fn template_variables(template: &str) -> Vec<String> {
+  let mut variables: Vec<String> = Vec::new();
+  for captures in PLACEHOLDER_RE.captures_iter(template) {
+    let name = &captures[1];
+    if !BUILTIN_VARIABLES.contains(&name) && !variables.iter().any(|v| v == name) {
+      variables.push(name.to_string());
+    }
+  }
+  variables
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render_template_values() {
+    let variables = HashMap::from([
+      ("title".to_string(), "Plan".to_string()),
+      ("path".to_string(), "work/Plan".to_string()),
+      ("owner".to_string(), "{{title}}".to_string()),
+    ]);
+    let content = render_template(
+      "---\ntitle: {{title}}\n---\n{{path}} {{owner}} {{ missing }} {{not a placeholder}}",
+      &variables,
+    );
+    // values are not substituted again
+    assert_eq!(
+      content,
+      "---\ntitle: Plan\n---\nwork/Plan {{title}} {{ missing }} {{not a placeholder}}"
+    );
+  }
+}
@@ -1,26 +1,22 @@
 #[cfg(test)]
 mod tests {
-  use crate::services::doc::{DocService, denormalize_path, normalize_path};
-  use crate::services::settings::{Settings, SettingsService};
-  use std::{
-    fs,
-    sync::{Arc, Mutex},
+  use crate::services::doc::{
+    ArticleConflict, DocExistsConflict, DocService, content_version, denormalize_path,
+    normalize_path,
   };
+  use crate::services::search::SearchService;
+  use crate::services::settings::{DocSortMode, Settings, SettingsService};
+  use std::{fs, sync::Arc};
 
   /// Creates a temporary directory and returns a DocService instance configured to use it.
   fn setup_test_service() -> (DocService, tempfile::TempDir) {
     let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
-    let settings = Settings {
-      doc_root_path: temp_dir.path().to_path_buf(),
-      ignore_dirs: vec![".git".to_string(), "node_modules".to_string()],
-    };
-
-    let settings_service = SettingsService {
-      settings: Arc::new(Mutex::new(settings)),
-      editor_settings_file: temp_dir.path().join("editor-settings.json"),
-    };
-
-    let doc_service = DocService::new(Arc::new(settings_service));
+    let settings_service = Arc::new(SettingsService::for_test(
+      temp_dir.path().to_path_buf(),
+      &[".git", "node_modules"],
+      temp_dir.path().join("editor-settings.json"),
+    ));
+    let doc_service = DocService::for_test(settings_service);
     (doc_service, temp_dir)
   }
 
@@ -30,7 +26,7 @@ mod tests {
     #[test]
     fn test_get_sub_doc_items() {
       let (service, _temp_dir) = setup_test_service();
-      let docs = service.get_sub_doc_items("", false).unwrap();
+      let docs = service.get_sub_doc_items("", false, false).unwrap();
       assert!(docs.is_empty());
     }
 
@@ -146,7 +142,7 @@ mod tests {
     #[test]
     fn test_update_article_creates_parent_dirs() {
       let (service, _temp_dir) = setup_test_service();
-      let doc_path = normalize_path(&vec![
+      let doc_path = normalize_path(&[
         "nested".to_string(),
         "deep".to_string(),
         "article".to_string(),
@@ -169,6 +165,159 @@ mod tests {
       assert!(result.is_err());
     }
 
+    #[test]
+    fn test_get_article_headings_and_keywords() {
+      let (service, _temp_dir) = setup_test_service();
+      let doc_path = "structured";
+      let content = "# Title\n\nSome **keyword** here.\n\nSection\n-------\n";
+
+      service.update_article(doc_path, content).unwrap();
+
+      let article = service.get_article(doc_path).unwrap().unwrap();
+      assert_eq!(article.headings.len(), 2);
+      assert_eq!(article.headings[0].text, "Title");
+      assert_eq!(article.headings[0].level, 1);
+      assert_eq!(article.headings[1].id, "section");
+      assert_eq!(article.headings[1].level, 2);
+      assert_eq!(article.keywords, vec!["keyword"]);
+
+      // the cached tree carries the same structure and is refreshed on save
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree[doc_path].headings, article.headings);
+      service.update_article(doc_path, "# Renamed").unwrap();
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree[doc_path].headings[0].text, "Renamed");
+      assert!(tree[doc_path].keywords.is_empty());
+    }
+
+    #[test]
+    fn test_update_front_matter() {
+      let (service, _temp_dir) = setup_test_service();
+      let doc_path = "meta";
+      let content = "---\ntitle: Meta\n# keep me\nstatus: draft\n---\n\n# Body\n";
+      service.update_article(doc_path, content).unwrap();
+
+      let article = service.get_article(doc_path).unwrap().unwrap();
+      assert_eq!(article.front_matter.as_ref().unwrap()["status"], "draft");
+
+      let fields = serde_json::json!({ "status": "done", "tags": ["a", "b"] });
+      let article = service
+        .update_front_matter(doc_path, fields.as_object().unwrap())
+        .unwrap();
+      assert_eq!(
+        article.content,
+        "---\ntitle: Meta\n# keep me\nstatus: done\ntags: [\"a\",\"b\"]\n---\n\n# Body\n"
+      );
+      assert_eq!(article.keywords, vec!["a", "b"]);
+
+      // listings expose the front matter as well
+      let docs = service.get_sub_doc_items("", false, false).unwrap();
+      let doc = docs.iter().find(|doc| doc.name == "meta").unwrap();
+      assert_eq!(doc.front_matter.as_ref().unwrap()["title"], "Meta");
+
+      let fields = serde_json::json!({ "status": null });
+      let article = service
+        .update_front_matter(doc_path, fields.as_object().unwrap())
+        .unwrap();
+      assert!(article.front_matter.unwrap().get("status").is_none());
+
+      assert!(
+        service
+          .update_front_matter("missing", fields.as_object().unwrap())
+          .is_err()
+      );
+    }
+
+    #[test]
+    fn test_listing_uses_cached_front_matter() {
+      let (service, temp_dir) = setup_test_service();
+      service
+        .update_article("meta", "---\nstatus: draft\n---\n")
+        .unwrap();
+      service.get_doc_tree(false).unwrap();
+
+      // saves refresh the cached front matter, the file is not read again
+      service
+        .update_article("meta", "---\nstatus: done\n---\n")
+        .unwrap();
+      fs::write(temp_dir.path().join("meta.md"), "---\nstatus: stale\n---\n").unwrap();
+      let docs = service.get_sub_doc_items("", false, false).unwrap();
+      assert_eq!(docs[0].front_matter.as_ref().unwrap()["status"], "done");
+
+      service.invalidate_doc_tree();
+      let docs = service.get_sub_doc_items("", false, false).unwrap();
+      assert_eq!(docs[0].front_matter.as_ref().unwrap()["status"], "stale");
+    }
+
+    #[test]
+    fn test_update_article_if_match() {
+      let (service, temp_dir) = setup_test_service();
+      let doc_path = "versioned";
+      service.update_article(doc_path, "first").unwrap();
+
+      let article = service.get_article(doc_path).unwrap().unwrap();
+      assert_eq!(article.version, content_version(b"first"));
+
+      let version = service
+        .update_article_if_match(doc_path, "second", Some(&article.version))
+        .unwrap();
+      assert_eq!(version, content_version(b"second"));
+
+      // another tab still holds the first version
+      let err = service
+        .update_article_if_match(doc_path, "stale", Some(&article.version))
+        .unwrap_err();
+      let conflict = err.downcast::<ArticleConflict>().unwrap();
+      assert_eq!(conflict.content.as_deref(), Some("second"));
+      assert_eq!(conflict.version, Some(version));
+      assert_eq!(
+        service.get_article(doc_path).unwrap().unwrap().content,
+        "second"
+      );
+
+      // the file was removed externally
+      fs::remove_file(temp_dir.path().join("versioned.md")).unwrap();
+      let err = service
+        .update_article_if_match(doc_path, "stale", Some(&article.version))
+        .unwrap_err();
+      let conflict = err.downcast::<ArticleConflict>().unwrap();
+      assert!(conflict.content.is_none() && conflict.version.is_none());
+
+      // unconditional saves still go through
+      service
+        .update_article_if_match(doc_path, "forced", None)
+        .unwrap();
+      assert_eq!(
+        service.get_article(doc_path).unwrap().unwrap().content,
+        "forced"
+      );
+    }
+
+    #[test]
+    fn test_update_article_keeps_crlf_and_version() {
+      let (service, temp_dir) = setup_test_service();
+      let file = temp_dir.path().join("windows.md");
+      fs::write(&file, "# Title\r\nold\r\n").unwrap();
+
+      let article = service.get_article("windows").unwrap().unwrap();
+      let version = service
+        .update_article_if_match("windows", "# Title\nnew\n", Some(&article.version))
+        .unwrap();
+
+      assert_eq!(fs::read_to_string(&file).unwrap(), "# Title\r\nnew\r\n");
+      // the returned version matches what a reload sees, so the next save doesn't conflict
+      assert_eq!(
+        service.get_article("windows").unwrap().unwrap().version,
+        version
+      );
+      let entries: Vec<_> = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+        .collect();
+      assert!(entries.is_empty());
+    }
+
     #[test]
     fn test_get_article_existing() {
       let (service, _temp_dir) = setup_test_service();
@@ -199,7 +348,7 @@ mod tests {
 
       // Copy file
       service
-        .copy_cut_doc(source_path, dest_path, true, true)
+        .copy_cut_doc(source_path, dest_path, true, true, false)
         .unwrap();
 
       // Verify both files exist
@@ -226,7 +375,7 @@ mod tests {
 
       // Move file
       service
-        .copy_cut_doc(source_path, dest_path, false, true)
+        .copy_cut_doc(source_path, dest_path, false, true, false)
         .unwrap();
 
       // Verify source is gone and dest exists
@@ -245,7 +394,7 @@ mod tests {
       let (service, _temp_dir) = setup_test_service();
       let source_path = "source-dir";
       let dest_path = "dest-dir";
-      let file_path = normalize_path(&vec!["source-dir".to_string(), "file".to_string()]);
+      let file_path = normalize_path(&["source-dir".to_string(), "file".to_string()]);
       let content = "# File Content";
 
       // Create source directory with file
@@ -255,7 +404,7 @@ mod tests {
 
       // Copy directory
       service
-        .copy_cut_doc(source_path, dest_path, true, false)
+        .copy_cut_doc(source_path, dest_path, true, false, false)
         .unwrap();
 
       // Verify both directories exist
@@ -265,7 +414,7 @@ mod tests {
       assert!(dest_fs.exists());
 
       // Verify file in copied directory
-      let dest_file_path = normalize_path(&vec!["dest-dir".to_string(), "file".to_string()]);
+      let dest_file_path = normalize_path(&["dest-dir".to_string(), "file".to_string()]);
       let dest_article = service.get_article(&dest_file_path).unwrap().unwrap();
       assert_eq!(dest_article.content, content);
     }
@@ -274,7 +423,7 @@ mod tests {
     fn test_copy_cut_invalid_parent_path() {
       let (service, _temp_dir) = setup_test_service();
       let source_path = "source-file";
-      let invalid_dest = normalize_path(&vec![
+      let invalid_dest = normalize_path(&[
         "nonexistent".to_string(),
         "parent".to_string(),
         "dest".to_string(),
@@ -283,7 +432,142 @@ mod tests {
       service.create_doc(source_path, true).unwrap();
 
       // Should fail because parent doesn't exist
-      let result = service.copy_cut_doc(source_path, &invalid_dest, true, true);
+      let result = service.copy_cut_doc(source_path, &invalid_dest, true, true, false);
+      assert!(result.is_err());
+    }
+  }
+
+  mod sandbox {
+    use super::*;
+
+    #[test]
+    fn test_rejects_traversal_paths() {
+      let (service, temp_dir) = setup_test_service();
+      let outside = temp_dir.path().parent().unwrap().join("escaped");
+
+      for hostile in ["..%2Fescaped", "a%2F..%2F..%2Fescaped", "%2E%2E%2Fescaped"] {
+        assert!(service.create_doc(hostile, true).is_err());
+        assert!(service.create_doc(hostile, false).is_err());
+        assert!(service.update_article(hostile, "pwned").is_err());
+        assert!(service.get_article(hostile).is_err());
+        assert!(service.delete_doc(hostile, false).is_err());
+        assert!(service.get_sub_doc_items(hostile, false, false).is_err());
+      }
+      assert!(!outside.exists());
+      assert!(!outside.with_extension("md").exists());
+    }
+
+    #[test]
+    fn test_rename_rejects_traversal_name() {
+      let (service, temp_dir) = setup_test_service();
+      service.create_doc("doc", true).unwrap();
+
+      let result = service.modify_name("doc", "../escaped", true, false);
+      assert!(result.is_err());
+      assert!(service.path_convertor("doc", true).unwrap().exists());
+      assert!(
+        !temp_dir
+          .path()
+          .parent()
+          .unwrap()
+          .join("escaped.md")
+          .exists()
+      );
+    }
+
+    #[test]
+    fn test_copy_cut_rejects_traversal_paste_path() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("doc", true).unwrap();
+
+      let result = service.copy_cut_doc("doc", "..%2Fescaped", true, true, false);
+      assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_symlink_escapes() {
+      let (service, temp_dir) = setup_test_service();
+      let outside = tempfile::tempdir().unwrap();
+      fs::write(outside.path().join("secret.md"), "secret").unwrap();
+      std::os::unix::fs::symlink(outside.path(), temp_dir.path().join("linked")).unwrap();
+
+      assert!(service.get_article("linked%2Fsecret").is_err());
+      assert!(service.update_article("linked%2Fsecret", "pwned").is_err());
+      assert!(service.get_sub_doc_items("linked", false, false).is_err());
+      assert_eq!(
+        fs::read_to_string(outside.path().join("secret.md")).unwrap(),
+        "secret"
+      );
+    }
+  }
+
+  #[cfg(target_os = "linux")]
+  mod home_root {
+    use super::*;
+    use std::path::Component;
+
+    /// Splits an absolute path into its normal components, e.g. `/tmp/.tmpX` -> `["tmp", ".tmpX"]`.
+    fn path_segments(path: &std::path::Path) -> Vec<String> {
+      path
+        .components()
+        .filter_map(|c| match c {
+          Component::Normal(p) => Some(p.to_string_lossy().to_string()),
+          _ => None,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_home_root_lists_filesystem_root() {
+      let (service, temp_dir) = setup_test_service();
+      let docs = service.get_sub_doc_items("", true, false).unwrap();
+
+      assert!(!docs.is_empty());
+      for doc in &docs {
+        // every item is prefixed with the "/" root for the UI
+        assert_eq!(doc.path[0], "/");
+        assert_eq!(doc.path.len(), 2);
+        assert!(!doc.name.starts_with('.'));
+      }
+
+      let top_level = path_segments(temp_dir.path())[0].clone();
+      assert!(docs.iter().any(|d| d.name == top_level && !d.is_file));
+    }
+
+    #[test]
+    fn test_home_root_lists_nested_folder() {
+      let (service, temp_dir) = setup_test_service();
+      fs::create_dir_all(temp_dir.path().join("notes")).unwrap();
+      fs::write(temp_dir.path().join("readme.md"), "").unwrap();
+      fs::write(temp_dir.path().join("image.png"), "").unwrap();
+
+      let segments = path_segments(temp_dir.path());
+      let docs = service
+        .get_sub_doc_items(&normalize_path(&segments), true, false)
+        .unwrap();
+
+      assert_eq!(docs.len(), 2);
+
+      let mut expected_dir_path = vec!["/".to_string()];
+      expected_dir_path.extend(segments.clone());
+      expected_dir_path.push("notes".to_string());
+      assert_eq!(docs[0].name, "notes");
+      assert!(!docs[0].is_file);
+      assert_eq!(docs[0].path, expected_dir_path);
+
+      let mut expected_file_path = vec!["/".to_string()];
+      expected_file_path.extend(segments);
+      expected_file_path.push("readme".to_string());
+      assert_eq!(docs[1].name, "readme");
+      assert!(docs[1].is_file);
+      assert_eq!(docs[1].path, expected_file_path);
+    }
+
+    #[test]
+    fn test_home_root_nonexistent_folder() {
+      let (service, _temp_dir) = setup_test_service();
+      let result = service.get_sub_doc_items("definitely-not-a-real-root-folder", true, false);
       assert!(result.is_err());
     }
   }
@@ -306,18 +590,18 @@ mod tests {
       fs::File::create(&fs_path).unwrap();
       service.update_article(old_path, content).unwrap();
 
-      let docs = service.get_sub_doc_items("", false).unwrap();
+      let docs = service.get_sub_doc_items("", false, false).unwrap();
       assert_eq!(docs.len(), 1);
       let actual_old_path = normalize_path(&docs[0].path);
 
       // Rename file using the actual normalized path from cache
       service
-        .modify_name(&actual_old_path, new_name, true)
+        .modify_name(&actual_old_path, new_name, true, false)
         .unwrap();
 
       // Verify old path doesn't exist, new path exists
       let old_fs = service.path_convertor(&actual_old_path, true).unwrap();
-      let new_path = normalize_path(&vec![new_name.to_string()]);
+      let new_path = normalize_path(&[new_name.to_string()]);
       let new_fs = service.path_convertor(&new_path, true).unwrap();
       assert!(!old_fs.exists());
       assert!(new_fs.exists());
@@ -332,7 +616,7 @@ mod tests {
       let (service, _temp_dir) = setup_test_service();
       let old_path = "old-dir";
       let new_name = "new-dir";
-      let file_path = normalize_path(&vec!["old-dir".to_string(), "file".to_string()]);
+      let file_path = normalize_path(&["old-dir".to_string(), "file".to_string()]);
       let content = "# File Content";
 
       // Create directory with file on filesystem
@@ -340,11 +624,13 @@ mod tests {
       service.create_doc(&file_path, true).unwrap();
       service.update_article(&file_path, content).unwrap();
 
-      let docs = service.get_sub_doc_items("", false).unwrap();
+      let docs = service.get_sub_doc_items("", false, false).unwrap();
       assert_eq!(docs.len(), 1);
 
       // Rename directory using the actual normalized path from cache
-      service.modify_name(old_path, new_name, false).unwrap();
+      service
+        .modify_name(old_path, new_name, false, false)
+        .unwrap();
 
       // Verify old path doesn't exist, new path exists
       let old_fs = service.path_convertor(old_path, false).unwrap();
@@ -353,7 +639,7 @@ mod tests {
       assert!(new_fs.exists());
 
       // Verify file in renamed directory
-      let new_file_path = normalize_path(&vec!["new-dir".to_string(), "file".to_string()]);
+      let new_file_path = normalize_path(&["new-dir".to_string(), "file".to_string()]);
       let article = service.get_article(&new_file_path).unwrap().unwrap();
       assert_eq!(article.content, content);
     }
@@ -368,7 +654,9 @@ mod tests {
       service.update_article(doc_path, content).unwrap();
 
       // Rename to same name should be a no-op
-      service.modify_name(doc_path, "test-file", true).unwrap();
+      service
+        .modify_name(doc_path, "test-file", true, false)
+        .unwrap();
 
       // Verify file still exists with same content
       let article = service.get_article(doc_path).unwrap().unwrap();
@@ -379,11 +667,1200 @@ mod tests {
     fn test_modify_name_nonexistent_file() {
       let (service, _temp_dir) = setup_test_service();
       // Should not error, just return Ok(())
-      let result = service.modify_name("nonexistent", "new-name", true);
+      let result = service.modify_name("nonexistent", "new-name", true, false);
       assert!(result.is_ok());
     }
   }
 
+  mod tree {
+    use super::*;
+
+    #[test]
+    fn test_get_doc_tree_structure() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("parent", false).unwrap();
+      service.create_doc("parent%2Fchild", false).unwrap();
+      service.create_doc("parent%2Fchild%2Ffile", true).unwrap();
+      service.create_doc("root-file", true).unwrap();
+
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree.len(), 4);
+
+      let parent = &tree["parent"];
+      assert!(!parent.is_file);
+      assert_eq!(parent.parent_key, None);
+      assert_eq!(parent.children_keys, vec!["parent%2Fchild"]);
+
+      let child = &tree["parent%2Fchild"];
+      assert_eq!(child.parent_key.as_deref(), Some("parent"));
+      assert_eq!(child.children_keys, vec!["parent%2Fchild%2Ffile"]);
+
+      let file = &tree["parent%2Fchild%2Ffile"];
+      assert!(file.is_file);
+      assert_eq!(file.path, vec!["parent", "child", "file"]);
+      assert_eq!(file.parent_key.as_deref(), Some("parent%2Fchild"));
+      assert!(file.children_keys.is_empty());
+
+      assert_eq!(tree["root-file"].parent_key, None);
+    }
+
+    #[test]
+    fn test_get_doc_tree_with_cache() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("test-file", true).unwrap();
+      let tree1 = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree1.len(), 1);
+
+      // Files created outside of DocService are not visible until a refresh
+      let another_path = service.path_convertor("another-file", true).unwrap();
+      fs::write(&another_path, "").unwrap();
+      let tree2 = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree1, tree2);
+    }
+
+    #[test]
+    fn test_get_doc_tree_force_refresh() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("test-file", true).unwrap();
+      assert_eq!(service.get_doc_tree(false).unwrap().len(), 1);
+
+      let another_path = service.path_convertor("another-file", true).unwrap();
+      fs::write(&another_path, "").unwrap();
+
+      let tree = service.get_doc_tree(true).unwrap();
+      assert_eq!(tree.len(), 2);
+      assert!(tree.contains_key("another-file"));
+    }
+
+    #[test]
+    fn test_get_doc_tree_invalidated_by_operations() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("dir", false).unwrap();
+      assert_eq!(service.get_doc_tree(false).unwrap().len(), 1);
+
+      service.create_doc("dir%2Ffile", true).unwrap();
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree["dir"].children_keys, vec!["dir%2Ffile"]);
+
+      service
+        .modify_name("dir%2Ffile", "renamed", true, false)
+        .unwrap();
+      let tree = service.get_doc_tree(false).unwrap();
+      assert!(tree.contains_key("dir%2Frenamed"));
+      assert!(!tree.contains_key("dir%2Ffile"));
+
+      service
+        .copy_cut_doc("dir%2Frenamed", "moved", false, true, false)
+        .unwrap();
+      let tree = service.get_doc_tree(false).unwrap();
+      assert!(tree.contains_key("moved"));
+      assert!(tree["dir"].children_keys.is_empty());
+
+      service.delete_doc("dir", false).unwrap();
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree.len(), 1);
+      assert!(tree.contains_key("moved"));
+    }
+
+    #[test]
+    fn test_get_doc_tree_skips_ignored_dirs() {
+      let (service, temp_dir) = setup_test_service();
+      fs::create_dir_all(temp_dir.path().join("node_modules")).unwrap();
+      fs::write(temp_dir.path().join("node_modules").join("a.md"), "").unwrap();
+      fs::create_dir_all(temp_dir.path().join("_assets")).unwrap();
+      service.create_doc("visible", true).unwrap();
+
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree.len(), 1);
+      assert!(tree.contains_key("visible"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_doc_tree_symlink_cycle() {
+      let (service, temp_dir) = setup_test_service();
+      service.create_doc("dir", false).unwrap();
+      service.create_doc("dir%2Ffile", true).unwrap();
+      std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("dir").join("loop"))
+        .unwrap();
+
+      let tree = service.get_doc_tree(false).unwrap();
+      assert!(tree.contains_key("dir%2Ffile"));
+      assert!(tree["dir%2Floop"].children_keys.is_empty());
+    }
+  }
+
+  mod links {
+    use super::*;
+
+    fn setup_linked_docs() -> (DocService, tempfile::TempDir) {
+      let (service, temp_dir) = setup_test_service();
+      service
+        .update_article(
+          "js%2Farray",
+          "[map](./map.md#usage) [css](../css/flex.md) [root](/css/flex.md)",
+        )
+        .unwrap();
+      service
+        .update_article("js%2Fmap", "[back](array.md)")
+        .unwrap();
+      service
+        .update_article(
+          "css%2Fflex",
+          "[arr](../js/array.md#top) [ext](https://x.com) ![img](../_assets/a.png)",
+        )
+        .unwrap();
+      service
+        .update_article("readme", "[a](js/array)\n\n[b]: <js/array.md>\n")
+        .unwrap();
+      (service, temp_dir)
+    }
+
+    #[test]
+    fn test_preview_rename_does_not_write() {
+      let (service, _temp_dir) = setup_linked_docs();
+      let updates = service
+        .preview_link_updates("js%2Farray", "js%2Flist", true)
+        .unwrap();
+
+      let files: Vec<&str> = updates.iter().map(|u| u.file_path.as_str()).collect();
+      assert_eq!(files, vec!["css%2Fflex", "js%2Fmap", "readme"]);
+      assert_eq!(updates[0].changes[0].from, "../js/array.md#top");
+      assert_eq!(updates[0].changes[0].to, "../js/list.md#top");
+      let readme: Vec<&str> = updates[2].changes.iter().map(|c| c.to.as_str()).collect();
+      assert_eq!(readme, vec!["js/list", "js/list.md"]);
+      assert_eq!(updates[2].changes[1].line, 3);
+
+      // nothing is touched by a preview
+      assert_eq!(
+        service.get_article("js%2Fmap").unwrap().unwrap().content,
+        "[back](array.md)"
+      );
+    }
+
+    #[test]
+    fn test_rename_rewrites_links() {
+      let (service, _temp_dir) = setup_linked_docs();
+      let updates = service
+        .modify_name("js%2Farray", "list", true, true)
+        .unwrap();
+      assert_eq!(updates.len(), 3);
+
+      let article = |path: &str| service.get_article(path).unwrap().unwrap().content;
+      assert_eq!(article("js%2Fmap"), "[back](list.md)");
+      assert_eq!(article("readme"), "[a](js/list)\n\n[b]: <js/list.md>\n");
+      // the renamed doc itself didn't change folders, so its links stay as they are
+      assert_eq!(
+        article("js%2Flist"),
+        "[map](./map.md#usage) [css](../css/flex.md) [root](/css/flex.md)"
+      );
+    }
+
+    #[test]
+    fn test_move_folder_rewrites_links_inside_and_out() {
+      let (service, _temp_dir) = setup_linked_docs();
+      service.create_doc("docs", false).unwrap();
+      service
+        .copy_cut_doc("js", "docs%2Fjs", false, false, true)
+        .unwrap();
+
+      let article = |path: &str| service.get_article(path).unwrap().unwrap().content;
+      assert_eq!(
+        article("docs%2Fjs%2Farray"),
+        "[map](./map.md#usage) [css](../../css/flex.md) [root](/css/flex.md)"
+      );
+      assert_eq!(article("docs%2Fjs%2Fmap"), "[back](array.md)");
+      assert_eq!(
+        article("css%2Fflex"),
+        "[arr](../docs/js/array.md#top) [ext](https://x.com) ![img](../_assets/a.png)"
+      );
+    }
+
+    #[test]
+    fn test_links_untouched_without_update_links() {
+      let (service, _temp_dir) = setup_linked_docs();
+      let updates = service
+        .modify_name("js%2Farray", "list", true, false)
+        .unwrap();
+      assert!(updates.is_empty());
+      assert_eq!(
+        service.get_article("js%2Fmap").unwrap().unwrap().content,
+        "[back](array.md)"
+      );
+    }
+  }
+
+  mod backlinks {
+    use super::*;
+
+    #[test]
+    fn test_backlinks_with_context() {
+      let (service, _temp_dir) = setup_test_service();
+      service.update_article("js%2Farray", "# Array").unwrap();
+      service
+        .update_article("js%2Fmap", "# Map\n\nLike [array](./array.md#top).\n")
+        .unwrap();
+      service
+        .update_article(
+          "readme",
+          "[a](js/array) [self](readme.md)\n\n[x](js/array.md)",
+        )
+        .unwrap();
+
+      let backlinks = service.get_backlinks("js%2Farray").unwrap();
+      let found: Vec<(&str, usize, &str)> = backlinks
+        .iter()
+        .map(|b| (b.file_path.as_str(), b.line, b.context.as_str()))
+        .collect();
+      assert_eq!(
+        found,
+        vec![
+          ("js%2Fmap", 3, "Like [array](./array.md#top)."),
+          ("readme", 1, "[a](js/array) [self](readme.md)"),
+          ("readme", 3, "[x](js/array.md)"),
+        ]
+      );
+      assert_eq!(backlinks[0].name, "map");
+      // links to itself are not backlinks
+      assert!(service.get_backlinks("readme").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_refresh_changed_doc() {
+      let (service, temp_dir) = setup_test_service();
+      service.update_article("target", "# Target").unwrap();
+      service.update_article("source", "# Source").unwrap();
+      assert!(service.get_backlinks("target").unwrap().is_empty());
+
+      // edited outside of the editor
+      fs::write(
+        temp_dir.path().join("source.md"),
+        "# Source\n## Links\n[t](target.md)",
+      )
+      .unwrap();
+      assert!(service.refresh_changed_doc("source.md").unwrap());
+      assert_eq!(service.get_backlinks("target").unwrap().len(), 1);
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree["source"].headings.len(), 2);
+
+      // a doc the cached tree doesn't know yet
+      fs::write(temp_dir.path().join("new.md"), "# New").unwrap();
+      assert!(!service.refresh_changed_doc("new.md").unwrap());
+    }
+
+    #[test]
+    fn test_backlinks_follow_saves_renames_and_deletes() {
+      let (service, _temp_dir) = setup_test_service();
+      service.update_article("target", "# Target").unwrap();
+      service.update_article("source", "[t](target.md)").unwrap();
+      assert_eq!(service.get_backlinks("target").unwrap().len(), 1);
+
+      // save
+      service.update_article("source", "no links").unwrap();
+      assert!(service.get_backlinks("target").unwrap().is_empty());
+      service.update_article("other", "[t](./target.md)").unwrap();
+      assert_eq!(
+        service.get_backlinks("target").unwrap()[0].file_path,
+        "other"
+      );
+
+      // rename of the linking doc
+      service
+        .modify_name("other", "renamed", true, false)
+        .unwrap();
+      assert_eq!(
+        service.get_backlinks("target").unwrap()[0].file_path,
+        "renamed"
+      );
+
+      // rename of the target, with the links rewritten
+      service.modify_name("target", "moved", true, true).unwrap();
+      assert_eq!(
+        service.get_backlinks("moved").unwrap()[0].file_path,
+        "renamed"
+      );
+
+      // delete
+      service.delete_doc("renamed", true).unwrap();
+      assert!(service.get_backlinks("moved").unwrap().is_empty());
+    }
+  }
+
+  mod wiki_links {
+    use super::*;
+    use crate::services::doc::wiki_links::WikiLinkStatus;
+
+    #[test]
+    fn test_resolve_wiki_links() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("js", false).unwrap();
+      service.create_doc("ts", false).unwrap();
+      service.update_article("js%2FArray", "# Array").unwrap();
+      service.update_article("ts%2Farray", "# Array").unwrap();
+      service.update_article("Map Filter", "# Map").unwrap();
+
+      let resolution = service
+        .resolve_wiki_link("[[map filter#Usage|see]]")
+        .unwrap();
+      assert_eq!(resolution.status, WikiLinkStatus::Resolved);
+      assert_eq!(resolution.file_path.as_deref(), Some("Map%20Filter"));
+      assert_eq!(resolution.heading_id.as_deref(), Some("usage"));
+
+      let resolution = service.resolve_wiki_link("array").unwrap();
+      assert_eq!(resolution.status, WikiLinkStatus::Ambiguous);
+      assert_eq!(resolution.candidates, vec!["js%2FArray", "ts%2Farray"]);
+      assert_eq!(
+        service
+          .resolve_wiki_link("JS/array")
+          .unwrap()
+          .file_path
+          .as_deref(),
+        Some("js%2FArray")
+      );
+      assert_eq!(
+        service.resolve_wiki_link("missing").unwrap().status,
+        WikiLinkStatus::Unresolved
+      );
+    }
+
+    #[test]
+    fn test_report_doc_wiki_links() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("js", false).unwrap();
+      service.update_article("js%2Farray", "# Array").unwrap();
+      service
+        .update_article(
+          "readme",
+          "# Readme\n\n[[Array|arrays]] [[nope]]\n\n[[#Readme]]\n",
+        )
+        .unwrap();
+
+      let reports = service.get_wiki_links("readme").unwrap();
+      let found: Vec<(&str, usize, WikiLinkStatus, Option<&str>)> = reports
+        .iter()
+        .map(|report| {
+          (
+            report.link.raw.as_str(),
+            report.link.line,
+            report.resolution.status,
+            report.resolution.file_path.as_deref(),
+          )
+        })
+        .collect();
+      assert_eq!(
+        found,
+        vec![
+          (
+            "[[Array|arrays]]",
+            3,
+            WikiLinkStatus::Resolved,
+            Some("js%2Farray")
+          ),
+          ("[[nope]]", 3, WikiLinkStatus::Unresolved, None),
+          ("[[#Readme]]", 5, WikiLinkStatus::Resolved, Some("readme")),
+        ]
+      );
+    }
+  }
+
+  mod extensions {
+    use super::*;
+
+    fn set_doc_extensions(service: &DocService, extensions: &[&str]) {
+      service
+        .settings_service
+        .settings
+        .lock()
+        .unwrap()
+        .doc_extensions = extensions.iter().map(|ext| ext.to_string()).collect();
+    }
+
+    #[test]
+    fn test_docs_keep_their_extension() {
+      let (service, temp_dir) = setup_test_service();
+      set_doc_extensions(&service, &["md", "markdown", ".txt"]);
+      fs::write(temp_dir.path().join("array.md"), "# Array").unwrap();
+      fs::write(temp_dir.path().join("map.markdown"), "# Map").unwrap();
+      fs::write(temp_dir.path().join("todo.txt"), "- [ ] todo").unwrap();
+      fs::write(temp_dir.path().join("page.mdx"), "# Page").unwrap();
+
+      let docs = service.get_sub_doc_items("", false, false).unwrap();
+      let names: Vec<&str> = docs.iter().map(|doc| doc.name.as_str()).collect();
+      assert_eq!(names, vec!["array", "map.markdown", "todo.txt"]);
+
+      let article = service.get_article("map.markdown").unwrap().unwrap();
+      assert_eq!(article.headings[0].text, "Map");
+      service.update_article("todo.txt", "- [x] todo").unwrap();
+      assert_eq!(
+        fs::read_to_string(temp_dir.path().join("todo.txt")).unwrap(),
+        "- [x] todo"
+      );
+      assert!(!temp_dir.path().join("todo.txt.md").exists());
+    }
+
+    #[test]
+    fn test_create_and_rename_with_extensions() {
+      let (service, temp_dir) = setup_test_service();
+      set_doc_extensions(&service, &["md", "txt"]);
+
+      service.create_doc("notes.txt", true).unwrap();
+      service.create_doc("plain", true).unwrap();
+      assert!(temp_dir.path().join("notes.txt").exists());
+      assert!(temp_dir.path().join("plain.md").exists());
+
+      // the real extension is kept unless the new name has another doc extension
+      service
+        .modify_name("notes.txt", "ideas", true, false)
+        .unwrap();
+      assert!(temp_dir.path().join("ideas.txt").exists());
+      service
+        .modify_name("plain", "plain.txt", true, false)
+        .unwrap();
+      assert!(temp_dir.path().join("plain.txt").exists());
+      assert!(!temp_dir.path().join("plain.md").exists());
+
+      let tree = service.get_doc_tree(false).unwrap();
+      let mut keys: Vec<&String> = tree.keys().collect();
+      keys.sort();
+      assert_eq!(keys, vec!["ideas.txt", "plain.txt"]);
+    }
+
+    #[test]
+    fn test_rename_to_name_with_doc_extension() {
+      let (service, temp_dir) = setup_test_service();
+      set_doc_extensions(&service, &["md", "txt"]);
+      service.create_doc("note.txt", true).unwrap();
+
+      // the new name's own extension replaces the old one
+      service
+        .modify_name("note.txt", "foo.md", true, false)
+        .unwrap();
+      assert!(temp_dir.path().join("foo.md").exists());
+      assert!(!temp_dir.path().join("foo.md.txt").exists());
+      assert!(!temp_dir.path().join("note.txt").exists());
+    }
+
+    #[test]
+    fn test_ambiguous_doc_path_is_rejected() {
+      let (service, temp_dir) = setup_test_service();
+      set_doc_extensions(&service, &["md", "markdown"]);
+      fs::write(temp_dir.path().join("a.markdown"), "# A").unwrap();
+      fs::write(temp_dir.path().join("a.markdown.md"), "# A md").unwrap();
+
+      assert!(service.get_article("a.markdown").is_err());
+    }
+
+    #[test]
+    fn test_links_to_other_extensions() {
+      let (service, temp_dir) = setup_test_service();
+      set_doc_extensions(&service, &["md", "mdx"]);
+      fs::write(temp_dir.path().join("page.mdx"), "# Page").unwrap();
+      service
+        .update_article("readme", "[page](./page.mdx) [img](./logo.png)")
+        .unwrap();
+
+      let backlinks = service.get_backlinks("page.mdx").unwrap();
+      assert_eq!(backlinks.len(), 1);
+      assert_eq!(backlinks[0].file_path, "readme");
+    }
+  }
+
+  mod templates {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_create_doc_from_template() {
+      let (service, temp_dir) = setup_test_service();
+      let templates_dir = temp_dir.path().join("_templates");
+      fs::create_dir_all(templates_dir.join("work")).unwrap();
+      fs::write(
+        templates_dir.join("work").join("meeting.md"),
+        "---\ntitle: {{title}}\n---\n# {{title}} ({{path}})\n\nProject: {{project}}\nOwner: {{owner}}\n",
+      )
+      .unwrap();
+
+      let variables = HashMap::from([("project".to_string(), "Editor".to_string())]);
+      let doc = service
+        .create_doc_from_template("notes%2Fstandup", "work/meeting", &variables)
+        .unwrap();
+      assert_eq!(doc.name, "standup");
+      assert_eq!(doc.front_matter.unwrap()["title"], "standup");
+
+      let content = fs::read_to_string(temp_dir.path().join("notes").join("standup.md")).unwrap();
+      assert_eq!(
+        content,
+        "---\ntitle: standup\n---\n# standup (notes/standup)\n\nProject: Editor\nOwner: {{owner}}\n"
+      );
+
+      // existing docs are not overwritten
+      let err = service
+        .create_doc_from_template("notes%2Fstandup", "work/meeting", &variables)
+        .unwrap_err();
+      assert_eq!(
+        err.downcast::<DocExistsConflict>().unwrap().file_path,
+        "notes%2Fstandup"
+      );
+      assert!(
+        service
+          .create_doc_from_template("other", "missing", &variables)
+          .is_err()
+      );
+    }
+
+    #[test]
+    fn test_list_templates() {
+      let (service, temp_dir) = setup_test_service();
+      assert!(service.list_templates().unwrap().is_empty());
+
+      let templates_dir = temp_dir.path().join("_templates");
+      fs::create_dir_all(templates_dir.join("work")).unwrap();
+      fs::write(templates_dir.join("Daily.md"), "# {{date}}").unwrap();
+      fs::write(
+        templates_dir.join("work").join("meeting.md"),
+        "{{project}} {{ owner }}",
+      )
+      .unwrap();
+      fs::write(templates_dir.join("logo.png"), "").unwrap();
+
+      let templates = service.list_templates().unwrap();
+      let found: Vec<(&str, &str, Vec<String>)> = templates
+        .iter()
+        .map(|t| (t.name.as_str(), t.file_path.as_str(), t.variables.clone()))
+        .collect();
+      assert_eq!(
+        found,
+        vec![
+          ("Daily", "_templates%2FDaily", vec![]),
+          (
+            "work/meeting",
+            "_templates%2Fwork%2Fmeeting",
+            vec!["project".to_string(), "owner".to_string()]
+          ),
+        ]
+      );
+    }
+
+    #[test]
+    fn test_templates_are_not_docs() {
+      let (service, temp_dir) = setup_test_service();
+      let templates_dir = temp_dir.path().join("_templates");
+      fs::create_dir_all(&templates_dir).unwrap();
+      fs::write(templates_dir.join("meeting.md"), "# {{title}}").unwrap();
+      fs::create_dir_all(temp_dir.path().join("work").join("_templates")).unwrap();
+      fs::write(
+        temp_dir
+          .path()
+          .join("work")
+          .join("_templates")
+          .join("meeting.md"),
+        "# Meeting",
+      )
+      .unwrap();
+      fs::write(temp_dir.path().join("notes.md"), "see [[meeting]]").unwrap();
+
+      let tree = service.get_doc_tree(true).unwrap();
+      assert!(!tree.contains_key("_templates"));
+      assert!(!tree.contains_key("_templates%2Fmeeting"));
+      // only the templates folder of the workspace root is skipped
+      assert!(tree.contains_key("work%2F_templates%2Fmeeting"));
+
+      let search = SearchService::new(service.settings_service.clone());
+      let found: Vec<Vec<String>> = search
+        .search_file_names("meeting")
+        .unwrap()
+        .into_iter()
+        .map(|found| found.path)
+        .collect();
+      assert_eq!(found, vec![vec!["work", "_templates", "meeting"]]);
+    }
+  }
+
+  mod history {
+    use super::*;
+    use crate::services::history::HISTORY_DIR;
+
+    #[test]
+    fn test_saves_record_versions() {
+      let (service, temp_dir) = setup_test_service();
+      // a doc written before the history existed
+      fs::write(temp_dir.path().join("note.md"), "v0").unwrap();
+
+      service.update_article("note", "v1").unwrap();
+      service.update_article("note", "v1").unwrap();
+      service.update_article("note", "v2").unwrap();
+
+      let versions = service.list_versions("note").unwrap();
+      let contents: Vec<String> = versions
+        .iter()
+        .map(|v| service.get_version("note", &v.id).unwrap().content)
+        .collect();
+      // unchanged saves are not recorded again
+      assert_eq!(contents, vec!["v2", "v1", "v0"]);
+      assert_eq!(versions[0].version, content_version(b"v2"));
+      assert!(
+        temp_dir
+          .path()
+          .join(HISTORY_DIR)
+          .join(".gitignore")
+          .exists()
+      );
+      assert!(service.get_doc_tree(false).unwrap().contains_key("note"));
+      assert_eq!(service.get_doc_tree(false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_restore_version() {
+      let (service, temp_dir) = setup_test_service();
+      service.update_article("note", "first").unwrap();
+      service.update_article("note", "second").unwrap();
+      let first = service.list_versions("note").unwrap()[1].clone();
+
+      let version = service.restore_version("note", &first.id).unwrap();
+      assert_eq!(version, first.version);
+      assert_eq!(
+        fs::read_to_string(temp_dir.path().join("note.md")).unwrap(),
+        "first"
+      );
+      // the restore is a new version, so "second" can be restored again
+      assert_eq!(service.list_versions("note").unwrap().len(), 3);
+      assert!(service.restore_version("note", "missing").is_err());
+    }
+
+    #[test]
+    fn test_max_versions_retention() {
+      let (service, _temp_dir) = setup_test_service();
+      service
+        .settings_service
+        .settings
+        .lock()
+        .unwrap()
+        .history_max_versions = 2;
+      for content in ["a", "b", "c"] {
+        service.update_article("note", content).unwrap();
+      }
+
+      let versions = service.list_versions("note").unwrap();
+      assert_eq!(versions.len(), 2);
+      assert_eq!(
+        service
+          .get_version("note", &versions[1].id)
+          .unwrap()
+          .content,
+        "b"
+      );
+    }
+
+    #[test]
+    fn test_history_follows_renames_and_moves() {
+      let (service, _temp_dir) = setup_test_service();
+      service.create_doc("js", false).unwrap();
+      service.update_article("js%2Farray", "v1").unwrap();
+      service.update_article("js%2Farray", "v2").unwrap();
+
+      service
+        .modify_name("js%2Farray", "list", true, false)
+        .unwrap();
+      assert_eq!(service.list_versions("js%2Flist").unwrap().len(), 2);
+      assert!(service.list_versions("js%2Farray").unwrap().is_empty());
+
+      service.modify_name("js", "ts", false, false).unwrap();
+      service.create_doc("archive", false).unwrap();
+      service
+        .copy_cut_doc("ts", "archive%2Fts", false, false, false)
+        .unwrap();
+      let versions = service.list_versions("archive%2Fts%2Flist").unwrap();
+      assert_eq!(
+        service
+          .get_version("archive%2Fts%2Flist", &versions[0].id)
+          .unwrap()
+          .content,
+        "v2"
+      );
+    }
+
+    #[test]
+    fn test_history_of_deep_paths() {
+      let (service, temp_dir) = setup_test_service();
+      let segments: Vec<String> = (0..6)
+        .map(|i| format!("folder-{}-{}", i, "x".repeat(30)))
+        .collect();
+      let folder = normalize_path(&segments);
+      service.create_doc(&folder, false).unwrap();
+      let doc = normalize_path(&[&segments[..], &["note".to_string()]].concat());
+      service.update_article(&doc, "deep").unwrap();
+
+      assert_eq!(service.list_versions(&doc).unwrap().len(), 1);
+      for entry in fs::read_dir(temp_dir.path().join(HISTORY_DIR)).unwrap() {
+        assert!(entry.unwrap().file_name().len() < 255);
+      }
+    }
+  }
+
+  mod ordering {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn item_names(service: &DocService, folder: &str) -> Vec<String> {
+      service
+        .get_sub_doc_items(folder, false, false)
+        .unwrap()
+        .into_iter()
+        .map(|item| item.name)
+        .collect()
+    }
+
+    #[test]
+    fn test_natural_order_folders_first() {
+      let (service, _temp_dir) = setup_test_service();
+      for name in ["10-intro", "2-setup", "Appendix", "1-basics"] {
+        service.create_doc(name, true).unwrap();
+      }
+      service.create_doc("part 10", false).unwrap();
+      service.create_doc("part 9", false).unwrap();
+
+      assert_eq!(
+        item_names(&service, ""),
+        vec![
+          "part 9", "part 10", "1-basics", "2-setup", "10-intro", "Appendix"
+        ]
+      );
+    }
+
+    #[test]
+    fn test_sort_by_modified_time() {
+      let (service, _temp_dir) = setup_test_service();
+      let now = SystemTime::now();
+      for (name, age) in [("old", 300), ("new", 0), ("middle", 100)] {
+        service.create_doc(name, true).unwrap();
+        let path = service.path_convertor(name, true).unwrap();
+        fs::File::options()
+          .write(true)
+          .open(path)
+          .unwrap()
+          .set_modified(now - Duration::from_secs(age))
+          .unwrap();
+      }
+      service.settings_service.settings.lock().unwrap().sort_mode = DocSortMode::Modified;
+
+      assert_eq!(item_names(&service, ""), vec!["new", "middle", "old"]);
+    }
+
+    #[test]
+    fn test_manual_order_is_saved_per_folder() {
+      let (service, temp_dir) = setup_test_service();
+      for name in [
+        "book%2Fintro",
+        "book%2Fsetup",
+        "book%2Fappendix",
+        "book%2Fextra",
+      ] {
+        service.create_doc(name, true).unwrap();
+      }
+      service.settings_service.settings.lock().unwrap().sort_mode = DocSortMode::Manual;
+
+      let order = vec![
+        "setup".to_string(),
+        "appendix".to_string(),
+        "intro".to_string(),
+      ];
+      let items = service.reorder_doc_items("book", order).unwrap();
+      let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+      // unordered items follow by name
+      assert_eq!(names, vec!["setup", "appendix", "intro", "extra"]);
+      assert!(temp_dir.path().join(".doc-order.json").exists());
+      // the tree follows the manual order too
+      let tree = service.get_doc_tree(false).unwrap();
+      assert_eq!(tree["book"].children_keys[0], "book%2Fsetup");
+
+      // a renamed item keeps its place
+      service
+        .modify_name("book%2Fappendix", "notes", true, false)
+        .unwrap();
+      assert_eq!(
+        item_names(&service, "book"),
+        vec!["setup", "notes", "intro", "extra"]
+      );
+
+      service.reorder_doc_items("book", Vec::new()).unwrap();
+      assert_eq!(
+        item_names(&service, "book"),
+        vec!["extra", "intro", "notes", "setup"]
+      );
+      assert!(service.reorder_doc_items("missing", Vec::new()).is_err());
+    }
+  }
+
+  mod metadata {
+    use super::*;
+    use crate::services::doc::DocItem;
+    use crate::services::git::StatusType;
+
+    fn find<'a>(items: &'a [DocItem], name: &str) -> &'a DocItem {
+      items.iter().find(|item| item.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_item_metadata() {
+      let (service, _temp_dir) = setup_test_service();
+      let content = "---\ntitle: Array\n---\n# Array\n\nMap **and** filter.\n";
+      service.create_doc("js%2Farray", true).unwrap();
+      service.write_article("js%2Farray", content).unwrap();
+      service.create_doc("js%2Fbasic", false).unwrap();
+      service.create_doc("js%2Fnode_modules", false).unwrap();
+      fs::write(
+        service
+          .path_convertor("js", false)
+          .unwrap()
+          .join("logo.png"),
+        "",
+      )
+      .unwrap();
+
+      let items = service.get_sub_doc_items("", false, true).unwrap();
+      let js = &find(&items, "js").metadata;
+      assert_eq!(js.child_count, Some(2));
+      assert_eq!(js.word_count, None);
+      assert!(js.modified_at.is_some());
+
+      let items = service.get_sub_doc_items("js", false, true).unwrap();
+      let array = &find(&items, "array").metadata;
+      assert_eq!(array.size, Some(content.len() as u64));
+      assert_eq!(array.word_count, Some(4));
+      assert_eq!(array.child_count, None);
+      assert!(array.modified_at.is_some());
+      assert_eq!(find(&items, "basic").metadata.child_count, Some(0));
+      // not a git repository
+      assert!(items.iter().all(|item| item.metadata.git_status.is_none()));
+    }
+
+    #[test]
+    fn test_item_git_status() {
+      let (service, temp_dir) = setup_test_service();
+      for path in [
+        "js%2Farray",
+        "js%2Fobject",
+        "css%2Fgrid",
+        "readme",
+        "[draft]%2Fnote",
+      ] {
+        service.create_doc(path, true).unwrap();
+      }
+      let repo = git2::Repository::init(temp_dir.path()).unwrap();
+      let mut index = repo.index().unwrap();
+      index.add_path(std::path::Path::new("js/array.md")).unwrap();
+      index.add_path(std::path::Path::new("readme.md")).unwrap();
+      index
+        .add_path(std::path::Path::new("[draft]/note.md"))
+        .unwrap();
+      index.write().unwrap();
+
+      let items = service.get_sub_doc_items("", false, true).unwrap();
+      let status = |name| find(&items, name).metadata.git_status;
+      assert_eq!(status("readme"), Some(StatusType::Added));
+      assert_eq!(status("css"), Some(StatusType::Untracked));
+      // an added and an untracked file
+      assert_eq!(status("js"), Some(StatusType::Modified));
+
+      let items = service.get_sub_doc_items("js", false, true).unwrap();
+      assert_eq!(
+        find(&items, "array").metadata.git_status,
+        Some(StatusType::Added)
+      );
+      assert_eq!(
+        find(&items, "object").metadata.git_status,
+        Some(StatusType::Untracked)
+      );
+
+      // not taken for a glob matching `d`, `r`, ...
+      let items = service.get_sub_doc_items("[draft]", false, true).unwrap();
+      assert_eq!(
+        find(&items, "note").metadata.git_status,
+        Some(StatusType::Added)
+      );
+    }
+  }
+
+  mod batch {
+    use super::*;
+    use crate::services::doc::{BatchItemStatus, BatchOperation};
+
+    #[test]
+    fn test_batch_runs_in_order_and_reports_each_item() {
+      let (service, temp_dir) = setup_test_service();
+      fs::create_dir_all(temp_dir.path().join("js")).unwrap();
+      fs::write(temp_dir.path().join("js/array.md"), "# Array").unwrap();
+      fs::write(temp_dir.path().join("js/map.md"), "# Map").unwrap();
+
+      let response = service.batch(
+        vec![
+          BatchOperation::Create {
+            file_path: "archive".to_string(),
+            is_file: false,
+          },
+          BatchOperation::Move {
+            from_path: "js%2Farray".to_string(),
+            to_path: "archive%2Farray".to_string(),
+            is_file: true,
+            update_links: false,
+          },
+          BatchOperation::Delete {
+            file_path: "js%2Fmissing".to_string(),
+            is_file: true,
+          },
+          BatchOperation::Copy {
+            from_path: "js%2Fmap".to_string(),
+            to_path: "archive%2Fmap".to_string(),
+            is_file: true,
+          },
+        ],
+        false,
+      );
+
+      let statuses: Vec<BatchItemStatus> = response.results.iter().map(|r| r.status).collect();
+      assert_eq!(
+        statuses,
+        vec![
+          BatchItemStatus::Done,
+          BatchItemStatus::Done,
+          BatchItemStatus::Failed,
+          BatchItemStatus::Done
+        ]
+      );
+      assert!(response.results[2].error.is_some());
+      assert!(!response.rolled_back);
+      assert!(temp_dir.path().join("archive/array.md").exists());
+      assert!(temp_dir.path().join("archive/map.md").exists());
+      assert!(temp_dir.path().join("js/map.md").exists());
+    }
+
+    #[test]
+    fn test_atomic_batch_rolls_back() {
+      let (service, temp_dir) = setup_test_service();
+      let root = temp_dir.path();
+      fs::create_dir_all(root.join("js")).unwrap();
+      fs::write(root.join("js/array.md"), "# Array").unwrap();
+      fs::write(root.join("js/old.md"), "# Old").unwrap();
+      fs::write(root.join("index.md"), "[Array](js/array.md)").unwrap();
+      let order = vec!["old".to_string(), "array".to_string()];
+      service.reorder_doc_items("js", order.clone()).unwrap();
+
+      let response = service.batch(
+        vec![
+          BatchOperation::Create {
+            file_path: "notes%2F2026%2Ftodo".to_string(),
+            is_file: true,
+          },
+          BatchOperation::Rename {
+            file_path: "js%2Farray".to_string(),
+            name: "list".to_string(),
+            is_file: true,
+            update_links: true,
+          },
+          BatchOperation::Delete {
+            file_path: "js%2Fold".to_string(),
+            is_file: true,
+          },
+          // the target is taken
+          BatchOperation::Copy {
+            from_path: "index".to_string(),
+            to_path: "js%2Flist".to_string(),
+            is_file: true,
+          },
+          BatchOperation::Delete {
+            file_path: "index".to_string(),
+            is_file: true,
+          },
+        ],
+        true,
+      );
+
+      let statuses: Vec<BatchItemStatus> = response.results.iter().map(|r| r.status).collect();
+      assert_eq!(
+        statuses,
+        vec![
+          BatchItemStatus::RolledBack,
+          BatchItemStatus::RolledBack,
+          BatchItemStatus::RolledBack,
+          BatchItemStatus::Failed,
+          BatchItemStatus::Skipped
+        ]
+      );
+      assert!(response.rolled_back);
+      // the link rewrites were undone along with the rename
+      assert!(response.results[1].link_updates.is_empty());
+
+      assert!(!root.join("notes").exists());
+      assert!(!root.join("js/list.md").exists());
+      assert_eq!(
+        fs::read_to_string(root.join("js/array.md")).unwrap(),
+        "# Array"
+      );
+      assert_eq!(fs::read_to_string(root.join("js/old.md")).unwrap(), "# Old");
+      assert_eq!(
+        fs::read_to_string(root.join("index.md")).unwrap(),
+        "[Array](js/array.md)"
+      );
+      assert_eq!(service.read_doc_order().unwrap().get("js"), Some(&order));
+    }
+  }
+
+  mod trash {
+    use super::*;
+    use crate::services::trash::{RestoreConflictStrategy, TRASH_DIR, TrashRestoreConflict};
+
+    #[test]
+    fn test_delete_moves_to_trash_and_restores() {
+      let (service, temp_dir) = setup_test_service();
+      service.create_doc("js", false).unwrap();
+      service.update_article("js%2Farray", "# Array").unwrap();
+      service.delete_doc("js", false).unwrap();
+
+      let items = service.trash_service.list().unwrap();
+      assert_eq!(items.len(), 1);
+      assert_eq!(items[0].original_path, "js");
+      assert!(!items[0].is_file);
+      // the trash is invisible to the tree and to git
+      assert!(service.get_doc_tree(false).unwrap().is_empty());
+      assert!(temp_dir.path().join(TRASH_DIR).join(".gitignore").exists());
+
+      let restored = service
+        .trash_service
+        .restore(&items[0].id, RestoreConflictStrategy::Fail)
+        .unwrap();
+      assert_eq!(restored.restored_path, "js");
+      assert_eq!(
+        service.get_article("js%2Farray").unwrap().unwrap().content,
+        "# Array"
+      );
+      assert!(service.trash_service.list().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_delete_symlink_pointing_outside() {
+      let (service, temp_dir) = setup_test_service();
+      let outside = tempfile::tempdir().unwrap();
+      fs::write(outside.path().join("secret.md"), "secret").unwrap();
+      std::os::unix::fs::symlink(
+        outside.path().join("secret.md"),
+        temp_dir.path().join("linked.md"),
+      )
+      .unwrap();
+      std::os::unix::fs::symlink(outside.path(), temp_dir.path().join("linked-dir")).unwrap();
+
+      service.delete_doc("linked", true).unwrap();
+      service.delete_doc("linked-dir", false).unwrap();
+      assert!(fs::symlink_metadata(temp_dir.path().join("linked.md")).is_err());
+      assert!(fs::symlink_metadata(temp_dir.path().join("linked-dir")).is_err());
+      // the targets are left alone
+      assert_eq!(
+        fs::read_to_string(outside.path().join("secret.md")).unwrap(),
+        "secret"
+      );
+      assert_eq!(service.trash_service.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_restore_conflicts() {
+      let (service, temp_dir) = setup_test_service();
+      service.update_article("note", "old").unwrap();
+      service.delete_doc("note", true).unwrap();
+      service.update_article("note", "new").unwrap();
+      let id = service.trash_service.list().unwrap()[0].id.clone();
+
+      let err = service
+        .trash_service
+        .restore(&id, RestoreConflictStrategy::Fail)
+        .unwrap_err();
+      assert!(err.downcast_ref::<TrashRestoreConflict>().is_some());
+
+      let restored = service
+        .trash_service
+        .restore(&id, RestoreConflictStrategy::KeepBoth)
+        .unwrap();
+      assert_eq!(restored.restored_path, "note (1).md");
+      assert_eq!(
+        fs::read_to_string(temp_dir.path().join("note (1).md")).unwrap(),
+        "old"
+      );
+
+      // replacing keeps the replaced version in the trash
+      service.delete_doc("note", true).unwrap();
+      service.update_article("note", "newest").unwrap();
+      let id = service.trash_service.list().unwrap()[0].id.clone();
+      service
+        .trash_service
+        .restore(&id, RestoreConflictStrategy::Replace)
+        .unwrap();
+      assert_eq!(service.get_article("note").unwrap().unwrap().content, "new");
+      let items = service.trash_service.list().unwrap();
+      assert_eq!(items.len(), 1);
+      assert_eq!(items[0].original_path, "note.md");
+      let trashed = temp_dir
+        .path()
+        .join(TRASH_DIR)
+        .join(&items[0].id)
+        .join("note.md");
+      assert_eq!(fs::read_to_string(trashed).unwrap(), "newest");
+    }
+
+    #[test]
+    fn test_purge_and_retention() {
+      let (service, temp_dir) = setup_test_service();
+      for name in ["a", "b", "c"] {
+        service.update_article(name, name).unwrap();
+        service.delete_doc(name, true).unwrap();
+      }
+      let items = service.trash_service.list().unwrap();
+      assert_eq!(items.len(), 3);
+
+      assert!(
+        service
+          .trash_service
+          .purge(Some(&["../a".to_string()]))
+          .is_err()
+      );
+      // an invalid id in the middle leaves the valid ones in the trash
+      assert!(
+        service
+          .trash_service
+          .purge(Some(&[
+            items[0].id.clone(),
+            "missing".to_string(),
+            items[1].id.clone()
+          ]))
+          .is_err()
+      );
+      assert_eq!(service.trash_service.list().unwrap().len(), 3);
+      assert_eq!(
+        service
+          .trash_service
+          .purge(Some(std::slice::from_ref(&items[0].id)))
+          .unwrap(),
+        1
+      );
+      assert_eq!(service.trash_service.list().unwrap().len(), 2);
+
+      // age one item past the retention period
+      let old = &items[1];
+      let meta_path = temp_dir
+        .path()
+        .join(TRASH_DIR)
+        .join(format!("{}.json", old.id));
+      let mut expired = old.clone();
+      expired.deleted_at -= 31 * 24 * 60 * 60;
+      fs::write(&meta_path, serde_json::to_string(&expired).unwrap()).unwrap();
+
+      let items = service.trash_service.list().unwrap();
+      assert_eq!(items.len(), 1);
+      assert!(!temp_dir.path().join(TRASH_DIR).join(&old.id).exists());
+
+      assert_eq!(service.trash_service.purge(None).unwrap(), 1);
+      assert!(service.trash_service.list().unwrap().is_empty());
+    }
+  }
+
   #[test]
   fn test_get_docs_with_cache() {
     let (service, _temp_dir) = setup_test_service();
@@ -391,11 +1868,11 @@ mod tests {
 
     // First call should scan filesystem
     service.create_doc(doc_path, true).unwrap();
-    let docs1 = service.get_sub_doc_items("", false).unwrap();
+    let docs1 = service.get_sub_doc_items("", false, false).unwrap();
     assert_eq!(docs1.len(), 1);
 
     // Second call should use cache
-    let docs2 = service.get_sub_doc_items("", false).unwrap();
+    let docs2 = service.get_sub_doc_items("", false, false).unwrap();
     assert_eq!(docs2.len(), 1);
     assert_eq!(docs1[0].id, docs2[0].id);
   }
@@ -406,7 +1883,7 @@ mod tests {
     let doc_path = "test-file";
 
     service.create_doc(doc_path, true).unwrap();
-    let docs1 = service.get_sub_doc_items("", false).unwrap();
+    let docs1 = service.get_sub_doc_items("", false, false).unwrap();
     assert_eq!(docs1.len(), 1);
 
     // Create another file directly on filesystem
@@ -414,7 +1891,7 @@ mod tests {
     fs::write(&another_path, "").unwrap();
 
     // Force refresh should pick up new file
-    let docs2 = service.get_sub_doc_items("", false).unwrap();
+    let docs2 = service.get_sub_doc_items("", false, false).unwrap();
     assert_eq!(docs2.len(), 2);
   }
 
@@ -429,7 +1906,7 @@ mod tests {
     let another_path = service.path_convertor("another-file", true).unwrap();
     fs::write(&another_path, "").unwrap();
 
-    let docs = service.get_sub_doc_items("", false).unwrap();
+    let docs = service.get_sub_doc_items("", false, false).unwrap();
     assert_eq!(docs.len(), 2);
   }
 
@@ -453,7 +1930,7 @@ mod tests {
     service.create_doc("visible-dir", false).unwrap();
     service.create_doc("visible-dir%2Ffile", true).unwrap();
 
-    let docs = service.get_sub_doc_items("", false).unwrap();
+    let docs = service.get_sub_doc_items("", false, false).unwrap();
     // Should only see visible-dir, not .git
     assert_eq!(docs.len(), 1);
     assert_eq!(docs[0].name, "visible-dir");
@@ -470,13 +1947,13 @@ mod tests {
     service.create_doc(child_path, false).unwrap();
     service.create_doc(file_path, true).unwrap();
 
-    let docs = service.get_sub_doc_items("", false).unwrap();
+    let docs = service.get_sub_doc_items("", false, false).unwrap();
     assert_eq!(docs.len(), 1);
     assert_eq!(docs[0].name, "parent");
-    let sub_doc_items = service.get_sub_doc_items(dir_path, false).unwrap();
+    let sub_doc_items = service.get_sub_doc_items(dir_path, false, false).unwrap();
     assert_eq!(sub_doc_items.len(), 1);
     assert_eq!(sub_doc_items[0].name, "child");
-    let sub_doc_items = service.get_sub_doc_items(child_path, false).unwrap();
+    let sub_doc_items = service.get_sub_doc_items(child_path, false, false).unwrap();
     assert_eq!(sub_doc_items.len(), 1);
     assert_eq!(sub_doc_items[0].name, "file");
   }
@@ -489,7 +1966,7 @@ mod tests {
     service.create_doc("m-file", true).unwrap();
     service.create_doc("b-dir", false).unwrap();
 
-    let docs = service.get_sub_doc_items("", false).unwrap();
+    let docs = service.get_sub_doc_items("", false, false).unwrap();
     assert_eq!(docs.len(), 4);
     // Directories should come first
     assert_eq!(docs[0].name, "a-dir");
@@ -519,6 +1996,15 @@ mod tests {
     let new_settings = Settings {
       doc_root_path: temp_dir.path().join("new-docs"),
       ignore_dirs: vec!["custom-ignore".to_string()],
+      trash_retention_days: 30,
+      doc_extensions: vec!["md".to_string()],
+      templates_dir: "_templates".to_string(),
+      journal_dir: "journal".to_string(),
+      journal_file_pattern: "%Y/%m/%Y-%m-%d".to_string(),
+      journal_template: String::new(),
+      history_max_versions: 50,
+      history_retention_days: 30,
+      sort_mode: DocSortMode::Name,
     };
 
     fs::create_dir_all(&new_settings.doc_root_path).unwrap();
@@ -0,0 +1,267 @@
+use std::{ops::Range, sync::LazyLock};
+
+use pulldown_cmark::{Event, Parser, Tag};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{
+  helpers::normalize_path,
+  links::line_of,
+  markdown::{parser_options, slugify},
+};
+
+static WIKI_LINK_RE: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"!?\[\[([^\[\]\n]+?)\]\]").unwrap());
+
+/// A `[[target#heading|alias]]` link found in a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WikiLink {
+  /// The whole link as written, e.g. `[[Note#Heading|alias]]`.
+  pub raw: String,
+  /// The doc part, e.g. `Note` or `folder/Note`. Empty for links to a heading of the same doc.
+  pub target: String,
+  pub heading: Option<String>,
+  pub alias: Option<String>,
+  /// `![[...]]` embeds instead of linking.
+  pub embed: bool,
+  pub line: usize,
+  #[serde(skip)]
+  pub range: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WikiLinkStatus {
+  Resolved,
+  /// More than one doc matches, see `candidates`.
+  Ambiguous,
+  Unresolved,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WikiLinkResolution {
+  pub status: WikiLinkStatus,
+  /// Normalized path of the resolved doc.
+  pub file_path: Option<String>,
+  /// Anchor of the linked heading, in the same format as the editor's heading ids.
+  pub heading_id: Option<String>,
+  /// Normalized paths of the matching docs when the target is ambiguous.
+  pub candidates: Vec<String>,
+}
+
+/// Finds the wiki links of a markdown document, ignoring code spans and blocks.
+///
+/// # Example
+/// ```
+/// use server::services::doc::wiki_links::parse_wiki_links;
+///
+/// let links = parse_wiki_links("See [[Note#Setup|the setup]] and `[[not a link]]`.");
+/// assert_eq!(links.len(), 1);
+/// assert_eq!(links[0].target, "Note");
+/// assert_eq!(links[0].heading.as_deref(), Some("Setup"));
+/// assert_eq!(links[0].alias.as_deref(), Some("the setup"));
+/// ```
+pub fn parse_wiki_links(content: &str) -> Vec<WikiLink> {
+  let code_ranges: Vec<Range<usize>> = Parser::new_ext(content, parser_options())
+    .into_offset_iter()
+    .filter_map(|(event, range)| match event {
+      Event::Code(_) | Event::Start(Tag::CodeBlock(_)) => Some(range),
+      _ => None,
+    })
+    .collect();
+
+  WIKI_LINK_RE
+    .captures_iter(content)
+    .filter_map(|captures| {
+      let whole = captures.get(0)?;
+      if code_ranges
+        .iter()
+        .any(|code| code.start <= whole.start() && whole.end() <= code.end)
+      {
+        return None;
+      }
+
+      // `\|` separates the alias inside tables
+      let inner = captures[1].replace("\\|", "|");
+      let (target_part, alias) = match inner.split_once('|') {
+        Some((target, alias)) => (target, Some(alias.trim().to_string())),
+        None => (inner.as_str(), None),
+      };
+      let (target, heading) = match target_part.split_once('#') {
+        Some((target, heading)) => (target, Some(heading.trim().to_string())),
+        None => (target_part, None),
+      };
+
+      Some(WikiLink {
+        raw: whole.as_str().to_string(),
+        target: target.trim().to_string(),
+        heading: heading.filter(|heading| !heading.is_empty()),
+        alias: alias.filter(|alias| !alias.is_empty()),
+        embed: whole.as_str().starts_with('!'),
+        line: line_of(content, whole.start()),
+        range: whole.range(),
+      })
+    })
+    .collect()
+}
+
+/// Resolves a wiki link target against the workspace docs, given as path segments
+/// without the `.md` extension. Matching is case-insensitive on whole path segments, so
+/// `[[Note]]` matches `a/note.md` and `[[b/note]]` narrows it down to `a/b/note.md`.
+/// When several docs match, one whose path is exactly the target (from the workspace
+/// root) wins, otherwise the link is ambiguous.
+///
+/// # Example
+/// ```
+/// use server::services::doc::wiki_links::{WikiLinkStatus, resolve_wiki_target};
+///
+/// let docs = vec![
+///   vec!["js".to_string(), "Array".to_string()],
+///   vec!["ts".to_string(), "array".to_string()],
+/// ];
+/// assert_eq!(resolve_wiki_target("array", None, &docs).status, WikiLinkStatus::Ambiguous);
+/// let resolution = resolve_wiki_target("TS/Array.md", Some("Map & Filter"), &docs);
+/// assert_eq!(resolution.file_path.as_deref(), Some("ts%2Farray"));
+/// assert_eq!(resolution.heading_id.as_deref(), Some("map--filter"));
+/// ```
+pub fn resolve_wiki_target(
+  target: &str,
+  heading: Option<&str>,
+  docs: &[Vec<String>],
+) -> WikiLinkResolution {
+  let heading_id = heading.map(slugify);
+  let unresolved = WikiLinkResolution {
+    status: WikiLinkStatus::Unresolved,
+    file_path: None,
+    heading_id: heading_id.clone(),
+    candidates: Vec::new(),
+  };
+
+  let target = target.trim();
+  let from_root = target.starts_with('/');
+  let target = target.strip_suffix(".md").unwrap_or(target);
+  let target: Vec<String> = target
+    .split(['/', '\\'])
+    .filter(|segment| !segment.is_empty())
+    .map(str::to_lowercase)
+    .collect();
+  if target.is_empty() {
+    return unresolved;
+  }
+
+  let matches: Vec<&Vec<String>> = docs
+    .iter()
+    .filter(|doc| {
+      doc.len() >= target.len()
+        && (!from_root || doc.len() == target.len())
+        && doc[doc.len() - target.len()..]
+          .iter()
+          .zip(&target)
+          .all(|(segment, target)| segment.to_lowercase() == *target)
+    })
+    .collect();
+
+  let exact: Vec<&&Vec<String>> = matches
+    .iter()
+    .filter(|doc| doc.len() == target.len())
+    .collect();
+  let resolved = match (matches.len(), exact.len()) {
+    (0, _) => return unresolved,
+    (1, _) => matches[0],
+    (_, 1) => *exact[0],
+    _ => {
+      let mut candidates: Vec<String> = matches.iter().map(|doc| normalize_path(doc)).collect();
+      candidates.sort();
+      return WikiLinkResolution {
+        status: WikiLinkStatus::Ambiguous,
+        candidates,
+        ..unresolved
+      };
+    }
+  };
+
+  WikiLinkResolution {
+    status: WikiLinkStatus::Resolved,
+    file_path: Some(normalize_path(resolved)),
+    heading_id,
+    candidates: Vec::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn docs(paths: &[&str]) -> Vec<Vec<String>> {
+    paths
+      .iter()
+      .map(|path| path.split('/').map(String::from).collect())
+      .collect()
+  }
+
+  #[test]
+  fn test_parse_wiki_links() {
+    let content = "[[Note]]\n![[Image Note|200]]\n| [[Table\\|alias]] |\n\n```\n[[code]]\n```\n[[#Local heading]]";
+    let links = parse_wiki_links(content);
+    let parsed: Vec<(&str, Option<&str>, Option<&str>, bool)> = links
+      .iter()
+      .map(|link| {
+        (
+          link.target.as_str(),
+          link.heading.as_deref(),
+          link.alias.as_deref(),
+          link.embed,
+        )
+      })
+      .collect();
+
+    assert_eq!(
+      parsed,
+      vec![
+        ("Note", None, None, false),
+        ("Image Note", None, Some("200"), true),
+        ("Table", None, Some("alias"), false),
+        ("", Some("Local heading"), None, false),
+      ]
+    );
+    let lines: Vec<usize> = links.iter().map(|link| link.line).collect();
+    assert_eq!(lines, vec![1, 2, 3, 8]);
+    assert_eq!(&content[links[1].range.clone()], "![[Image Note|200]]");
+  }
+
+  #[test]
+  fn test_resolve_shortest_unique_path() {
+    let docs = docs(&["notes/Daily", "work/meetings/daily", "work/Plan", "plan"]);
+
+    let resolution = resolve_wiki_target("daily", None, &docs);
+    assert_eq!(resolution.status, WikiLinkStatus::Ambiguous);
+    assert_eq!(
+      resolution.candidates,
+      vec!["notes%2FDaily", "work%2Fmeetings%2Fdaily"]
+    );
+
+    let resolution = resolve_wiki_target("Meetings/Daily", None, &docs);
+    assert_eq!(
+      resolution.file_path.as_deref(),
+      Some("work%2Fmeetings%2Fdaily")
+    );
+
+    // a root level doc wins over deeper ones with the same name
+    let resolution = resolve_wiki_target("PLAN", None, &docs);
+    assert_eq!(resolution.file_path.as_deref(), Some("plan"));
+    let resolution = resolve_wiki_target("/work/plan", None, &docs);
+    assert_eq!(resolution.file_path.as_deref(), Some("work%2FPlan"));
+
+    assert_eq!(
+      resolve_wiki_target("missing", None, &docs).status,
+      WikiLinkStatus::Unresolved
+    );
+    // segments match as a whole
+    assert_eq!(
+      resolve_wiki_target("ly", None, &docs).status,
+      WikiLinkStatus::Unresolved
+    );
+  }
+}
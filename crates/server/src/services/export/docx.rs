@@ -0,0 +1,881 @@
+use std::{collections::HashMap, fs::File, io::Write, path::Path};
+
+use pulldown_cmark::{Alignment, Event, HeadingLevel, Tag, TagEnd};
+use zip::{CompressionMethod, write::SimpleFileOptions};
+
+use super::{
+  ExportDoc, ExportFile, ExportLinks, ExportService, LinkTarget, article_title, escape_html,
+  finish_archive, html::anchored_events, temp_archive,
+};
+use crate::services::doc::{denormalize_path, normalize_path};
+
+const DOCX_MIME: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Image formats Word can show without a fallback.
+const DOCX_IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "bmp"];
+
+/// Width of the text on a letter page with 1 inch margins, in EMUs.
+const MAX_IMAGE_WIDTH: u64 = 6 * 914_400 + 457_200;
+const EMUS_PER_PIXEL: u64 = 9_525;
+
+/// Width of the text in twentieths of a point.
+const TEXT_WIDTH: usize = 9_360;
+
+/// Numbering of the bullet lists, ordered lists get their own to restart from their start.
+const BULLET_NUM_ID: u32 = 1;
+const BULLET_ABSTRACT_ID: u32 = 0;
+const ORDERED_ABSTRACT_ID: u32 = 1;
+
+/// The relationships of `document.xml` to styles and numbering come first.
+const FIXED_RELATIONSHIPS: usize = 2;
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="xml" ContentType="application/xml"/>
+  <Default Extension="png" ContentType="image/png"/>
+  <Default Extension="jpg" ContentType="image/jpeg"/>
+  <Default Extension="jpeg" ContentType="image/jpeg"/>
+  <Default Extension="gif" ContentType="image/gif"/>
+  <Default Extension="bmp" ContentType="image/bmp"/>
+  <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+  <Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+  <Override PartName="/word/numbering.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml"/>
+  <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
+</Types>
+"#;
+
+const PACKAGE_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
+</Relationships>
+"#;
+
+const STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:docDefaults>
+    <w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:eastAsia="Calibri" w:cs="Calibri"/><w:sz w:val="22"/><w:szCs w:val="22"/><w:lang w:val="en-US"/></w:rPr></w:rPrDefault>
+    <w:pPrDefault><w:pPr><w:spacing w:after="160" w:line="264" w:lineRule="auto"/></w:pPr></w:pPrDefault>
+  </w:docDefaults>
+  <w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style>
+  <w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="240"/></w:pPr><w:rPr><w:sz w:val="52"/><w:szCs w:val="52"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="360" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="36"/><w:szCs w:val="36"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="120"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="30"/><w:szCs w:val="30"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading3"><w:name w:val="heading 3"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="80"/><w:outlineLvl w:val="2"/></w:pPr><w:rPr><w:b/><w:sz w:val="26"/><w:szCs w:val="26"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading4"><w:name w:val="heading 4"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="80"/><w:outlineLvl w:val="3"/></w:pPr><w:rPr><w:b/><w:sz w:val="24"/><w:szCs w:val="24"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading5"><w:name w:val="heading 5"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="80"/><w:outlineLvl w:val="4"/></w:pPr><w:rPr><w:b/><w:sz w:val="22"/><w:szCs w:val="22"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading6"><w:name w:val="heading 6"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="80"/><w:outlineLvl w:val="5"/></w:pPr><w:rPr><w:b/><w:color w:val="595959"/><w:sz w:val="22"/><w:szCs w:val="22"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Quote"><w:name w:val="Quote"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:pBdr><w:left w:val="single" w:sz="18" w:space="8" w:color="D0D7DE"/></w:pBdr><w:ind w:left="360"/></w:pPr><w:rPr><w:color w:val="57606A"/></w:rPr></w:style>
+  <w:style w:type="paragraph" w:styleId="ListParagraph"><w:name w:val="List Paragraph"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="60"/><w:ind w:left="720"/></w:pPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Code"><w:name w:val="Code"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:shd w:val="clear" w:color="auto" w:fill="F6F8FA"/><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Consolas"/><w:sz w:val="19"/><w:szCs w:val="19"/></w:rPr></w:style>
+  <w:style w:type="character" w:styleId="CodeChar"><w:name w:val="Code Char"/><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Consolas"/><w:shd w:val="clear" w:color="auto" w:fill="F0F0F0"/></w:rPr></w:style>
+  <w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/><w:rPr><w:color w:val="0563C1"/><w:u w:val="single"/></w:rPr></w:style>
+  <w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:tblPr><w:tblBorders><w:top w:val="single" w:sz="4" w:space="0" w:color="D0D7DE"/><w:left w:val="single" w:sz="4" w:space="0" w:color="D0D7DE"/><w:bottom w:val="single" w:sz="4" w:space="0" w:color="D0D7DE"/><w:right w:val="single" w:sz="4" w:space="0" w:color="D0D7DE"/><w:insideH w:val="single" w:sz="4" w:space="0" w:color="D0D7DE"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="D0D7DE"/></w:tblBorders><w:tblCellMar><w:left w:w="108" w:type="dxa"/><w:right w:w="108" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style>
+</w:styles>
+"#;
+
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+impl ExportService {
+  /// Converts a doc to a Word document: headings, lists, tables, code blocks, emphasis,
+  /// links and the linked images of the workspace.
+  ///
+  /// # Arguments
+  /// * `file_path` - Normalized path string (percent-encoded), e.g., `"js%2Fbasic%2Farray"`
+  ///
+  /// # Example
+  /// ```ignore
+  /// let docx = export_service.export_docx("js%2Fbasic%2Farray")?;
+  /// io::copy(&mut docx.file, &mut File::create(&docx.file_name)?)?;
+  /// ```
+  pub fn export_docx(&self, file_path: &str) -> Result<ExportFile, anyhow::Error> {
+    tracing::info!("[ExportService] export_docx: {}", file_path);
+    let Some(article) = self.doc_service.get_article(file_path)? else {
+      return Err(anyhow::anyhow!("The file {} does not exist.", file_path));
+    };
+    let path = denormalize_path(file_path);
+    let name = path.last().cloned().unwrap_or_default();
+    let doc = ExportDoc {
+      key: normalize_path(&path),
+      relative: path.clone(),
+      path,
+      title: article_title(&article, &name),
+      content: article.content,
+    };
+
+    // only links to the headings of the doc itself can be followed in the document
+    let links = self.export_links(std::slice::from_ref(&doc))?;
+    let markdown = self.rewrite_links(&doc, &links, |target| match target {
+      LinkTarget::Doc { anchor, .. } => anchor.as_ref().map(|anchor| format!("#{}", anchor)),
+      LinkTarget::Asset { .. } => None,
+    });
+    let (events, _) = anchored_events(&markdown);
+
+    let mut writer = DocxWriter::new(|dest| self.docx_image(&doc, dest, &links));
+    if !starts_with_title(&events) {
+      writer.title(&doc.title);
+    }
+    for event in events {
+      writer.event(event);
+    }
+    let file = writer.finish(&doc.title)?;
+
+    Ok(ExportFile {
+      file_name: format!("{}.docx", name),
+      mime: DOCX_MIME.to_string(),
+      file,
+    })
+  }
+
+  /// Reads an image of the workspace linked from `doc`, if Word can show it.
+  fn docx_image(
+    &self,
+    doc: &ExportDoc,
+    dest: &str,
+    links: &ExportLinks,
+  ) -> Option<(Vec<u8>, String)> {
+    let LinkTarget::Asset { path } = self.link_target(doc, dest, links)? else {
+      return None;
+    };
+    let extension = Path::new(path.last()?)
+      .extension()?
+      .to_string_lossy()
+      .to_lowercase();
+    if !DOCX_IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+      return None;
+    }
+    let (bytes, _) = self.img_service.get_image(&path.join("/")).ok()?;
+    Some((bytes, extension))
+  }
+}
+
+/// Whether the doc starts with its own title, after the front matter.
+fn starts_with_title(events: &[Event]) -> bool {
+  let start = match events.first() {
+    Some(Event::Start(Tag::MetadataBlock(_))) => events
+      .iter()
+      .position(|event| matches!(event, Event::End(TagEnd::MetadataBlock(_))))
+      .map_or(events.len(), |end| end + 1),
+    _ => 0,
+  };
+  matches!(
+    events.get(start),
+    Some(Event::Start(Tag::Heading {
+      level: HeadingLevel::H1,
+      ..
+    }))
+  )
+}
+
+/// Name of the bookmark of a heading, Word only allows letters, digits and underscores.
+fn bookmark_name(anchor: &str) -> String {
+  let name: String = anchor
+    .chars()
+    .map(|c| if c.is_alphanumeric() { c } else { '_' })
+    .collect();
+  // bookmarks starting with an underscore are hidden
+  format!("_{}", name).chars().take(40).collect()
+}
+
+/// Whether a link leaves the document, e.g. `https://` or `mailto:` links.
+fn is_external(dest: &str) -> bool {
+  dest.split_once(':').is_some_and(|(scheme, _)| {
+    scheme.len() > 1
+      && scheme
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+  })
+}
+
+struct ListLevel {
+  num_id: u32,
+}
+
+struct PendingImage {
+  dest: String,
+  alt: String,
+}
+
+struct MediaFile {
+  name: String,
+  bytes: Vec<u8>,
+}
+
+/// Writes the body of `document.xml` from markdown events, collecting the hyperlinks,
+/// images and list numberings it needs.
+struct DocxWriter<F> {
+  load_image: F,
+  body: String,
+  /// The paragraph being written, without its closing tag.
+  paragraph: Option<String>,
+  heading: Option<(HeadingLevel, String)>,
+  quote_depth: usize,
+  in_code_block: bool,
+  in_metadata: bool,
+  in_html_comment: bool,
+  lists: Vec<ListLevel>,
+  /// The next paragraph is the first of a list item and shows its bullet or number.
+  item_marker_pending: bool,
+  table_alignments: Vec<Alignment>,
+  table_cell: usize,
+  in_table_head: bool,
+  bold: usize,
+  italic: usize,
+  strikethrough: usize,
+  /// Inside a link, whether it is written as a hyperlink.
+  link: Option<bool>,
+  image: Option<PendingImage>,
+  /// Relationships of `document.xml` after the fixed ones.
+  relationships: Vec<String>,
+  media: Vec<MediaFile>,
+  image_ids: HashMap<String, (String, u64, u64)>,
+  /// Ordered lists, as `(num_id, level, start)`.
+  ordered_lists: Vec<(u32, usize, u64)>,
+  bookmarks: usize,
+  drawings: usize,
+}
+
+impl<F> DocxWriter<F>
+where
+  F: FnMut(&str) -> Option<(Vec<u8>, String)>,
+{
+  fn new(load_image: F) -> Self {
+    Self {
+      load_image,
+      body: String::new(),
+      paragraph: None,
+      heading: None,
+      quote_depth: 0,
+      in_code_block: false,
+      in_metadata: false,
+      in_html_comment: false,
+      lists: Vec::new(),
+      item_marker_pending: false,
+      table_alignments: Vec::new(),
+      table_cell: 0,
+      in_table_head: false,
+      bold: 0,
+      italic: 0,
+      strikethrough: 0,
+      link: None,
+      image: None,
+      relationships: Vec::new(),
+      media: Vec::new(),
+      image_ids: HashMap::new(),
+      ordered_lists: Vec::new(),
+      bookmarks: 0,
+      drawings: 0,
+    }
+  }
+
+  fn title(&mut self, title: &str) {
+    self.body.push_str(&format!(
+      "<w:p><w:pPr><w:pStyle w:val=\"Title\"/></w:pPr>{}</w:p>",
+      text_run(title, "")
+    ));
+  }
+
+  fn event(&mut self, event: Event) {
+    if self.in_metadata && !matches!(event, Event::End(TagEnd::MetadataBlock(_))) {
+      return;
+    }
+    if self.image.is_some() {
+      match event {
+        Event::End(TagEnd::Image) => self.end_image(),
+        Event::Text(text) | Event::Code(text) => {
+          if let Some(image) = &mut self.image {
+            image.alt.push_str(&text);
+          }
+        }
+        _ => {}
+      }
+      return;
+    }
+
+    match event {
+      Event::Start(tag) => self.start(tag),
+      Event::End(tag) => self.end(tag),
+      // a paragraph per line, the text of a block may come in several pieces
+      Event::Text(text) if self.in_code_block => {
+        for (index, line) in text.split('\n').enumerate() {
+          if index > 0 {
+            self.open_paragraph();
+            self.close_paragraph();
+          }
+          if !line.is_empty() {
+            self.run(line, "");
+          }
+        }
+      }
+      Event::Text(text) => self.run(&text, ""),
+      Event::Code(text) | Event::InlineMath(text) | Event::DisplayMath(text) => {
+        self.run(&text, "CodeChar")
+      }
+      // comments of html blocks come line by line
+      Event::Html(raw) | Event::InlineHtml(raw)
+        if self.in_html_comment || raw.trim_start().starts_with("<!--") =>
+      {
+        self.in_html_comment = !raw.contains("-->");
+      }
+      Event::Html(raw) | Event::InlineHtml(raw) => self.run(raw.trim_end_matches('\n'), ""),
+      Event::SoftBreak => self.run(" ", ""),
+      Event::HardBreak => {
+        self.open_paragraph();
+        self.push("<w:r><w:br/></w:r>");
+      }
+      Event::Rule => {
+        self.close_paragraph();
+        self.body.push_str(
+          "<w:p><w:pPr><w:pBdr><w:bottom w:val=\"single\" w:sz=\"6\" w:space=\"1\" w:color=\"D0D7DE\"/></w:pBdr></w:pPr></w:p>",
+        );
+      }
+      Event::TaskListMarker(checked) => self.run(if checked { "☒ " } else { "☐ " }, ""),
+      Event::FootnoteReference(label) => self.run(&format!("[{}]", label), ""),
+    }
+  }
+
+  fn start(&mut self, tag: Tag) {
+    match tag {
+      Tag::Paragraph | Tag::HtmlBlock => {
+        self.close_paragraph();
+        self.open_paragraph();
+      }
+      Tag::Heading { level, id, .. } => {
+        self.close_paragraph();
+        self.heading = Some((level, id.map(|id| id.to_string()).unwrap_or_default()));
+        self.open_paragraph();
+      }
+      Tag::BlockQuote(_) => {
+        self.close_paragraph();
+        self.quote_depth += 1;
+      }
+      Tag::CodeBlock(_) => {
+        self.close_paragraph();
+        self.in_code_block = true;
+      }
+      Tag::List(start) => {
+        self.close_paragraph();
+        let num_id = match start {
+          Some(start) => {
+            let num_id = BULLET_NUM_ID + 1 + self.ordered_lists.len() as u32;
+            self.ordered_lists.push((num_id, self.lists.len(), start));
+            num_id
+          }
+          None => BULLET_NUM_ID,
+        };
+        self.lists.push(ListLevel { num_id });
+      }
+      Tag::Item => {
+        self.close_paragraph();
+        self.item_marker_pending = true;
+      }
+      Tag::FootnoteDefinition(label) => {
+        self.close_paragraph();
+        self.run(&format!("[{}] ", label), "");
+      }
+      Tag::Table(alignments) => {
+        self.close_paragraph();
+        let width = TEXT_WIDTH / alignments.len().max(1);
+        let grid: String = alignments
+          .iter()
+          .map(|_| format!("<w:gridCol w:w=\"{}\"/>", width))
+          .collect();
+        self.body.push_str(&format!(
+          "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr><w:tblGrid>{}</w:tblGrid>",
+          grid
+        ));
+        self.table_alignments = alignments;
+      }
+      Tag::TableHead => {
+        self.in_table_head = true;
+        self.table_cell = 0;
+        self.body.push_str("<w:tr><w:trPr><w:tblHeader/></w:trPr>");
+      }
+      Tag::TableRow => {
+        self.table_cell = 0;
+        self.body.push_str("<w:tr>");
+      }
+      Tag::TableCell => {
+        let width = TEXT_WIDTH / self.table_alignments.len().max(1);
+        self.body.push_str(&format!(
+          "<w:tc><w:tcPr><w:tcW w:w=\"{}\" w:type=\"dxa\"/></w:tcPr>",
+          width
+        ));
+      }
+      Tag::Emphasis => self.italic += 1,
+      Tag::Strong => self.bold += 1,
+      Tag::Strikethrough => self.strikethrough += 1,
+      Tag::Link { dest_url, .. } => {
+        self.open_paragraph();
+        if let Some(anchor) = dest_url.strip_prefix('#') {
+          self.push(&format!(
+            "<w:hyperlink w:anchor=\"{}\" w:history=\"1\">",
+            escape_html(&bookmark_name(anchor))
+          ));
+          self.link = Some(true);
+        } else if is_external(&dest_url) {
+          let id = self.relationship(
+            "hyperlink",
+            &escape_html(&dest_url),
+            " TargetMode=\"External\"",
+          );
+          self.push(&format!("<w:hyperlink r:id=\"{}\" w:history=\"1\">", id));
+          self.link = Some(true);
+        } else {
+          // links to other docs and files can't be followed from the document
+          self.link = Some(false);
+        }
+      }
+      Tag::Image { dest_url, .. } => {
+        self.image = Some(PendingImage {
+          dest: dest_url.to_string(),
+          alt: String::new(),
+        });
+      }
+      Tag::MetadataBlock(_) => self.in_metadata = true,
+      _ => {}
+    }
+  }
+
+  fn end(&mut self, tag: TagEnd) {
+    match tag {
+      TagEnd::Paragraph | TagEnd::HtmlBlock | TagEnd::FootnoteDefinition => self.close_paragraph(),
+      TagEnd::Heading(_) => {
+        self.close_paragraph();
+        self.heading = None;
+      }
+      TagEnd::BlockQuote(_) => {
+        self.close_paragraph();
+        self.quote_depth = self.quote_depth.saturating_sub(1);
+      }
+      TagEnd::CodeBlock => {
+        self.close_paragraph();
+        self.in_code_block = false;
+      }
+      TagEnd::List(_) => {
+        self.close_paragraph();
+        self.lists.pop();
+      }
+      TagEnd::Item => {
+        // an empty item still shows its bullet
+        if self.item_marker_pending {
+          self.open_paragraph();
+        }
+        self.close_paragraph();
+      }
+      TagEnd::Table => {
+        self.body.push_str("</w:tbl>");
+        self.table_alignments.clear();
+      }
+      TagEnd::TableHead => {
+        self.in_table_head = false;
+        self.body.push_str("</w:tr>");
+      }
+      TagEnd::TableRow => self.body.push_str("</w:tr>"),
+      TagEnd::TableCell => {
+        // a cell needs a paragraph, even when empty
+        self.open_paragraph();
+        self.close_paragraph();
+        self.body.push_str("</w:tc>");
+        self.table_cell += 1;
+      }
+      TagEnd::Emphasis => self.italic = self.italic.saturating_sub(1),
+      TagEnd::Strong => self.bold = self.bold.saturating_sub(1),
+      TagEnd::Strikethrough => self.strikethrough = self.strikethrough.saturating_sub(1),
+      // links that can't be followed have no hyperlink to close
+      TagEnd::Link if self.link.take() == Some(true) => self.push("</w:hyperlink>"),
+      TagEnd::MetadataBlock(_) => self.in_metadata = false,
+      _ => {}
+    }
+  }
+
+  /// Opens a paragraph styled after where it is, unless one is open.
+  fn open_paragraph(&mut self) {
+    if self.paragraph.is_some() {
+      return;
+    }
+    let mut properties = String::new();
+    let mut bookmark = String::new();
+    let list = self
+      .lists
+      .last()
+      .map(|list| (self.lists.len() - 1, list.num_id));
+    if let Some((level, id)) = &self.heading {
+      properties.push_str(&format!("<w:pStyle w:val=\"Heading{}\"/>", *level as usize));
+      if !id.is_empty() {
+        self.bookmarks += 1;
+        bookmark = format!(
+          "<w:bookmarkStart w:id=\"{0}\" w:name=\"{1}\"/><w:bookmarkEnd w:id=\"{0}\"/>",
+          self.bookmarks,
+          escape_html(&bookmark_name(id))
+        );
+      }
+    } else if self.in_code_block {
+      properties.push_str("<w:pStyle w:val=\"Code\"/>");
+    } else if list.is_some() {
+      properties.push_str("<w:pStyle w:val=\"ListParagraph\"/>");
+    } else if self.quote_depth > 0 {
+      properties.push_str("<w:pStyle w:val=\"Quote\"/>");
+    }
+    if let Some((level, num_id)) = list {
+      // the first paragraph of an item shows its marker whatever its style, e.g. `- # Title`
+      if self.item_marker_pending {
+        properties.push_str(&format!(
+          "<w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>",
+          level, num_id
+        ));
+      } else if self.heading.is_none() && !self.in_code_block {
+        properties.push_str(&format!("<w:ind w:left=\"{}\"/>", 720 * (level + 1)));
+      }
+    }
+    self.item_marker_pending = false;
+
+    if !self.table_alignments.is_empty() {
+      properties.push_str("<w:spacing w:after=\"0\"/>");
+      match self.table_alignments.get(self.table_cell) {
+        Some(Alignment::Center) => properties.push_str("<w:jc w:val=\"center\"/>"),
+        Some(Alignment::Right) => properties.push_str("<w:jc w:val=\"right\"/>"),
+        _ => {}
+      }
+    }
+
+    let properties = if properties.is_empty() {
+      String::new()
+    } else {
+      format!("<w:pPr>{}</w:pPr>", properties)
+    };
+    self.paragraph = Some(format!("<w:p>{}{}", properties, bookmark));
+  }
+
+  fn close_paragraph(&mut self) {
+    if let Some(paragraph) = self.paragraph.take() {
+      self.body.push_str(&paragraph);
+      self.body.push_str("</w:p>");
+    }
+  }
+
+  fn push(&mut self, xml: &str) {
+    if let Some(paragraph) = &mut self.paragraph {
+      paragraph.push_str(xml);
+    }
+  }
+
+  /// Writes text with the current formatting, `style` being a character style.
+  fn run(&mut self, text: &str, style: &str) {
+    self.open_paragraph();
+    let mut properties = String::new();
+    let style = match (style, self.link) {
+      ("", Some(true)) => "Hyperlink",
+      (style, _) => style,
+    };
+    if !style.is_empty() {
+      properties.push_str(&format!("<w:rStyle w:val=\"{}\"/>", style));
+    }
+    if self.bold > 0 || self.in_table_head {
+      properties.push_str("<w:b/>");
+    }
+    if self.italic > 0 {
+      properties.push_str("<w:i/>");
+    }
+    if self.strikethrough > 0 {
+      properties.push_str("<w:strike/>");
+    }
+    let run = text_run(text, &properties);
+    self.push(&run);
+  }
+
+  fn end_image(&mut self) {
+    let Some(image) = self.image.take() else {
+      return;
+    };
+    let image_id = match self.image_ids.get(&image.dest) {
+      Some(image_id) => Some(image_id.clone()),
+      None => (self.load_image)(&image.dest).map(|(bytes, extension)| {
+        let (width, height) = imagesize::blob_size(&bytes)
+          .map(|size| (size.width as u64, size.height as u64))
+          .unwrap_or((400, 300));
+        let name = format!("image{}.{}", self.media.len() + 1, extension);
+        let id = self.relationship("image", &format!("media/{}", name), "");
+        self.media.push(MediaFile { name, bytes });
+        let image_id = (id, width.max(1), height.max(1));
+        self.image_ids.insert(image.dest.clone(), image_id.clone());
+        image_id
+      }),
+    };
+
+    // images Word can't show are left as their alt text
+    let Some((id, width, height)) = image_id else {
+      let alt = if image.alt.is_empty() {
+        image.dest
+      } else {
+        image.alt
+      };
+      self.run(&alt, "");
+      return;
+    };
+    let mut cx = width * EMUS_PER_PIXEL;
+    let mut cy = height * EMUS_PER_PIXEL;
+    if cx > MAX_IMAGE_WIDTH {
+      cy = cy * MAX_IMAGE_WIDTH / cx;
+      cx = MAX_IMAGE_WIDTH;
+    }
+    self.drawings += 1;
+    let drawing = format!(
+      "<w:r><w:drawing><wp:inline distT=\"0\" distB=\"0\" distL=\"0\" distR=\"0\">\
+       <wp:extent cx=\"{cx}\" cy=\"{cy}\"/><wp:docPr id=\"{n}\" name=\"Picture {n}\" descr=\"{alt}\"/>\
+       <a:graphic xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\">\
+       <a:graphicData uri=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">\
+       <pic:pic xmlns:pic=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">\
+       <pic:nvPicPr><pic:cNvPr id=\"{n}\" name=\"Picture {n}\"/><pic:cNvPicPr/></pic:nvPicPr>\
+       <pic:blipFill><a:blip r:embed=\"{id}\"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>\
+       <pic:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"{cx}\" cy=\"{cy}\"/></a:xfrm>\
+       <a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></pic:spPr></pic:pic></a:graphicData></a:graphic>\
+       </wp:inline></w:drawing></w:r>",
+      n = self.drawings,
+      alt = escape_html(&image.alt),
+    );
+    self.open_paragraph();
+    self.push(&drawing);
+  }
+
+  /// Adds a relationship of `document.xml`, returning its id.
+  fn relationship(&mut self, kind: &str, target: &str, attributes: &str) -> String {
+    let id = format!("rId{}", FIXED_RELATIONSHIPS + self.relationships.len() + 1);
+    self.relationships.push(format!(
+      "<Relationship Id=\"{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/{}\" Target=\"{}\"{}/>",
+      id, kind, target, attributes
+    ));
+    id
+  }
+
+  fn numbering_xml(&self) -> String {
+    let bullet_levels: String = (0..9)
+      .map(|level| {
+        format!(
+          "<w:lvl w:ilvl=\"{}\"><w:start w:val=\"1\"/><w:numFmt w:val=\"bullet\"/><w:lvlText w:val=\"{}\"/><w:lvlJc w:val=\"left\"/><w:pPr><w:ind w:left=\"{}\" w:hanging=\"360\"/></w:pPr></w:lvl>",
+          level,
+          BULLETS[level % BULLETS.len()],
+          720 * (level + 1)
+        )
+      })
+      .collect();
+    let ordered_levels: String = (0..9)
+      .map(|level| {
+        format!(
+          "<w:lvl w:ilvl=\"{}\"><w:start w:val=\"1\"/><w:numFmt w:val=\"decimal\"/><w:lvlText w:val=\"%{}.\"/><w:lvlJc w:val=\"left\"/><w:pPr><w:ind w:left=\"{}\" w:hanging=\"360\"/></w:pPr></w:lvl>",
+          level,
+          level + 1,
+          720 * (level + 1)
+        )
+      })
+      .collect();
+    let ordered_nums: String = self
+      .ordered_lists
+      .iter()
+      .map(|(num_id, level, start)| {
+        format!(
+          "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"{}\"/><w:lvlOverride w:ilvl=\"{}\"><w:startOverride w:val=\"{}\"/></w:lvlOverride></w:num>",
+          num_id, ORDERED_ABSTRACT_ID, level, start
+        )
+      })
+      .collect();
+
+    format!(
+      "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+       <w:numbering xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+       <w:abstractNum w:abstractNumId=\"{}\"><w:multiLevelType w:val=\"hybridMultilevel\"/>{}</w:abstractNum>\
+       <w:abstractNum w:abstractNumId=\"{}\"><w:multiLevelType w:val=\"hybridMultilevel\"/>{}</w:abstractNum>\
+       <w:num w:numId=\"{}\"><w:abstractNumId w:val=\"{}\"/></w:num>{}</w:numbering>\n",
+      BULLET_ABSTRACT_ID,
+      bullet_levels,
+      ORDERED_ABSTRACT_ID,
+      ordered_levels,
+      BULLET_NUM_ID,
+      BULLET_ABSTRACT_ID,
+      ordered_nums
+    )
+  }
+
+  /// Packages the document.
+  fn finish(mut self, title: &str) -> Result<File, anyhow::Error> {
+    self.close_paragraph();
+    let document = format!(
+      "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+       <w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" \
+       xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\" \
+       xmlns:wp=\"http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing\">\
+       <w:body>{}<w:sectPr><w:pgSz w:w=\"12240\" w:h=\"15840\"/>\
+       <w:pgMar w:top=\"1440\" w:right=\"1440\" w:bottom=\"1440\" w:left=\"1440\" w:header=\"720\" w:footer=\"720\" w:gutter=\"0\"/>\
+       </w:sectPr></w:body></w:document>\n",
+      self.body
+    );
+    let document_rels = format!(
+      "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+       <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+       <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/>\
+       <Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering\" Target=\"numbering.xml\"/>\
+       {}</Relationships>\n",
+      self.relationships.concat()
+    );
+    let core = format!(
+      "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+       <cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+       xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><dc:title>{}</dc:title></cp:coreProperties>\n",
+      escape_html(title)
+    );
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = temp_archive()?;
+    let numbering = self.numbering_xml();
+    let parts = [
+      ("[Content_Types].xml", CONTENT_TYPES_XML),
+      ("_rels/.rels", PACKAGE_RELS_XML),
+      ("docProps/core.xml", &core),
+      ("word/document.xml", &document),
+      ("word/_rels/document.xml.rels", &document_rels),
+      ("word/styles.xml", STYLES_XML),
+      ("word/numbering.xml", &numbering),
+    ];
+    for (name, content) in parts {
+      zip.start_file(name, options)?;
+      zip.write_all(content.as_bytes())?;
+    }
+    for media in &self.media {
+      zip.start_file(format!("word/media/{}", media.name), options)?;
+      zip.write_all(&media.bytes)?;
+    }
+    finish_archive(zip)
+  }
+}
+
+/// A run of text, with tabs and the characters XML can't hold taken care of.
+fn text_run(text: &str, properties: &str) -> String {
+  let text: String = text
+    .chars()
+    .filter(|c| !c.is_control() || matches!(c, '\t' | '\n'))
+    .map(|c| if c == '\n' { ' ' } else { c })
+    .collect();
+  let mut content = String::new();
+  for (index, part) in text.split('\t').enumerate() {
+    if index > 0 {
+      content.push_str("<w:tab/>");
+    }
+    if !part.is_empty() {
+      content.push_str(&format!(
+        "<w:t xml:space=\"preserve\">{}</w:t>",
+        escape_html(part)
+      ));
+    }
+  }
+  if properties.is_empty() {
+    format!("<w:r>{}</w:r>", content)
+  } else {
+    format!("<w:r><w:rPr>{}</w:rPr>{}</w:r>", properties, content)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn writer(markdown: &str) -> DocxWriter<impl FnMut(&str) -> Option<(Vec<u8>, String)>> {
+    let (events, _) = anchored_events(markdown);
+    let mut writer = DocxWriter::new(|_: &str| None);
+    for event in events {
+      writer.event(event);
+    }
+    writer.close_paragraph();
+    writer
+  }
+
+  /// The properties of each paragraph written for `markdown`.
+  fn paragraph_properties(markdown: &str) -> Vec<String> {
+    writer(markdown)
+      .body
+      .split("<w:p>")
+      .skip(1)
+      .map(|paragraph| {
+        paragraph
+          .strip_prefix("<w:pPr>")
+          .and_then(|rest| rest.split_once("</w:pPr>"))
+          .map(|(properties, _)| properties.to_string())
+          .unwrap_or_default()
+      })
+      .collect()
+  }
+
+  fn list_item(level: usize, num_id: u32) -> String {
+    format!(
+      "<w:pStyle w:val=\"ListParagraph\"/><w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>",
+      level, num_id
+    )
+  }
+
+  #[test]
+  fn test_nested_lists() {
+    let markdown = "- a\n  1. b\n  2. c\n\n     more\n- d\n";
+    let writer = writer(markdown);
+    let ordered = BULLET_NUM_ID + 1;
+    assert_eq!(writer.ordered_lists, vec![(ordered, 1, 1)]);
+
+    assert_eq!(
+      paragraph_properties(markdown),
+      vec![
+        list_item(0, BULLET_NUM_ID),
+        list_item(1, ordered),
+        list_item(1, ordered),
+        // a following paragraph of the item is indented without a marker
+        "<w:pStyle w:val=\"ListParagraph\"/><w:ind w:left=\"1440\"/>".to_string(),
+        list_item(0, BULLET_NUM_ID),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_items_starting_with_other_blocks_keep_their_marker() {
+    let marker = format!(
+      "<w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"{}\"/></w:numPr>",
+      BULLET_NUM_ID
+    );
+    assert_eq!(
+      paragraph_properties("- # Title\n- ```\n  code\n  ```\n-\n"),
+      vec![
+        format!("<w:pStyle w:val=\"Heading1\"/>{}", marker),
+        format!("<w:pStyle w:val=\"Code\"/>{}", marker),
+        // an empty item
+        list_item(0, BULLET_NUM_ID),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_tables() {
+    let markdown = "| Name | Size |\n|:-----|:----:|\n| a | |\n";
+    let body = writer(markdown).body;
+    assert_eq!(body.matches("<w:tr>").count(), 2);
+    assert_eq!(body.matches("<w:tc>").count(), 4);
+    assert!(body.starts_with(
+      "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr>\
+       <w:tblGrid><w:gridCol w:w=\"4680\"/><w:gridCol w:w=\"4680\"/></w:tblGrid>\
+       <w:tr><w:trPr><w:tblHeader/></w:trPr>"
+    ));
+    assert!(body.ends_with("</w:tr></w:tbl>"));
+
+    assert_eq!(
+      paragraph_properties(markdown),
+      vec![
+        "<w:spacing w:after=\"0\"/>",
+        "<w:spacing w:after=\"0\"/><w:jc w:val=\"center\"/>",
+        "<w:spacing w:after=\"0\"/>",
+        // an empty cell still gets its paragraph
+        "<w:spacing w:after=\"0\"/><w:jc w:val=\"center\"/>",
+      ]
+    );
+    // the header row is bold
+    assert!(
+      body.contains("<w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">Name</w:t></w:r>")
+    );
+    assert!(body.contains("<w:r><w:t xml:space=\"preserve\">a</w:t></w:r>"));
+  }
+}
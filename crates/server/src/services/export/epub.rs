@@ -0,0 +1,307 @@
+use std::{collections::HashSet, io::Write, path::Path};
+
+use chrono::Utc;
+use pulldown_cmark::{Event, html};
+use sha2::{Digest, Sha256};
+use zip::{CompressionMethod, write::SimpleFileOptions};
+
+use super::{
+  ExportFile, ExportHeading, ExportNavItem, ExportService, LinkTarget, escape_html, finish_archive,
+  html::{anchored_events, titled_body},
+  relative_href, temp_archive,
+};
+use crate::services::img::ImgService;
+
+const EPUB_MIME: &str = "application/epub+zip";
+
+/// Deepest heading level listed in the table of contents.
+const TOC_MAX_LEVEL: u8 = 3;
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const STYLE: &str = "body { font-family: serif; line-height: 1.5; }
+h1, h2, h3, h4, h5, h6 { font-family: sans-serif; line-height: 1.2; }
+pre { white-space: pre-wrap; font-size: 0.85em; background: #f6f8fa; padding: 0.5em; }
+code { font-family: monospace; }
+blockquote { margin-left: 0; padding-left: 1em; border-left: 3px solid #d0d7de; color: #57606a; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: 0.2em 0.5em; }
+img { max-width: 100%; }
+nav ol { list-style: none; }
+";
+
+/// A chapter of the book, for the table of contents.
+struct Chapter {
+  file: String,
+  /// Headings of the chapter, without the one used as its title.
+  headings: Vec<ExportHeading>,
+}
+
+impl ExportService {
+  /// Turns the docs of a folder (the whole workspace when empty) into an EPUB book: a
+  /// chapter per doc in the sidebar order, a table of contents of the chapters and their
+  /// headings, links between docs pointing to their chapters and the linked images embedded.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let book = export_service.export_epub("guides")?;
+  /// io::copy(&mut book.file, &mut File::create(&book.file_name)?)?;
+  /// ```
+  pub fn export_epub(&self, folder_doc_path: &str) -> Result<ExportFile, anyhow::Error> {
+    tracing::info!("[ExportService] export_epub: {:?}", folder_doc_path);
+    let (docs, nav) = self.collect_docs(folder_doc_path)?;
+    let folder_depth = docs[0].path.len() - docs[0].relative.len();
+    let title = self.folder_title(&docs[0].path[..folder_depth]);
+    let links = self.export_links(&docs)?;
+    let chapter_files: Vec<String> = (1..=docs.len())
+      .map(|number| format!("chapter-{}.xhtml", number))
+      .collect();
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = temp_archive()?;
+    // the mimetype must come first, uncompressed
+    zip.start_file(
+      "mimetype",
+      SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+    )?;
+    zip.write_all(EPUB_MIME.as_bytes())?;
+    zip.start_file("META-INF/container.xml", options)?;
+    zip.write_all(CONTAINER_XML.as_bytes())?;
+    zip.start_file("OEBPS/style.css", options)?;
+    zip.write_all(STYLE.as_bytes())?;
+
+    let mut images: Vec<Vec<String>> = Vec::new();
+    let mut seen_images: HashSet<Vec<String>> = HashSet::new();
+    let mut chapters = Vec::new();
+    for (index, doc) in docs.iter().enumerate() {
+      let chapter_file = &chapter_files[index];
+      let markdown = self.rewrite_links(doc, &links, |target| match target {
+        LinkTarget::Doc { index, anchor } => Some(match anchor {
+          Some(anchor) => format!("{}#{}", chapter_files[*index], anchor),
+          None => chapter_files[*index].clone(),
+        }),
+        // other files can't be opened from the book
+        LinkTarget::Asset { path } => {
+          if !ImgService::is_image(Path::new(path.last()?)) {
+            return None;
+          }
+          if seen_images.insert(path.clone()) {
+            images.push(path.clone());
+          }
+          Some(relative_href(
+            std::slice::from_ref(chapter_file),
+            &image_path(path),
+          ))
+        }
+      });
+
+      let (events, mut headings) = anchored_events(&markdown);
+      let body = render_xhtml(events);
+      // the doc's own title is the chapter's one
+      if body.starts_with("<h1") && !headings.is_empty() {
+        headings.remove(0);
+      }
+      let body = titled_body(&doc.title, body);
+      zip.start_file(format!("OEBPS/{}", chapter_file), options)?;
+      zip.write_all(xhtml_page(&doc.title, &body).as_bytes())?;
+      chapters.push(Chapter {
+        file: chapter_file.clone(),
+        headings,
+      });
+    }
+
+    let mut image_items = Vec::new();
+    for (number, path) in images.iter().enumerate() {
+      let (bytes, mime) = self.img_service.get_image(&path.join("/"))?;
+      let href = relative_href(&["content.opf".to_string()], &image_path(path));
+      zip.start_file(format!("OEBPS/{}", image_path(path).join("/")), options)?;
+      zip.write_all(&bytes)?;
+      image_items.push(format!(
+        "    <item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+        number + 1,
+        escape_html(&href),
+        mime
+      ));
+    }
+
+    let toc = format!(
+      "<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n{}</nav>\n",
+      escape_html(&title),
+      toc_list(&nav, &chapters)
+    );
+    zip.start_file("OEBPS/nav.xhtml", options)?;
+    zip.write_all(xhtml_page(&title, &toc).as_bytes())?;
+
+    let doc_root = self.settings_service.get_settings().doc_root_path;
+    let identifier = Sha256::digest(format!("{}\n{}", doc_root.display(), folder_doc_path));
+    zip.start_file("OEBPS/content.opf", options)?;
+    zip.write_all(
+      package_opf(
+        &title,
+        &format!("urn:md-editor:{:x}", identifier),
+        &chapter_files,
+        &image_items.concat(),
+      )
+      .as_bytes(),
+    )?;
+
+    let file = finish_archive(zip)?;
+    tracing::info!(
+      "[ExportService] exported {} chapters and {} images to an epub",
+      docs.len(),
+      images.len()
+    );
+    Ok(ExportFile {
+      file_name: format!("{}.epub", if title.is_empty() { "export" } else { &title }),
+      mime: EPUB_MIME.to_string(),
+      file,
+    })
+  }
+}
+
+/// Path of an embedded image in the book, from the `OEBPS` folder.
+fn image_path(path: &[String]) -> Vec<String> {
+  let mut image = vec!["images".to_string()];
+  image.extend(path.iter().cloned());
+  image
+}
+
+/// Renders events to XHTML, the raw HTML of the docs being shown as text since it may
+/// not be valid XML.
+fn render_xhtml<'a>(events: Vec<Event<'a>>) -> String {
+  let mut in_comment = false;
+  let events = events.into_iter().filter_map(|event| match event {
+    // comments of html blocks come line by line
+    Event::Html(raw) | Event::InlineHtml(raw)
+      if in_comment || raw.trim_start().starts_with("<!--") =>
+    {
+      in_comment = !raw.contains("-->");
+      None
+    }
+    Event::Html(raw) | Event::InlineHtml(raw) => Some(Event::Text(raw)),
+    event => Some(event),
+  });
+  let mut xhtml = String::new();
+  html::push_html(&mut xhtml, events);
+  xhtml
+}
+
+fn xhtml_page(title: &str, body: &str) -> String {
+  format!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n\
+     <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+     <head>\n<title>{}</title>\n<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n</head>\n\
+     <body>\n{}</body>\n</html>\n",
+    escape_html(title),
+    body
+  )
+}
+
+fn package_opf(
+  title: &str,
+  identifier: &str,
+  chapter_files: &[String],
+  image_items: &str,
+) -> String {
+  let chapter_items: String = chapter_files
+    .iter()
+    .enumerate()
+    .map(|(index, file)| {
+      format!(
+        "    <item id=\"chapter-{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+        index + 1,
+        file
+      )
+    })
+    .collect();
+  let itemrefs: String = (1..=chapter_files.len())
+    .map(|number| format!("    <itemref idref=\"chapter-{}\"/>\n", number))
+    .collect();
+
+  format!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">{}</dc:identifier>
+    <dc:title>{}</dc:title>
+    <dc:language>en</dc:language>
+    <meta property="dcterms:modified">{}</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="style" href="style.css" media-type="text/css"/>
+{}{}  </manifest>
+  <spine>
+{}  </spine>
+</package>
+"#,
+    escape_html(identifier),
+    escape_html(title),
+    Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+    chapter_items,
+    image_items,
+    itemrefs
+  )
+}
+
+/// Table of contents following the navigation, each chapter listing its headings.
+fn toc_list(items: &[ExportNavItem], chapters: &[Chapter]) -> String {
+  let mut toc = String::from("<ol>\n");
+  for item in items {
+    let title = escape_html(&item.title);
+    match item.doc {
+      Some(index) => {
+        let chapter = &chapters[index];
+        let headings: Vec<&ExportHeading> = chapter
+          .headings
+          .iter()
+          .filter(|heading| heading.level <= TOC_MAX_LEVEL && !heading.text.trim().is_empty())
+          .collect();
+        toc.push_str(&format!(
+          "<li><a href=\"{}\">{}</a>\n{}</li>\n",
+          chapter.file,
+          title,
+          heading_list(&headings, &chapter.file)
+        ));
+      }
+      None => toc.push_str(&format!(
+        "<li><span>{}</span>\n{}</li>\n",
+        title,
+        toc_list(&item.children, chapters)
+      )),
+    }
+  }
+  toc.push_str("</ol>\n");
+  toc
+}
+
+/// Nests the headings of a chapter by level, e.g. the `h3`s under the `h2` before them.
+fn heading_list(headings: &[&ExportHeading], chapter_file: &str) -> String {
+  if headings.is_empty() {
+    return String::new();
+  }
+  let mut list = String::from("<ol>\n");
+  let mut rest = headings;
+  while let Some((heading, tail)) = rest.split_first() {
+    let end = tail
+      .iter()
+      .position(|next| next.level <= heading.level)
+      .unwrap_or(tail.len());
+    list.push_str(&format!(
+      "<li><a href=\"{}#{}\">{}</a>\n{}</li>\n",
+      chapter_file,
+      escape_html(&heading.id),
+      escape_html(&heading.text),
+      heading_list(&tail[..end], chapter_file)
+    ));
+    rest = &tail[end..];
+  }
+  list.push_str("</ol>\n");
+  list
+}
@@ -0,0 +1,266 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use pulldown_cmark::{CowStr, Event, Parser, Tag, TagEnd, html};
+
+use super::{
+  ExportDoc, ExportHeading, ExportNavItem, ExportService, ExportSummary, LinkTarget, escape_html,
+  relative_href,
+};
+use crate::{
+  services::doc::markdown::parser_options, services::doc::markdown::slugify,
+  utils::atomic_write::write_atomic,
+};
+
+/// Exported page of the folder itself, unless it has an `index` doc.
+const INDEX_PAGE: &str = "index.html";
+
+const STYLE: &str = "
+body { margin: 0; display: flex; font: 16px/1.6 -apple-system, BlinkMacSystemFont, 'Segoe UI', Helvetica, Arial, sans-serif; color: #24292f; }
+nav { width: 260px; flex-shrink: 0; height: 100vh; position: sticky; top: 0; overflow-y: auto; padding: 16px; box-sizing: border-box; background: #f6f8fa; border-right: 1px solid #d0d7de; font-size: 14px; }
+nav ul { list-style: none; margin: 0; padding-left: 14px; }
+nav > ul { padding-left: 0; }
+nav a { color: inherit; text-decoration: none; }
+nav a:hover { text-decoration: underline; }
+nav .current { font-weight: 600; color: #0969da; }
+nav .folder { color: #57606a; font-weight: 600; }
+main { flex: 1; min-width: 0; max-width: 860px; padding: 32px 48px; }
+pre { background: #f6f8fa; padding: 12px 16px; overflow-x: auto; border-radius: 6px; }
+code { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 85%; }
+blockquote { margin: 0; padding: 0 16px; color: #57606a; border-left: 4px solid #d0d7de; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: 6px 12px; }
+img { max-width: 100%; }
+";
+
+impl ExportService {
+  /// Renders the docs of a folder (the whole workspace when empty) to a static site in
+  /// `out_dir`: a page per doc with the folder's navigation, heading anchors, links
+  /// between docs pointing to their pages and the linked images copied along.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let summary = export_service.export_html("guides", Path::new("/tmp/site"))?;
+  /// println!("{} pages written to {:?}", summary.docs, summary.out_path);
+  /// ```
+  pub fn export_html(
+    &self,
+    folder_doc_path: &str,
+    out_dir: &Path,
+  ) -> Result<ExportSummary, anyhow::Error> {
+    tracing::info!(
+      "[ExportService] export_html: {:?} to {:?}",
+      folder_doc_path,
+      out_dir
+    );
+    if out_dir.is_file() {
+      return Err(anyhow::anyhow!("{} is not a directory.", out_dir.display()));
+    }
+    let (docs, nav) = self.collect_docs(folder_doc_path)?;
+    let folder_depth = docs[0].path.len() - docs[0].relative.len();
+    let links = self.export_links(&docs)?;
+    let pages: Vec<Vec<String>> = docs.iter().map(page_path).collect();
+    let doc_root = self.settings_service.get_settings().doc_root_path;
+
+    fs::create_dir_all(out_dir)?;
+    let mut assets: HashSet<Vec<String>> = HashSet::new();
+    for (index, doc) in docs.iter().enumerate() {
+      let page = &pages[index];
+      let markdown = self.rewrite_links(doc, &links, |target| match target {
+        LinkTarget::Doc { index, anchor } => {
+          let href = relative_href(page, &pages[*index]);
+          Some(match anchor {
+            Some(anchor) => format!("{}#{}", href, anchor),
+            None => href,
+          })
+        }
+        LinkTarget::Asset { path } => {
+          let asset = asset_path(path, &doc.path[..folder_depth]);
+          assets.insert(path.clone());
+          Some(relative_href(page, &asset))
+        }
+      });
+
+      let body = titled_body(&doc.title, render_html(&markdown));
+      let html = page_html(
+        &doc.title,
+        &body,
+        &nav_html(&nav, &pages, page, Some(index)),
+      );
+      write_file(&out_dir.join(page.join("/")), html.as_bytes())?;
+    }
+
+    let index_page = vec![INDEX_PAGE.to_string()];
+    if !pages.contains(&index_page) {
+      let title = self.folder_title(&docs[0].path[..folder_depth]);
+      let body = titled_body(&title, nav_list(&nav, &pages, &index_page, None));
+      let html = page_html(&title, &body, &nav_html(&nav, &pages, &index_page, None));
+      write_file(&out_dir.join(INDEX_PAGE), html.as_bytes())?;
+    }
+
+    for path in &assets {
+      let asset = asset_path(path, &docs[0].path[..folder_depth]);
+      let dest = out_dir.join(asset.join("/"));
+      if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+      }
+      fs::copy(doc_root.join(path.join("/")), dest)?;
+    }
+
+    tracing::info!(
+      "[ExportService] exported {} docs and {} assets to {:?}",
+      docs.len(),
+      assets.len(),
+      out_dir
+    );
+    Ok(ExportSummary {
+      docs: docs.len(),
+      assets: assets.len(),
+      out_path: out_dir.to_path_buf(),
+    })
+  }
+}
+
+/// Renders markdown to HTML, giving the headings the same anchors as the editor outline.
+///
+/// # Example
+/// ```
+/// use server::services::export::render_html;
+///
+/// let html = render_html("---\ntitle: Notes\n---\n## Getting Started\n\nSome *text*.\n");
+/// assert_eq!(html, "<h2 id=\"getting-started\">Getting Started</h2>\n<p>Some <em>text</em>.</p>\n");
+/// ```
+pub fn render_html(markdown: &str) -> String {
+  let (events, _) = anchored_events(markdown);
+  let mut html = String::new();
+  html::push_html(&mut html, events.into_iter());
+  html
+}
+
+/// Parses markdown, giving the headings their anchors, and returns the headings found.
+pub(super) fn anchored_events(markdown: &str) -> (Vec<Event<'_>>, Vec<ExportHeading>) {
+  let mut events: Vec<Event> = Parser::new_ext(markdown, parser_options()).collect();
+  let mut headings = Vec::new();
+
+  for start in 0..events.len() {
+    let Event::Start(Tag::Heading { level, id, .. }) = &events[start] else {
+      continue;
+    };
+    let level = *level as u8;
+    let has_id = id.is_some();
+    let mut text = String::new();
+    for event in &events[start + 1..] {
+      match event {
+        Event::End(TagEnd::Heading(_)) => break,
+        Event::Text(content) | Event::Code(content) => text.push_str(content),
+        Event::SoftBreak | Event::HardBreak => text.push(' '),
+        _ => {}
+      }
+    }
+    if let Event::Start(Tag::Heading { id, .. }) = &mut events[start] {
+      if !has_id {
+        *id = Some(CowStr::from(slugify(&text)));
+      }
+      headings.push(ExportHeading {
+        level,
+        id: id.as_deref().unwrap_or_default().to_string(),
+        text,
+      });
+    }
+  }
+
+  (events, headings)
+}
+
+/// Prepends the title to a rendered doc, unless the doc starts with its own.
+pub(super) fn titled_body(title: &str, html: String) -> String {
+  if html.starts_with("<h1") {
+    html
+  } else {
+    format!("<h1 class=\"title\">{}</h1>\n{}", escape_html(title), html)
+  }
+}
+
+/// Output path of a doc page, from the exported folder.
+fn page_path(doc: &ExportDoc) -> Vec<String> {
+  let mut page = doc.relative.clone();
+  if let Some(name) = page.last_mut() {
+    name.push_str(".html");
+  }
+  page
+}
+
+/// Output path of a linked file: from the exported folder when it's inside of it,
+/// otherwise from the workspace root, e.g. `_assets/logo.png`.
+fn asset_path(path: &[String], folder: &[String]) -> Vec<String> {
+  match path.strip_prefix(folder) {
+    Some(relative) if !relative.is_empty() => relative.to_vec(),
+    _ => path.to_vec(),
+  }
+}
+
+fn page_html(title: &str, body: &str, nav: &str) -> String {
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+     <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+     <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}\n<main>\n{}</main>\n</body>\n</html>\n",
+    escape_html(title),
+    STYLE,
+    nav,
+    body
+  )
+}
+
+fn nav_html(
+  items: &[ExportNavItem],
+  pages: &[Vec<String>],
+  page: &[String],
+  current: Option<usize>,
+) -> String {
+  format!(
+    "<nav>\n<a href=\"{}\">Home</a>\n{}</nav>",
+    relative_href(page, &[INDEX_PAGE.to_string()]),
+    nav_list(items, pages, page, current)
+  )
+}
+
+fn nav_list(
+  items: &[ExportNavItem],
+  pages: &[Vec<String>],
+  page: &[String],
+  current: Option<usize>,
+) -> String {
+  let mut html = String::from("<ul>\n");
+  for item in items {
+    let title = escape_html(&item.title);
+    match item.doc {
+      Some(index) => {
+        let class = if current == Some(index) {
+          " class=\"current\""
+        } else {
+          ""
+        };
+        html.push_str(&format!(
+          "<li><a{} href=\"{}\">{}</a></li>\n",
+          class,
+          escape_html(&relative_href(page, &pages[index])),
+          title
+        ));
+      }
+      None => html.push_str(&format!(
+        "<li><span class=\"folder\">{}</span>\n{}</li>\n",
+        title,
+        nav_list(&item.children, pages, page, current)
+      )),
+    }
+  }
+  html.push_str("</ul>\n");
+  html
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<(), anyhow::Error> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  write_atomic(path, content)?;
+  Ok(())
+}
@@ -0,0 +1,815 @@
+mod docx;
+mod epub;
+mod html;
+
+use std::{
+  collections::HashMap,
+  fs::File,
+  io::{self, Seek},
+  ops::Range,
+  path::PathBuf,
+  sync::Arc,
+};
+
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::Serialize;
+use zip::ZipWriter;
+
+pub use html::render_html;
+
+use crate::{
+  services::{
+    doc::{
+      Article, DocService, NormalizedDocMap, denormalize_path,
+      helpers::doc_name,
+      links::{extract_links, resolve_link},
+      markdown::{parser_options, slugify},
+      normalize_path,
+      wiki_links::{WikiLinkStatus, parse_wiki_links, resolve_wiki_target},
+    },
+    img::ImgService,
+    settings::SettingsService,
+  },
+  utils::workspace_path::WorkspacePath,
+};
+
+/// Characters encoded in the path segments of exported links.
+const HREF_SEGMENT_ENCODE: &AsciiSet = &CONTROLS
+  .add(b' ')
+  .add(b'"')
+  .add(b'#')
+  .add(b'%')
+  .add(b'\'')
+  .add(b'(')
+  .add(b')')
+  .add(b'<')
+  .add(b'>')
+  .add(b'?')
+  .add(b'[')
+  .add(b']')
+  .add(b'`');
+
+/// A doc to export, in the order of the sidebar.
+#[derive(Debug, Clone)]
+pub struct ExportDoc {
+  /// Normalized path of the doc, e.g. `js%2Farray`.
+  pub key: String,
+  /// Doc path segments from the workspace root.
+  pub path: Vec<String>,
+  /// Doc path segments from the exported folder.
+  pub relative: Vec<String>,
+  /// The front matter `title`, or the doc name.
+  pub title: String,
+  pub content: String,
+}
+
+/// A heading of an exported doc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportHeading {
+  pub level: u8,
+  /// Anchor of the heading, in the same format as the editor's heading ids.
+  pub id: String,
+  pub text: String,
+}
+
+/// A folder or doc of the exported navigation.
+#[derive(Debug, Clone)]
+pub struct ExportNavItem {
+  pub title: String,
+  /// Index of the doc in the exported docs, `None` for folders.
+  pub doc: Option<usize>,
+  pub children: Vec<ExportNavItem>,
+}
+
+/// What a link of an exported doc points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+  /// Another exported doc, with the anchor of a heading.
+  Doc {
+    index: usize,
+    anchor: Option<String>,
+  },
+  /// An image of the workspace, e.g. of `_assets`.
+  Asset { path: Vec<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+  pub docs: usize,
+  /// Images the docs link to, copied next to them.
+  pub assets: usize,
+  pub out_path: PathBuf,
+}
+
+/// An exported file to download.
+#[derive(Debug)]
+pub struct ExportFile {
+  pub file_name: String,
+  pub mime: String,
+  /// Temp file holding the content, read from the start. It's removed once closed.
+  pub file: File,
+}
+
+/// What the links of the exported docs resolve against, read once per export.
+struct ExportLinks {
+  /// Index of each exported doc by its normalized path.
+  doc_indexes: HashMap<String, usize>,
+  doc_root: PathBuf,
+  doc_extensions: Vec<String>,
+  /// Path segments of every doc of the workspace, to resolve wiki links.
+  wiki_link_docs: Vec<Vec<String>>,
+}
+
+pub struct ExportService {
+  settings_service: Arc<SettingsService>,
+  doc_service: Arc<DocService>,
+  img_service: Arc<ImgService>,
+}
+
+impl ExportService {
+  pub fn new(
+    settings_service: Arc<SettingsService>,
+    doc_service: Arc<DocService>,
+    img_service: Arc<ImgService>,
+  ) -> Self {
+    Self {
+      settings_service,
+      doc_service,
+      img_service,
+    }
+  }
+
+  /// Name of the exported folder, or of the workspace for the whole of it.
+  fn folder_title(&self, folder: &[String]) -> String {
+    folder.last().cloned().unwrap_or_else(|| {
+      self
+        .settings_service
+        .get_settings()
+        .doc_root_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+    })
+  }
+
+  /// Docs of a folder (the whole workspace when empty) in the sidebar order, with the
+  /// navigation tree between them. Folders without docs are left out.
+  fn collect_docs(
+    &self,
+    folder_doc_path: &str,
+  ) -> Result<(Vec<ExportDoc>, Vec<ExportNavItem>), anyhow::Error> {
+    let folder: Vec<String> = denormalize_path(folder_doc_path)
+      .into_iter()
+      .filter(|segment| !segment.is_empty())
+      .collect();
+    let doc_tree = self.doc_service.get_doc_tree(false)?;
+
+    let root_keys = if folder.is_empty() {
+      self
+        .doc_service
+        .get_sub_doc_items("", false, false)?
+        .into_iter()
+        .map(|item| normalize_path(&item.path))
+        .collect()
+    } else {
+      match doc_tree.get(&normalize_path(&folder)) {
+        Some(doc) if !doc.is_file => doc.children_keys.clone(),
+        _ => {
+          return Err(anyhow::anyhow!(
+            "The folder {} does not exist.",
+            folder_doc_path
+          ));
+        }
+      }
+    };
+
+    let mut docs = Vec::new();
+    let nav = self.collect_nav(&doc_tree, &root_keys, folder.len(), &mut docs)?;
+    if docs.is_empty() {
+      return Err(anyhow::anyhow!(
+        "The folder {} has no docs to export.",
+        folder_doc_path
+      ));
+    }
+    Ok((docs, nav))
+  }
+
+  fn collect_nav(
+    &self,
+    doc_tree: &NormalizedDocMap,
+    keys: &[String],
+    folder_depth: usize,
+    docs: &mut Vec<ExportDoc>,
+  ) -> Result<Vec<ExportNavItem>, anyhow::Error> {
+    let mut items = Vec::new();
+    for key in keys {
+      let Some(doc) = doc_tree.get(key) else {
+        continue;
+      };
+      if !doc.is_file {
+        let children = self.collect_nav(doc_tree, &doc.children_keys, folder_depth, docs)?;
+        if !children.is_empty() {
+          items.push(ExportNavItem {
+            title: doc.name.clone(),
+            doc: None,
+            children,
+          });
+        }
+        continue;
+      }
+
+      let Some(article) = self.doc_service.get_article(key)? else {
+        continue;
+      };
+      let title = article_title(&article, &doc.name);
+      items.push(ExportNavItem {
+        title: title.clone(),
+        doc: Some(docs.len()),
+        children: Vec::new(),
+      });
+      docs.push(ExportDoc {
+        key: key.clone(),
+        path: doc.path.clone(),
+        relative: doc.path[folder_depth..].to_vec(),
+        title,
+        content: article.content,
+      });
+    }
+    Ok(items)
+  }
+
+  fn export_links(&self, docs: &[ExportDoc]) -> Result<ExportLinks, anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    Ok(ExportLinks {
+      doc_indexes: docs
+        .iter()
+        .enumerate()
+        .map(|(index, doc)| (doc.key.clone(), index))
+        .collect(),
+      doc_root: settings.doc_root_path,
+      doc_extensions: settings.doc_extensions,
+      wiki_link_docs: self
+        .doc_service
+        .get_doc_tree(false)?
+        .into_values()
+        .filter(|doc| doc.is_file)
+        .map(|doc| doc.path)
+        .collect(),
+    })
+  }
+
+  /// Resolves a link destination of `doc` to an exported doc or a workspace image.
+  /// External, missing, pure `#anchor` links and links into hidden folders resolve
+  /// to `None`, like links to other files and to docs left out of the export.
+  fn link_target(&self, doc: &ExportDoc, dest: &str, links: &ExportLinks) -> Option<LinkTarget> {
+    let resolved = resolve_link(&doc.path, dest)?;
+    if resolved
+      .target
+      .iter()
+      .any(|segment| segment.starts_with('.'))
+    {
+      return None;
+    }
+    let anchor = resolved
+      .suffix
+      .split_once('#')
+      .map(|(_, anchor)| anchor.to_string())
+      .filter(|anchor| !anchor.is_empty());
+
+    if let Some(doc_path) = linked_doc_path(&resolved.target, &links.doc_extensions)
+      && let Some(index) = links.doc_indexes.get(&normalize_path(&doc_path))
+    {
+      return Some(LinkTarget::Doc {
+        index: *index,
+        anchor,
+      });
+    }
+
+    let file = WorkspacePath::resolve(&links.doc_root, &resolved.target).ok()?;
+    let file = file.to_path_buf();
+    (file.is_file() && ImgService::is_image(&file)).then_some(LinkTarget::Asset {
+      path: resolved.target,
+    })
+  }
+
+  /// Whether a link destination of `doc` points to an existing doc that isn't exported,
+  /// e.g. one outside the exported folder or in a hidden folder.
+  fn links_unexported_doc(&self, doc: &ExportDoc, dest: &str, links: &ExportLinks) -> bool {
+    let Some(resolved) = resolve_link(&doc.path, dest) else {
+      return false;
+    };
+    linked_doc_path(&resolved.target, &links.doc_extensions).is_some_and(|doc_path| {
+      let key = normalize_path(&doc_path);
+      !links.doc_indexes.contains_key(&key)
+        && self.doc_service.doc_exists(&key, true).unwrap_or(false)
+    })
+  }
+
+  /// Rewrites the links and wiki links of `doc` with the destinations given by `href`.
+  /// Links and wiki links to docs that aren't exported become plain text, wiki links
+  /// become regular links and links `href` returns `None` for are left as they are.
+  fn rewrite_links(
+    &self,
+    doc: &ExportDoc,
+    links: &ExportLinks,
+    mut href: impl FnMut(&LinkTarget) -> Option<String>,
+  ) -> String {
+    let content = &doc.content;
+    let mut replacements: Vec<(Range<usize>, String)> = Vec::new();
+
+    for (range, text, dest) in link_spans(content) {
+      if self.links_unexported_doc(doc, &dest, links) {
+        replacements.push((range, text));
+      }
+    }
+
+    for link in extract_links(content) {
+      if let Some(dest) = self
+        .link_target(doc, &link.dest, links)
+        .and_then(|target| href(&target))
+      {
+        replacements.push((link.range, dest));
+      }
+    }
+
+    for link in parse_wiki_links(content) {
+      let text = link
+        .alias
+        .clone()
+        .or_else(|| {
+          (!link.target.is_empty()).then(|| match &link.heading {
+            Some(heading) => format!("{} > {}", link.target, heading),
+            None => link.target.clone(),
+          })
+        })
+        .or_else(|| link.heading.clone())
+        .unwrap_or_default();
+
+      let target = if link.target.is_empty() {
+        links
+          .doc_indexes
+          .get(&doc.key)
+          .map(|index| LinkTarget::Doc {
+            index: *index,
+            anchor: link.heading.as_deref().map(slugify),
+          })
+      } else {
+        Some(resolve_wiki_target(
+          &link.target,
+          link.heading.as_deref(),
+          &links.wiki_link_docs,
+        ))
+        .filter(|resolution| resolution.status == WikiLinkStatus::Resolved)
+        .and_then(|resolution| {
+          let index = links.doc_indexes.get(resolution.file_path.as_deref()?)?;
+          Some(LinkTarget::Doc {
+            index: *index,
+            anchor: resolution.heading_id,
+          })
+        })
+      };
+      let replacement = match target.and_then(|target| href(&target)) {
+        Some(dest) => format!("[{}](<{}>)", text, dest),
+        None => text,
+      };
+      replacements.push((link.range, replacement));
+    }
+
+    replacements.sort_by_key(|(range, _)| range.start);
+    let mut rewritten = String::with_capacity(content.len());
+    let mut last = 0;
+    for (range, replacement) in replacements {
+      // the destination of a link made plain text, or a wiki link inside a link
+      // destination, keep the first
+      if range.start < last {
+        continue;
+      }
+      rewritten.push_str(&content[last..range.start]);
+      rewritten.push_str(&replacement);
+      last = range.end;
+    }
+    rewritten.push_str(&content[last..]);
+    rewritten
+  }
+}
+
+/// Doc path segments a link target points to, with one of the doc `extensions` or
+/// without extension.
+fn linked_doc_path(target: &[String], extensions: &[String]) -> Option<Vec<String>> {
+  let (last, dir) = target.split_last()?;
+  let name = doc_name(last, extensions).or_else(|| {
+    let has_extension = last
+      .rsplit_once('.')
+      .is_some_and(|(stem, _)| !stem.is_empty());
+    (!has_extension).then(|| last.clone())
+  })?;
+  let mut doc_path = dir.to_vec();
+  doc_path.push(name);
+  Some(doc_path)
+}
+
+/// The links of `content` (not images) as their source range, the source of their
+/// text and their destination, e.g. `[*a*](b.md)` gives `*a*` and `b.md`.
+fn link_spans(content: &str) -> Vec<(Range<usize>, String, String)> {
+  let mut spans = Vec::new();
+  let mut current: Option<(Range<usize>, Option<Range<usize>>, String)> = None;
+
+  for (event, range) in Parser::new_ext(content, parser_options()).into_offset_iter() {
+    match event {
+      Event::Start(Tag::Link { dest_url, .. }) => {
+        current = Some((range, None, dest_url.to_string()));
+      }
+      Event::End(TagEnd::Link) => {
+        if let Some((link, text, dest)) = current.take() {
+          let text = text.map(|text| content[text].to_string());
+          spans.push((link, text.unwrap_or_default(), dest));
+        }
+      }
+      _ => {
+        if let Some((_, text, _)) = current.as_mut() {
+          *text = Some(match text.take() {
+            Some(text) => text.start.min(range.start)..text.end.max(range.end),
+            None => range,
+          });
+        }
+      }
+    }
+  }
+  spans
+}
+
+/// Starts an archive in a temp file, so exports aren't held in memory while downloading.
+fn temp_archive() -> io::Result<ZipWriter<File>> {
+  Ok(ZipWriter::new(tempfile::tempfile()?))
+}
+
+/// Finishes an archive of [`temp_archive`], rewound to be read from the start.
+fn finish_archive(zip: ZipWriter<File>) -> Result<File, anyhow::Error> {
+  let mut file = zip.finish()?;
+  file.rewind()?;
+  Ok(file)
+}
+
+/// The front matter `title` of an article, or its doc name.
+fn article_title(article: &Article, name: &str) -> String {
+  article
+    .front_matter
+    .as_ref()
+    .and_then(|front_matter| front_matter["title"].as_str())
+    .map(|title| title.to_string())
+    .unwrap_or_else(|| name.to_string())
+}
+
+/// Relative link from a file at `from` to the file at `to`, both given as path
+/// segments from the same folder, with each segment percent-encoded.
+///
+/// # Example
+/// ```
+/// use server::services::export::relative_href;
+///
+/// let from = vec!["js".to_string(), "array.html".to_string()];
+/// let to = vec!["css".to_string(), "flex box.html".to_string()];
+/// assert_eq!(relative_href(&from, &to), "../css/flex%20box.html");
+/// ```
+pub fn relative_href(from: &[String], to: &[String]) -> String {
+  let from_dir = &from[..from.len().saturating_sub(1)];
+  let common = from_dir
+    .iter()
+    .zip(to.iter())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let mut parts: Vec<String> = vec!["..".to_string(); from_dir.len() - common];
+  parts.extend(
+    to[common..]
+      .iter()
+      .map(|segment| utf8_percent_encode(segment, HREF_SEGMENT_ENCODE).to_string()),
+  );
+  parts.join("/")
+}
+
+/// Escapes text for HTML and XML content and attribute values.
+///
+/// # Example
+/// ```
+/// use server::services::export::escape_html;
+///
+/// assert_eq!(escape_html("<a href=\"x\">Q&A</a>"), "&lt;a href=&quot;x&quot;&gt;Q&amp;A&lt;/a&gt;");
+/// ```
+pub fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{fs, path::Path, sync::Arc};
+
+  use super::*;
+  use crate::services::search::SearchService;
+
+  fn setup_test_service() -> (ExportService, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let doc_root_path = temp_dir.path().join("docs");
+    fs::create_dir_all(&doc_root_path).unwrap();
+
+    let settings_service = Arc::new(SettingsService::for_test(
+      doc_root_path,
+      &["node_modules"],
+      temp_dir.path().join("editor-settings.json"),
+    ));
+    let search_service = Arc::new(SearchService::new(settings_service.clone()));
+    let doc_service = Arc::new(DocService::for_test(settings_service.clone()));
+    let img_service = Arc::new(ImgService::new(settings_service.clone(), search_service));
+    (
+      ExportService::new(settings_service, doc_service, img_service),
+      temp_dir,
+    )
+  }
+
+  fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+  }
+
+  #[test]
+  fn test_collect_docs() {
+    let (service, temp_dir) = setup_test_service();
+    let root = temp_dir.path().join("docs");
+    write(&root, "readme.md", "---\ntitle: Welcome\n---\nHi\n");
+    write(&root, "js/array.md", "Arrays\n");
+    write(&root, "js/basic/types.md", "Types\n");
+    write(&root, "empty/notes.txt", "not a doc");
+
+    let (docs, nav) = service.collect_docs("").unwrap();
+    let titles: Vec<&str> = docs.iter().map(|doc| doc.title.as_str()).collect();
+    assert_eq!(titles, vec!["types", "array", "Welcome"]);
+    assert_eq!(nav.len(), 2);
+    assert_eq!(nav[0].title, "js");
+    assert_eq!(nav[0].children[0].title, "basic");
+    assert_eq!(nav[0].children[0].children[0].doc, Some(0));
+
+    let (docs, _) = service.collect_docs("js").unwrap();
+    assert_eq!(docs[0].relative, vec!["basic", "types"]);
+    assert!(service.collect_docs("empty").is_err());
+    assert!(service.collect_docs("missing").is_err());
+  }
+
+  #[test]
+  fn test_rewrite_links() {
+    let (service, temp_dir) = setup_test_service();
+    let root = temp_dir.path().join("docs");
+    write(
+      &root,
+      "js/array.md",
+      "See [types](basic/types.md#setup), [[types#Setup]], [[#Usage|usage]] and [[missing]].\n\
+       ![logo](../_assets/logo.png) [site](https://example.com)\n\
+       [the *plan*](../.drafts/plan.md) ![key](../.drafts/key.png) [data](../_assets/data.csv)\n",
+    );
+    write(&root, "js/basic/types.md", "## Setup\n");
+    write(&root, "_assets/logo.png", "png");
+    write(&root, "_assets/data.csv", "a,b");
+    write(&root, ".drafts/plan.md", "# Plan");
+    write(&root, ".drafts/key.png", "png");
+
+    let (docs, _) = service.collect_docs("").unwrap();
+    let links = service.export_links(&docs).unwrap();
+    let rewritten = service.rewrite_links(&docs[1], &links, |target| match target {
+      LinkTarget::Doc { index, anchor } => Some(format!("doc{}#{}", index, anchor.clone()?)),
+      LinkTarget::Asset { path } => Some(path.join("/")),
+    });
+    assert_eq!(
+      rewritten,
+      "See [types](doc0#setup), [types > Setup](<doc0#setup>), [usage](<doc1#usage>) and missing.\n\
+       ![logo](_assets/logo.png) [site](https://example.com)\n\
+       the *plan* ![key](../.drafts/key.png) [data](../_assets/data.csv)\n"
+    );
+  }
+
+  #[test]
+  fn test_export_html() {
+    let (service, temp_dir) = setup_test_service();
+    let root = temp_dir.path().join("docs");
+    write(
+      &root,
+      "guide/intro.md",
+      "# Start Here\n\nRead [[setup#Install]].\n",
+    );
+    write(
+      &root,
+      "guide/more/setup.md",
+      "## Install\n\n![diagram](diagram.png) ![logo](/_assets/logo.png)\n",
+    );
+    write(&root, "guide/more/diagram.png", "png");
+    write(&root, "_assets/logo.png", "png");
+    write(&root, "other.md", "Not exported\n");
+
+    let out = temp_dir.path().join("site");
+    let summary = service.export_html("guide", &out).unwrap();
+    assert_eq!(summary.docs, 2);
+    assert_eq!(summary.assets, 2);
+
+    let intro = fs::read_to_string(out.join("intro.html")).unwrap();
+    assert!(intro.contains("<h1 id=\"start-here\">Start Here</h1>"));
+    assert!(!intro.contains("class=\"title\""));
+    assert!(intro.contains("<a href=\"more/setup.html#install\">setup &gt; Install</a>"));
+    assert!(intro.contains("<a class=\"current\" href=\"intro.html\">intro</a>"));
+    assert!(!intro.contains("other"));
+
+    let setup = fs::read_to_string(out.join("more/setup.html")).unwrap();
+    assert!(setup.contains("<h1 class=\"title\">setup</h1>"));
+    assert!(setup.contains("<h2 id=\"install\">Install</h2>"));
+    assert!(setup.contains("src=\"diagram.png\""));
+    assert!(setup.contains("src=\"../_assets/logo.png\""));
+    assert!(setup.contains("<a href=\"../index.html\">Home</a>"));
+    assert!(out.join("more/diagram.png").is_file());
+    assert!(out.join("_assets/logo.png").is_file());
+
+    let index = fs::read_to_string(out.join("index.html")).unwrap();
+    assert!(index.contains("<title>guide</title>"));
+    assert!(index.contains("href=\"more/setup.html\""));
+
+    fs::write(temp_dir.path().join("file"), "").unwrap();
+    assert!(
+      service
+        .export_html("guide", &temp_dir.path().join("file"))
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn test_export_epub() {
+    use std::io::Read;
+
+    let (service, temp_dir) = setup_test_service();
+    let root = temp_dir.path().join("docs");
+    write(
+      &root,
+      "book/intro.md",
+      "# Welcome\n\n## Setup\n\n### Install\n\n## Usage\n\nSee [[guide#Steps]] and ![logo](../_assets/logo.png)\n\n<!-- draft\nnotes -->\n<br>\n",
+    );
+    write(
+      &root,
+      "book/more/guide.md",
+      "## Steps\n\n![logo](/_assets/logo.png)\n",
+    );
+    write(&root, "_assets/logo.png", "png");
+
+    let book = service.export_epub("book").unwrap();
+    assert_eq!(book.file_name, "book.epub");
+    assert_eq!(book.mime, "application/epub+zip");
+
+    let mut archive = zip::ZipArchive::new(book.file).unwrap();
+    let read = |archive: &mut zip::ZipArchive<_>, name: &str| {
+      let mut content = String::new();
+      archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+      content
+    };
+    {
+      let mimetype = archive.by_index(0).unwrap();
+      assert_eq!(mimetype.name(), "mimetype");
+      assert_eq!(mimetype.compression(), zip::CompressionMethod::Stored);
+    }
+    assert_eq!(read(&mut archive, "mimetype"), "application/epub+zip");
+    assert_eq!(read(&mut archive, "OEBPS/images/_assets/logo.png"), "png");
+
+    // folders first, as in the sidebar
+    let opf = read(&mut archive, "OEBPS/content.opf");
+    assert!(opf.contains("<dc:title>book</dc:title>"));
+    assert!(opf.contains("href=\"images/_assets/logo.png\" media-type=\"image/png\""));
+    let guide = read(&mut archive, "OEBPS/chapter-1.xhtml");
+    assert!(guide.contains("<h1 class=\"title\">guide</h1>"));
+    assert!(guide.contains("src=\"images/_assets/logo.png\""));
+
+    let intro = read(&mut archive, "OEBPS/chapter-2.xhtml");
+    assert!(intro.contains("<a href=\"chapter-1.xhtml#steps\">guide &gt; Steps</a>"));
+    assert!(intro.contains("&lt;br&gt;"));
+    assert!(!intro.contains("draft") && !intro.contains("notes"));
+
+    let toc = read(&mut archive, "OEBPS/nav.xhtml");
+    assert!(toc.contains(
+      "<li><span>more</span>\n<ol>\n<li><a href=\"chapter-1.xhtml\">guide</a>\n\
+       <ol>\n<li><a href=\"chapter-1.xhtml#steps\">Steps</a>"
+    ));
+    assert!(toc.contains(
+      "<li><a href=\"chapter-2.xhtml\">intro</a>\n<ol>\n\
+       <li><a href=\"chapter-2.xhtml#setup\">Setup</a>\n<ol>\n\
+       <li><a href=\"chapter-2.xhtml#install\">Install</a>\n</li>\n</ol>\n</li>\n\
+       <li><a href=\"chapter-2.xhtml#usage\">Usage</a>\n</li>\n</ol>\n</li>"
+    ));
+  }
+
+  #[test]
+  fn test_export_docx() {
+    use std::io::Read;
+
+    let (service, temp_dir) = setup_test_service();
+    let root = temp_dir.path().join("docs");
+    write(
+      &root,
+      "notes/plan.md",
+      "---\ntitle: The Plan\n---\n## Goals & Scope\n\nSome **bold**, *italic* and `code` with [a site](https://example.com?a=1&b=2), \
+       [[#Goals & Scope|back up]] and [[other]].\n\n- one\n  - nested\n- two\n\n3. three\n4. four\n\n\
+       | Name | Size |\n|------|-----:|\n| a | 1 |\n\n```rust\nfn main() {\n\n\tprintln!();\n}\n```\n\n\
+       ![wide](/_assets/wide.png) ![vector](/_assets/logo.svg)\n",
+    );
+    write(&root, "other.md", "Other\n");
+    // a 2000x1000 png header is enough to size it
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+    png.extend(2000u32.to_be_bytes());
+    png.extend(1000u32.to_be_bytes());
+    png.extend([8, 6, 0, 0, 0]);
+    write(&root, "_assets/logo.svg", "<svg/>");
+    fs::write(root.join("_assets/wide.png"), &png).unwrap();
+
+    let docx = service.export_docx("notes%2Fplan").unwrap();
+    assert_eq!(docx.file_name, "plan.docx");
+    assert_eq!(
+      docx.mime,
+      "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+    );
+
+    let mut archive = zip::ZipArchive::new(docx.file).unwrap();
+    let read = |archive: &mut zip::ZipArchive<_>, name: &str| {
+      let mut content = String::new();
+      archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+      content
+    };
+    let document = read(&mut archive, "word/document.xml");
+    let rels = read(&mut archive, "word/_rels/document.xml.rels");
+    let numbering = read(&mut archive, "word/numbering.xml");
+    assert_eq!(archive.by_name("word/media/image1.png").unwrap().size(), 29);
+
+    // front matter title, heading with its bookmark
+    assert!(!document.contains("title: The Plan"));
+    assert!(document.contains(
+      "<w:pStyle w:val=\"Title\"/></w:pPr><w:r><w:t xml:space=\"preserve\">The Plan</w:t>"
+    ));
+    assert!(document.contains(
+      "<w:pStyle w:val=\"Heading2\"/></w:pPr><w:bookmarkStart w:id=\"1\" w:name=\"_goals__scope\"/>"
+    ));
+    assert!(document.contains("Goals &amp; Scope"));
+
+    // emphasis, code and links
+    assert!(document.contains("<w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">bold</w:t>"));
+    assert!(document.contains("<w:rPr><w:i/></w:rPr><w:t xml:space=\"preserve\">italic</w:t>"));
+    assert!(
+      document
+        .contains("<w:rStyle w:val=\"CodeChar\"/></w:rPr><w:t xml:space=\"preserve\">code</w:t>")
+    );
+    assert!(document.contains(
+      "<w:hyperlink r:id=\"rId3\" w:history=\"1\"><w:r><w:rPr><w:rStyle w:val=\"Hyperlink\"/>"
+    ));
+    assert!(rels.contains("Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink\" Target=\"https://example.com?a=1&amp;b=2\" TargetMode=\"External\""));
+    assert!(document.contains("<w:hyperlink w:anchor=\"_goals__scope\" w:history=\"1\">"));
+    assert!(document.contains("back up"));
+    assert!(document.contains("> and other.</w:t>"));
+
+    // lists, the ordered one starting from 3
+    assert!(document.contains("<w:numPr><w:ilvl w:val=\"1\"/><w:numId w:val=\"1\"/></w:numPr></w:pPr><w:r><w:t xml:space=\"preserve\">nested"));
+    assert!(document.contains("<w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"2\"/></w:numPr></w:pPr><w:r><w:t xml:space=\"preserve\">three"));
+    assert!(numbering.contains("<w:num w:numId=\"2\"><w:abstractNumId w:val=\"1\"/><w:lvlOverride w:ilvl=\"0\"><w:startOverride w:val=\"3\"/>"));
+
+    // table with a bold header and aligned cells
+    assert!(document.contains("<w:tblHeader/></w:trPr><w:tc>"));
+    assert!(document.contains("<w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">Name</w:t>"));
+    assert!(
+      document.contains("<w:jc w:val=\"right\"/></w:pPr><w:r><w:t xml:space=\"preserve\">1</w:t>")
+    );
+
+    // a paragraph per line of code, blank lines and tabs kept
+    assert!(document.contains(
+      "<w:p><w:pPr><w:pStyle w:val=\"Code\"/></w:pPr><w:r><w:t xml:space=\"preserve\">fn main() {</w:t></w:r></w:p>\
+       <w:p><w:pPr><w:pStyle w:val=\"Code\"/></w:pPr></w:p>\
+       <w:p><w:pPr><w:pStyle w:val=\"Code\"/></w:pPr><w:r><w:tab/><w:t xml:space=\"preserve\">println!();</w:t>"
+    ));
+
+    // wide images are scaled to the page, svgs are left as their alt text
+    assert!(document.contains("<wp:extent cx=\"5943600\" cy=\"2971800\"/>"));
+    assert!(document.contains("<a:blip r:embed=\"rId4\"/>"));
+    assert!(rels.contains("Id=\"rId4\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" Target=\"media/image1.png\""));
+    assert!(document.contains(">vector</w:t>"));
+
+    assert!(service.export_docx("missing").is_err());
+  }
+}
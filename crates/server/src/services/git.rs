@@ -5,9 +5,12 @@ use std::{
 
 use git2::{Repository, Status, StatusOptions};
 
-use crate::services::{settings::Settings, settings::SettingsService};
+use crate::{
+  services::{settings::Settings, settings::SettingsService, trash::TrashService},
+  utils::workspace_path::WorkspacePath,
+};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StatusType {
   Added,
@@ -17,6 +20,30 @@ pub enum StatusType {
   Rename,
 }
 
+impl StatusType {
+  /// Summarizes the staged and unstaged changes of a path, `None` if it's unchanged.
+  pub fn from_status(status: Status) -> Option<Self> {
+    if status.contains(Status::WT_NEW) && !status.contains(Status::INDEX_NEW) {
+      Some(Self::Untracked)
+    } else if status.contains(Status::INDEX_NEW) {
+      Some(Self::Added)
+    } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+      Some(Self::Deleted)
+    } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+      Some(Self::Rename)
+    } else if status.intersects(
+      Status::INDEX_MODIFIED
+        | Status::WT_MODIFIED
+        | Status::INDEX_TYPECHANGE
+        | Status::WT_TYPECHANGE,
+    ) {
+      Some(Self::Modified)
+    } else {
+      None
+    }
+  }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Change {
@@ -48,13 +75,15 @@ pub struct RemoteInfo {
 pub struct GitService {
   repo: Arc<Mutex<Option<Repository>>>,
   settings_service: Arc<SettingsService>,
+  trash_service: Arc<TrashService>,
 }
 
 impl GitService {
-  pub fn new(settings_service: Arc<SettingsService>) -> Self {
+  pub fn new(settings_service: Arc<SettingsService>, trash_service: Arc<TrashService>) -> Self {
     let service = Self {
       repo: Arc::new(Mutex::new(None)),
       settings_service,
+      trash_service,
     };
 
     // Initialize with current settings
@@ -71,7 +100,7 @@ impl GitService {
     let repo_clone = self.repo.clone();
     let git_service_clone = self.clone();
     tokio::spawn(async move {
-      if let Some(_) = repo_clone.lock().unwrap().as_ref() {
+      if repo_clone.lock().unwrap().is_some() {
         tracing::info!("[GitService] git initially pulling...");
         if let Err(e) = git_service_clone.exec_pull() {
           tracing::info!("[GitService] failed to pull doc: {}", e);
@@ -176,7 +205,7 @@ impl GitService {
       staged,
       changes: !statuses.is_empty(),
       no_git: false,
-      remotes: Self::get_remote_info(&repo)?,
+      remotes: Self::get_remote_info(repo)?,
     })
   }
 
@@ -215,12 +244,12 @@ impl GitService {
       return Some(format!("http://{}", rest));
     }
     // SSH: git@host:path/repo → https://host/path/repo
-    if let Some(rest) = without_dot_git.strip_prefix("git@") {
-      if let Some(colon) = rest.find(':') {
-        let host = &rest[..colon];
-        let path = &rest[colon + 1..];
-        return Some(format!("https://{}/{}", host, path));
-      }
+    if let Some(rest) = without_dot_git.strip_prefix("git@")
+      && let Some(colon) = rest.find(':')
+    {
+      let host = &rest[..colon];
+      let path = &rest[colon + 1..];
+      return Some(format!("https://{}/{}", host, path));
     }
     None
   }
@@ -236,12 +265,12 @@ impl GitService {
 
     // Paths from frontend are relative to doc_root_path (which should be the git repo root)
     for path in change_paths {
-      let path_obj = std::path::Path::new(&path);
-      let full_path = settings.doc_root_path.join(path_obj);
+      let workspace_path = WorkspacePath::from_relative(&settings.doc_root_path, &path)?;
+      let path_obj = workspace_path.relative();
 
       // If file exists, add it to index (for new/modified files)
       // If file doesn't exist, remove it from index (for deleted files)
-      if full_path.exists() {
+      if workspace_path.to_path_buf().exists() {
         index.add_path(path_obj)?;
       } else {
         index.remove_path(path_obj)?;
@@ -402,6 +431,11 @@ impl GitService {
   pub fn restore(&self, staged: bool, changes: Vec<Change>) -> Result<(), anyhow::Error> {
     let settings = self.settings_service.get_settings();
 
+    // Paths are passed to git and the filesystem, so make sure none of them leave the workspace
+    for change in &changes {
+      WorkspacePath::from_relative(&settings.doc_root_path, &change.change_path)?;
+    }
+
     if staged {
       // Restore staged changes: git restore --staged <paths>
       let paths: Vec<&str> = changes.iter().map(|c| c.change_path.as_str()).collect();
@@ -415,15 +449,12 @@ impl GitService {
         .filter(|c| matches!(c.status, StatusType::Untracked))
         .collect();
 
-      // Delete untracked files
+      // Discard untracked files into the trash, they can't be recovered from git
       for change in &untracked {
-        let full_path = settings.doc_root_path.join(&change.change_path);
+        let full_path =
+          WorkspacePath::from_relative(&settings.doc_root_path, &change.change_path)?.to_path_buf();
         if full_path.exists() {
-          if full_path.is_file() {
-            std::fs::remove_file(&full_path)?;
-          } else {
-            std::fs::remove_dir_all(&full_path)?;
-          }
+          self.trash_service.move_to_trash(&full_path)?;
         }
       }
 
@@ -445,8 +476,34 @@ impl GitService {
   /// Reinitializes the git repository when settings change.
   /// This reopens the repository at the new doc_root_path if it exists.
   pub fn sync_git(&self, settings: &Settings) {
-    let doc_root_path = &settings.doc_root_path;
+    let repo = Self::open_repo(&settings.doc_root_path);
+    *self.repo.lock().unwrap() = repo;
+  }
 
+  /// Like [`Self::sync_git`] without waiting for the repository to open, so switching
+  /// workspaces stays fast. The repository of the previous workspace is dropped right
+  /// away, and the new one is kept only if the workspace is still the current one.
+  pub fn sync_git_in_background(&self, settings: &Settings) {
+    *self.repo.lock().unwrap() = None;
+    let git_service = self.clone();
+    let doc_root_path = settings.doc_root_path.clone();
+    tokio::task::spawn_blocking(move || {
+      let repo = Self::open_repo(&doc_root_path);
+      let mut repo_guard = git_service.repo.lock().unwrap();
+      if git_service
+        .settings_service
+        .settings
+        .lock()
+        .unwrap()
+        .doc_root_path
+        == doc_root_path
+      {
+        *repo_guard = repo;
+      }
+    });
+  }
+
+  fn open_repo(doc_root_path: &Path) -> Option<Repository> {
     // Log current user (for Windows service debugging)
     #[cfg(target_os = "windows")]
     {
@@ -477,23 +534,20 @@ impl GitService {
       doc_root_path
     );
 
-    let repo = if doc_root_path.exists() {
-      match Repository::open(doc_root_path) {
-        Ok(repo) => Some(repo),
-        Err(e) => {
-          tracing::info!(
-            "[GitService] Failed to open git repository at {:?}: {}",
-            doc_root_path,
-            e
-          );
-          None
-        }
+    if !doc_root_path.exists() {
+      return None;
+    }
+    match Repository::open(doc_root_path) {
+      Ok(repo) => Some(repo),
+      Err(e) => {
+        tracing::info!(
+          "[GitService] Failed to open git repository at {:?}: {}",
+          doc_root_path,
+          e
+        );
+        None
       }
-    } else {
-      None
-    };
-
-    *self.repo.lock().unwrap() = repo;
+    }
   }
 
   pub fn is_repo(&self) -> bool {
@@ -0,0 +1,253 @@
+use std::{
+  cmp::Reverse,
+  fs,
+  io::{Read, Write},
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+  services::{
+    doc::{content_version, normalize_path},
+    settings::SettingsService,
+  },
+  utils::{atomic_write::write_atomic, workspace_path::WorkspacePath},
+};
+
+/// Workspace-local version history. Hidden and git ignored like the trash.
+pub const HISTORY_DIR: &str = ".history";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Longest history folder name kept as is, deeper paths get a hashed name to stay below
+/// the 255 bytes file name limit of most filesystems.
+const MAX_DIR_NAME_LEN: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryVersion {
+  pub id: String,
+  /// Unix timestamp (milliseconds) of the save.
+  pub created_at: u64,
+  /// Content version of the snapshot, see [`content_version`].
+  pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryVersionContent {
+  #[serde(flatten)]
+  pub version: HistoryVersion,
+  pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListHistoryQuery {
+  #[serde(rename = "filePath")]
+  pub file_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetHistoryVersionQuery {
+  #[serde(rename = "filePath")]
+  pub file_path: String,
+  pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreHistoryRequest {
+  pub file_path: String,
+  pub id: String,
+}
+
+pub struct HistoryService {
+  settings_service: Arc<SettingsService>,
+}
+
+impl HistoryService {
+  pub fn new(settings_service: Arc<SettingsService>) -> Self {
+    Self { settings_service }
+  }
+
+  /// Stores `content` as the latest version of the doc at `doc_path`, unless it equals
+  /// the latest stored version, then applies the retention rules of the workspace.
+  ///
+  /// # Example
+  /// ```ignore
+  /// history_service.record(&doc_root.join("js").join("array.md"), "# Array")?;
+  /// let versions = history_service.list(&doc_root.join("js").join("array.md"))?;
+  /// ```
+  pub fn record(&self, doc_path: &Path, content: &str) -> Result<(), anyhow::Error> {
+    let doc_dir = self.doc_dir(doc_path)?;
+    let version = content_version(content.as_bytes());
+    let latest = self.read_versions(&doc_dir)?.into_iter().next();
+    if latest
+      .as_ref()
+      .is_some_and(|latest| latest.version == version)
+    {
+      return Ok(());
+    }
+
+    self.ensure_history_dir()?;
+    fs::create_dir_all(&doc_dir)?;
+    // strictly increasing, so saves within the same millisecond keep their order
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    let created_at = latest.map_or(now, |latest| now.max(latest.created_at + 1));
+    let id = format!("{}-{}", created_at, version);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content.as_bytes())?;
+    write_atomic(&doc_dir.join(format!("{}.gz", id)), encoder.finish()?)?;
+
+    self.prune(&doc_dir)
+  }
+
+  /// Moves the versions of the doc at `from` along with it once it was renamed or moved
+  /// to `to`. For a folder, the versions of every doc inside of it are moved.
+  ///
+  /// # Example
+  /// ```ignore
+  /// fs::rename(&doc_root.join("js"), &doc_root.join("ts"))?;
+  /// history_service.move_history(&doc_root.join("js"), &doc_root.join("ts"))?;
+  /// ```
+  pub fn move_history(&self, from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+    if fs::symlink_metadata(to)?.is_dir() {
+      for entry in fs::read_dir(to)? {
+        let name = entry?.file_name();
+        self.move_history(&from.join(&name), &to.join(&name))?;
+      }
+      return Ok(());
+    }
+
+    let from_dir = self.doc_dir(from)?;
+    if !from_dir.exists() {
+      return Ok(());
+    }
+    let to_dir = self.doc_dir(to)?;
+    // versions of a doc that used to live at the new path don't belong to this one
+    if to_dir.exists() {
+      fs::remove_dir_all(&to_dir)?;
+    }
+    fs::rename(&from_dir, &to_dir)?;
+    Ok(())
+  }
+
+  /// Lists the stored versions of a doc, most recent first.
+  pub fn list(&self, doc_path: &Path) -> Result<Vec<HistoryVersion>, anyhow::Error> {
+    self.read_versions(&self.doc_dir(doc_path)?)
+  }
+
+  /// Reads a stored version of a doc.
+  pub fn get(&self, doc_path: &Path, id: &str) -> Result<HistoryVersionContent, anyhow::Error> {
+    let doc_dir = self.doc_dir(doc_path)?;
+    let Some(version) = self
+      .read_versions(&doc_dir)?
+      .into_iter()
+      .find(|version| version.id == id)
+    else {
+      return Err(anyhow::anyhow!("Version {} does not exist.", id));
+    };
+
+    let mut content = String::new();
+    GzDecoder::new(fs::File::open(doc_dir.join(format!("{}.gz", id)))?)
+      .read_to_string(&mut content)?;
+    Ok(HistoryVersionContent { version, content })
+  }
+
+  /// Drops versions beyond `history_max_versions` and older than
+  /// `history_retention_days`, always keeping the latest one.
+  fn prune(&self, doc_dir: &Path) -> Result<(), anyhow::Error> {
+    let (max_versions, retention_days) = {
+      let settings = self.settings_service.settings.lock().unwrap();
+      (
+        settings.history_max_versions as usize,
+        settings.history_retention_days as u64,
+      )
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    let cutoff = now.saturating_sub(retention_days * SECONDS_PER_DAY * 1000);
+
+    for (index, version) in self.read_versions(doc_dir)?.iter().enumerate().skip(1) {
+      let too_many = max_versions > 0 && index >= max_versions;
+      let expired = retention_days > 0 && version.created_at < cutoff;
+      if too_many || expired {
+        fs::remove_file(doc_dir.join(format!("{}.gz", version.id)))?;
+      }
+    }
+    Ok(())
+  }
+
+  fn read_versions(&self, doc_dir: &Path) -> Result<Vec<HistoryVersion>, anyhow::Error> {
+    if !doc_dir.exists() {
+      return Ok(Vec::new());
+    }
+
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(doc_dir)? {
+      let file_name = entry?.file_name().to_string_lossy().to_string();
+      let Some(id) = file_name.strip_suffix(".gz") else {
+        continue;
+      };
+      let Some((created_at, version)) = id.split_once('-') else {
+        continue;
+      };
+      let Ok(created_at) = created_at.parse() else {
+        continue;
+      };
+      versions.push(HistoryVersion {
+        id: id.to_string(),
+        created_at,
+        version: version.to_string(),
+      });
+    }
+    versions.sort_by_key(|version| Reverse(version.created_at));
+    Ok(versions)
+  }
+
+  /// Versions of a doc live in a folder named after its encoded workspace path.
+  fn doc_dir(&self, doc_path: &Path) -> Result<PathBuf, anyhow::Error> {
+    let doc_root = self.get_doc_root_path();
+    let segments = WorkspacePath::from_absolute(&doc_root, doc_path)?.segments();
+    if segments.is_empty() {
+      return Err(anyhow::anyhow!("The workspace root has no history."));
+    }
+    Ok(doc_root.join(HISTORY_DIR).join(doc_dir_name(&segments)))
+  }
+
+  fn ensure_history_dir(&self) -> Result<(), anyhow::Error> {
+    let history_dir = self.get_doc_root_path().join(HISTORY_DIR);
+    fs::create_dir_all(&history_dir)?;
+
+    let gitignore = history_dir.join(".gitignore");
+    if !gitignore.exists() {
+      write_atomic(&gitignore, "# doc versions, managed by the editor\n*\n")?;
+    }
+    Ok(())
+  }
+
+  fn get_doc_root_path(&self) -> PathBuf {
+    let settings = self.settings_service.settings.lock().unwrap();
+    settings.doc_root_path.clone()
+  }
+}
+
+/// The encoded path of the doc, or its start followed by the hash of the whole path when
+/// it's too long for a file name.
+fn doc_dir_name(segments: &[String]) -> String {
+  let name = normalize_path(segments);
+  if name.len() <= MAX_DIR_NAME_LEN {
+    return name;
+  }
+  // the encoded path is ascii, any byte is a char boundary
+  format!(
+    "{}-{:x}",
+    &name[..MAX_DIR_NAME_LEN],
+    Sha256::digest(name.as_bytes())
+  )
+}
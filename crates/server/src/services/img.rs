@@ -8,9 +8,12 @@ use std::{
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 
-use crate::services::{search::SearchService, settings::SettingsService};
+use crate::{
+  services::{search::SearchService, settings::SettingsService},
+  utils::{atomic_write::write_atomic, workspace_path::WorkspacePath},
+};
 
-const ASSETS_DIR: &str = "_assets";
+pub const ASSETS_DIR: &str = "_assets";
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -44,17 +47,13 @@ impl ImgService {
   /// Returns (bytes, mime_type).
   pub fn get_image(&self, img_path: &str) -> Result<(Vec<u8>, String), anyhow::Error> {
     let settings = self.settings_service.get_settings();
-    let full_path = settings.doc_root_path.join(img_path);
+    // Prevent directory traversal and symlink escapes
+    let full_path = WorkspacePath::from_relative(&settings.doc_root_path, img_path)?.to_path_buf();
 
-    if !full_path.exists() {
+    if !full_path.is_file() {
       return Err(anyhow::anyhow!("Image not found: {}", img_path));
     }
 
-    // Prevent directory traversal
-    if !full_path.starts_with(&settings.doc_root_path) {
-      return Err(anyhow::anyhow!("Invalid image path"));
-    }
-
     let bytes = fs::read(&full_path)?;
     let mime = Self::infer_mime(&full_path);
 
@@ -99,16 +98,7 @@ impl ImgService {
         continue;
       }
 
-      let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-      if !matches!(
-        ext.as_str(),
-        "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "ico" | "bmp" | "avif"
-      ) {
+      if !Self::is_image(&path) {
         continue;
       }
 
@@ -127,16 +117,28 @@ impl ImgService {
       });
     }
 
-    images.sort_by(|a, b| b.created_time.cmp(&a.created_time));
+    images.sort_by_key(|img| std::cmp::Reverse(img.created_time));
     Ok(images)
   }
 
+  /// Whether the file has an image extension.
+  pub fn is_image(path: &Path) -> bool {
+    let ext = path
+      .extension()
+      .and_then(|e| e.to_str())
+      .unwrap_or("")
+      .to_lowercase();
+
+    matches!(
+      ext.as_str(),
+      "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "ico" | "bmp" | "avif"
+    )
+  }
+
   /// Deletes an image from the `_assets` directory by file name.
   /// Only allows deleting files directly inside `_assets` to prevent traversal.
   pub fn delete_image(&self, file_name: &str) -> Result<(), anyhow::Error> {
-    let settings = self.settings_service.get_settings();
-    let assets_dir = settings.doc_root_path.join(ASSETS_DIR);
-    let file_path = assets_dir.join(file_name);
+    let file_path = self.asset_path(file_name)?;
 
     if !file_path.exists() {
       return Err(anyhow::anyhow!("Image not found: {}", file_name));
@@ -147,6 +149,16 @@ impl ImgService {
     Ok(())
   }
 
+  /// Resolves a file name directly inside `_assets`, rejecting traversal and nested paths.
+  fn asset_path(&self, file_name: &str) -> Result<PathBuf, anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let asset_path = WorkspacePath::resolve(&settings.doc_root_path, [ASSETS_DIR, file_name])?;
+    if asset_path.segments().len() != 2 {
+      return Err(anyhow::anyhow!("Invalid image name: {}", file_name));
+    }
+    Ok(asset_path.to_path_buf())
+  }
+
   fn content_hash(data: &[u8]) -> String {
     let digest = Sha256::digest(data);
     digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
@@ -154,9 +166,7 @@ impl ImgService {
 
   /// Get the docs that are using the image link
   pub fn get_image_ref_docs(&self, file_name: &str) -> Result<Vec<ImgRefDoc>, anyhow::Error> {
-    let settings = self.settings_service.get_settings();
-    let assets_dir = settings.doc_root_path.join(ASSETS_DIR);
-    let file_path = assets_dir.join(file_name);
+    let file_path = self.asset_path(file_name)?;
 
     if !file_path.exists() {
       return Err(anyhow::anyhow!("Image not found: {}", file_name));
@@ -197,7 +207,7 @@ impl ImgService {
     let base_path = assets_dir.join(&base_name);
 
     if !base_path.exists() {
-      fs::write(&base_path, data)?;
+      write_atomic(&base_path, data)?;
       tracing::info!("[ImgService] Saved image: {}", base_path.display());
       return Ok(base_name);
     }
@@ -212,7 +222,7 @@ impl ImgService {
       let candidate_path = assets_dir.join(&candidate_name);
 
       if !candidate_path.exists() {
-        fs::write(&candidate_path, data)?;
+        write_atomic(&candidate_path, data)?;
         tracing::info!(
           "[ImgService] Saved image (collision): {}",
           candidate_path.display()
@@ -0,0 +1,279 @@
+use std::{collections::HashMap, fmt::Write, fs, path::Path, sync::Arc};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  services::{
+    doc::{DocService, helpers::doc_name, normalize_path},
+    settings::SettingsService,
+  },
+  utils::workspace_path::WorkspacePath,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenJournalRequest {
+  /// Day of the note, today when omitted.
+  #[serde(default)]
+  pub date: Option<NaiveDate>,
+  /// Template for a new note, overrides `journalTemplate` of the workspace settings.
+  #[serde(default)]
+  pub template: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListJournalQuery {
+  /// First day to include.
+  pub from: Option<NaiveDate>,
+  /// Last day to include.
+  pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+  pub date: NaiveDate,
+  /// Normalized path of the note.
+  pub file_path: String,
+  pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenJournalResponse {
+  #[serde(flatten)]
+  pub entry: JournalEntry,
+  /// The note didn't exist and was just created.
+  pub created: bool,
+}
+
+pub struct JournalService {
+  settings_service: Arc<SettingsService>,
+  doc_service: Arc<DocService>,
+}
+
+impl JournalService {
+  pub fn new(settings_service: Arc<SettingsService>, doc_service: Arc<DocService>) -> Self {
+    Self {
+      settings_service,
+      doc_service,
+    }
+  }
+
+  /// Returns the note of `date` (today when `None`), creating it from the journal
+  /// template if it doesn't exist yet. The `{{date}}` placeholder of the template is the
+  /// date of the note.
+  ///
+  /// # Example
+  /// ```ignore
+  /// // journal/2026/10/2026-10-18.md with the default settings
+  /// let note = journal_service.open(NaiveDate::from_ymd_opt(2026, 10, 18), None)?;
+  /// ```
+  pub fn open(
+    &self,
+    date: Option<NaiveDate>,
+    template: Option<&str>,
+  ) -> Result<OpenJournalResponse, anyhow::Error> {
+    let date = date.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let entry = self.entry(date)?;
+    if self.doc_service.doc_exists(&entry.file_path, true)? {
+      return Ok(OpenJournalResponse {
+        entry,
+        created: false,
+      });
+    }
+
+    let settings = self.settings_service.get_settings();
+    let template = template
+      .map(String::from)
+      .unwrap_or(settings.journal_template)
+      .trim()
+      .to_string();
+    if template.is_empty() {
+      self.doc_service.create_doc(&entry.file_path, true)?;
+    } else {
+      let variables = HashMap::from([("date".to_string(), date.format("%Y-%m-%d").to_string())]);
+      self
+        .doc_service
+        .create_doc_from_template(&entry.file_path, &template, &variables)?;
+    }
+
+    tracing::info!("[JournalService] created the note of {}", date);
+    Ok(OpenJournalResponse {
+      entry,
+      created: true,
+    })
+  }
+
+  /// Lists the notes of the journal folder whose path matches the filename pattern,
+  /// most recent first, optionally limited to the days between `from` and `to`.
+  pub fn list(
+    &self,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+  ) -> Result<Vec<JournalEntry>, anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let journal_dir = journal_segments(&settings.journal_dir);
+    let journal_path =
+      WorkspacePath::resolve(&settings.doc_root_path, journal_dir.clone())?.to_path_buf();
+
+    let mut entries = Vec::new();
+    if journal_path.is_dir() {
+      let mut names = Vec::new();
+      collect_doc_names(
+        &journal_path,
+        &mut Vec::new(),
+        &settings.doc_extensions,
+        &mut names,
+      )?;
+
+      for relative in names {
+        let Ok(date) =
+          NaiveDate::parse_from_str(&relative.join("/"), &settings.journal_file_pattern)
+        else {
+          continue;
+        };
+        if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+          continue;
+        }
+        entries.push(JournalEntry {
+          date,
+          name: relative.last().cloned().unwrap_or_default(),
+          file_path: normalize_path(&[&journal_dir[..], &relative[..]].concat()),
+        });
+      }
+    }
+
+    entries.sort_by(|a, b| b.date.cmp(&a.date).then(a.file_path.cmp(&b.file_path)));
+    Ok(entries)
+  }
+
+  /// The note path of a day, following the journal folder and filename pattern.
+  fn entry(&self, date: NaiveDate) -> Result<JournalEntry, anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let mut file_name = String::new();
+    write!(file_name, "{}", date.format(&settings.journal_file_pattern)).map_err(|_| {
+      anyhow::anyhow!(
+        "Invalid journal file pattern: {}",
+        settings.journal_file_pattern
+      )
+    })?;
+
+    let mut segments = journal_segments(&settings.journal_dir);
+    segments.extend(journal_segments(&file_name));
+    let Some(name) = segments.last().cloned() else {
+      return Err(anyhow::anyhow!("The journal file pattern is empty."));
+    };
+    Ok(JournalEntry {
+      date,
+      file_path: normalize_path(&segments),
+      name,
+    })
+  }
+}
+
+fn journal_segments(path: &str) -> Vec<String> {
+  path
+    .split(['/', '\\'])
+    .filter(|segment| !segment.is_empty())
+    .map(String::from)
+    .collect()
+}
+
+/// Collects the doc paths (doc names, relative to `dir`) of a folder recursively.
+fn collect_doc_names(
+  dir: &Path,
+  prefix: &mut Vec<String>,
+  doc_extensions: &[String],
+  names: &mut Vec<Vec<String>>,
+) -> Result<(), anyhow::Error> {
+  for entry in fs::read_dir(dir)? {
+    let entry = entry?;
+    let file_name = entry.file_name().to_string_lossy().to_string();
+    if file_name.starts_with('.') {
+      continue;
+    }
+
+    let path = entry.path();
+    if path.is_dir() {
+      prefix.push(file_name);
+      collect_doc_names(&path, prefix, doc_extensions, names)?;
+      prefix.pop();
+    } else if let Some(name) = doc_name(&file_name, doc_extensions) {
+      names.push([&prefix[..], &[name]].concat());
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn setup_test_service() -> (JournalService, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let settings_service = Arc::new(SettingsService::for_test(
+      temp_dir.path().to_path_buf(),
+      &[],
+      temp_dir.path().join("editor-settings.json"),
+    ));
+    let doc_service = Arc::new(DocService::for_test(settings_service.clone()));
+    (JournalService::new(settings_service, doc_service), temp_dir)
+  }
+
+  fn date(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+  }
+
+  #[test]
+  fn test_open_creates_note_once() {
+    let (service, temp_dir) = setup_test_service();
+    let templates_dir = temp_dir.path().join("_templates");
+    fs::create_dir_all(&templates_dir).unwrap();
+    fs::write(templates_dir.join("daily.md"), "# {{date}}\n").unwrap();
+
+    let opened = service
+      .open(Some(date("2026-10-18")), Some("daily"))
+      .unwrap();
+    assert!(opened.created);
+    assert_eq!(opened.entry.file_path, "journal%2F2026%2F10%2F2026-10-18");
+    let note = temp_dir.path().join("journal/2026/10/2026-10-18.md");
+    assert_eq!(fs::read_to_string(&note).unwrap(), "# 2026-10-18\n");
+
+    fs::write(&note, "edited").unwrap();
+    let opened = service
+      .open(Some(date("2026-10-18")), Some("daily"))
+      .unwrap();
+    assert!(!opened.created);
+    assert_eq!(fs::read_to_string(&note).unwrap(), "edited");
+  }
+
+  #[test]
+  fn test_list_by_date_range() {
+    let (service, temp_dir) = setup_test_service();
+    for day in ["2026-09-30", "2026-10-01", "2026-10-18"] {
+      service.open(Some(date(day)), None).unwrap();
+    }
+    // files not following the pattern are not journal entries
+    fs::write(temp_dir.path().join("journal/2026/10/notes.md"), "").unwrap();
+
+    let dates: Vec<NaiveDate> = service
+      .list(None, None)
+      .unwrap()
+      .into_iter()
+      .map(|entry| entry.date)
+      .collect();
+    assert_eq!(
+      dates,
+      vec![date("2026-10-18"), date("2026-10-01"), date("2026-09-30")]
+    );
+
+    let entries = service
+      .list(Some(date("2026-10-01")), Some(date("2026-10-17")))
+      .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name, "2026-10-01");
+    assert_eq!(entries[0].file_path, "journal%2F2026%2F10%2F2026-10-01");
+  }
+}
@@ -1,5 +1,12 @@
+pub mod collab;
 pub mod doc;
+pub mod export;
 pub mod git;
+pub mod history;
 pub mod img;
+pub mod journal;
 pub mod search;
 pub mod settings;
+pub mod stats;
+pub mod trash;
+pub mod watcher;
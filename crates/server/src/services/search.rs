@@ -8,7 +8,10 @@ use grep_searcher::{Searcher, sinks::UTF8};
 use ignore::WalkBuilder;
 use serde::Serialize;
 
-use crate::services::settings::SettingsService;
+use crate::{
+  services::{doc::helpers::doc_name, settings::SettingsService},
+  utils::workspace_path::WorkspacePath,
+};
 
 const INTERNAL_IGNORE_DIRS: &[&str] = &["_assets"];
 
@@ -49,6 +52,7 @@ impl SearchService {
   pub fn search_file_names(&self, query: &str) -> Result<Vec<FileNameMatch>, anyhow::Error> {
     let doc_root = self.get_doc_root_path();
     let ignore_dirs = self.get_ignore_dirs();
+    let doc_extensions = self.get_doc_extensions();
 
     if !doc_root.exists() {
       return Err(anyhow::anyhow!(
@@ -66,19 +70,17 @@ impl SearchService {
       if !path.is_file() {
         continue;
       }
-      if !Self::is_markdown(path) {
+      let Some(name) = Self::doc_file_name(path, &doc_extensions) else {
+        continue;
+      };
+      if !Self::is_within_root(&entry, &doc_root) {
         continue;
       }
 
-      let file_stem = path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or_default();
-
-      if file_stem.to_lowercase().contains(&query_lower) {
-        let doc_path = Self::fs_path_to_doc_path(path, &doc_root);
+      if name.to_lowercase().contains(&query_lower) {
+        let doc_path = Self::fs_path_to_doc_path(path, &doc_root, &doc_extensions);
         results.push(FileNameMatch {
-          name: file_stem.to_string(),
+          name,
           path: doc_path,
         });
       }
@@ -107,6 +109,7 @@ impl SearchService {
   ) -> Result<Vec<FileContentMatches>, anyhow::Error> {
     let doc_root = self.get_doc_root_path();
     let ignore_dirs = self.get_ignore_dirs();
+    let doc_extensions = self.get_doc_extensions();
 
     if !doc_root.exists() {
       return Err(anyhow::anyhow!(
@@ -125,7 +128,10 @@ impl SearchService {
       let entry = entry?;
       let path = entry.path().to_path_buf();
 
-      if !path.is_file() || !Self::is_markdown(&path) {
+      if !path.is_file() || Self::doc_file_name(&path, &doc_extensions).is_none() {
+        continue;
+      }
+      if !Self::is_within_root(&entry, &doc_root) {
         continue;
       }
 
@@ -167,7 +173,7 @@ impl SearchService {
         });
 
       if !line_matches.is_empty() {
-        let doc_path = Self::fs_path_to_doc_path(&path, &doc_root);
+        let doc_path = Self::fs_path_to_doc_path(&path, &doc_root, &doc_extensions);
         let name = doc_path.last().cloned().unwrap_or_default();
         results.push(FileContentMatches {
           name,
@@ -177,12 +183,14 @@ impl SearchService {
       }
     }
 
-    results.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    results.sort_by_key(|a| a.name.to_lowercase());
 
     Ok(results)
   }
 
-  fn build_walker<'a>(&self, doc_root: &Path, ignore_dirs: &'a [String]) -> ignore::Walk {
+  /// Walks the docs of the workspace, skipping hidden, git ignored, internal and
+  /// ignored folders and the templates folder.
+  pub(crate) fn build_walker(&self, doc_root: &Path, ignore_dirs: &[String]) -> ignore::Walk {
     let mut builder = WalkBuilder::new(doc_root);
     builder.hidden(true).git_ignore(true);
 
@@ -193,6 +201,13 @@ impl SearchService {
     for dir in &all_ignores {
       let _ = overrides.add(&format!("!{}/**", dir));
     }
+    // templates are not docs, only the folder at the workspace root is skipped
+    let templates_dir = self.settings_service.get_settings().templates_dir;
+    let templates_dir = templates_dir.replace('\\', "/");
+    let templates_dir = templates_dir.trim_matches('/');
+    if !templates_dir.is_empty() {
+      let _ = overrides.add(&format!("!/{}", templates_dir));
+    }
     if let Ok(overrides) = overrides.build() {
       builder.overrides(overrides);
     }
@@ -200,23 +215,40 @@ impl SearchService {
     builder.build()
   }
 
-  fn is_markdown(path: &Path) -> bool {
-    path
-      .extension()
-      .and_then(|ext| ext.to_str())
-      .map_or(false, |ext| ext == "md")
+  /// The walker does not descend into symlinked dirs, but symlinked files are still yielded,
+  /// so make sure they do not point outside the workspace.
+  pub(crate) fn is_within_root(entry: &ignore::DirEntry, doc_root: &Path) -> bool {
+    if !entry.path_is_symlink() {
+      return true;
+    }
+    match WorkspacePath::from_absolute(doc_root, entry.path()) {
+      Ok(_) => true,
+      Err(e) => {
+        tracing::warn!("Skip searching {}: {}", entry.path().display(), e);
+        false
+      }
+    }
+  }
+
+  /// Name of the doc in doc paths, `None` if the file doesn't have a doc extension.
+  pub(crate) fn doc_file_name(path: &Path, doc_extensions: &[String]) -> Option<String> {
+    doc_name(path.file_name()?.to_str()?, doc_extensions)
   }
 
-  fn fs_path_to_doc_path(abs_path: &Path, doc_root: &Path) -> Vec<String> {
+  pub(crate) fn fs_path_to_doc_path(
+    abs_path: &Path,
+    doc_root: &Path,
+    doc_extensions: &[String],
+  ) -> Vec<String> {
     let rel = abs_path.strip_prefix(doc_root).unwrap_or(abs_path);
     let mut parts: Vec<String> = rel
       .components()
       .map(|c| c.as_os_str().to_string_lossy().to_string())
       .collect();
-    if let Some(last) = parts.last_mut() {
-      if let Some(stripped) = last.strip_suffix(".md") {
-        *last = stripped.to_string();
-      }
+    if let Some(last) = parts.last_mut()
+      && let Some(name) = doc_name(last, doc_extensions)
+    {
+      *last = name;
     }
     parts
   }
@@ -230,4 +262,9 @@ impl SearchService {
     let settings = self.settings_service.settings.lock().unwrap();
     settings.ignore_dirs.clone()
   }
+
+  fn get_doc_extensions(&self) -> Vec<String> {
+    let settings = self.settings_service.settings.lock().unwrap();
+    settings.doc_extensions.clone()
+  }
 }
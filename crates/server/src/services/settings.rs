@@ -1,12 +1,15 @@
 use std::{
   fs,
-  path::PathBuf,
+  path::{Path, PathBuf},
   sync::{Arc, Mutex},
+  time::{SystemTime, UNIX_EPOCH},
 };
 
 use serde::{Deserialize, Serialize};
 use struct_patch::Patch;
 
+use crate::utils::atomic_write::write_atomic;
+
 #[derive(Patch, Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 #[patch(attribute(derive(Deserialize, Debug)))]
@@ -16,18 +19,127 @@ use struct_patch::Patch;
 pub struct Settings {
   pub doc_root_path: PathBuf,
   pub ignore_dirs: Vec<String>,
+  /// Days deleted docs are kept in the workspace trash, `0` keeps them until purged.
+  pub trash_retention_days: u32,
+  /// File extensions (without the dot) of the docs, the first one is used for new docs.
+  pub doc_extensions: Vec<String>,
+  /// Workspace relative folder holding the doc templates.
+  pub templates_dir: String,
+  /// Workspace relative folder of the daily notes.
+  pub journal_dir: String,
+  /// `strftime` pattern of a daily note path inside `journal_dir`, without extension.
+  pub journal_file_pattern: String,
+  /// Template of new daily notes, empty for blank notes.
+  pub journal_template: String,
+  /// Versions kept in the local history of each doc, `0` keeps all of them.
+  pub history_max_versions: u32,
+  /// Days versions are kept in the local history, `0` keeps them forever.
+  pub history_retention_days: u32,
+  /// Order of the items of a folder, folders always come before docs.
+  pub sort_mode: DocSortMode,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DocSortMode {
+  /// Natural order of the names, `2-setup` before `10-intro`.
+  #[default]
+  Name,
+  /// Most recently modified first.
+  Modified,
+  /// Most recently created first.
+  Created,
+  /// The order saved for the folder, unordered items follow by name.
+  Manual,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct EditorSettings {
   pub doc_root_path: PathBuf,
+  /// Registered workspaces, missing in editor settings files written by older versions.
+  #[serde(default)]
+  pub workspaces: Vec<Workspace>,
+}
+
+/// A workspace of the registry, to switch to it without browsing for its folder.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+  pub name: String,
+  pub path: PathBuf,
+  /// Milliseconds since the Unix epoch, `None` if it was never opened.
+  pub last_opened: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddWorkspaceRequest {
+  /// Folder of the workspace, absolute or relative to the home folder.
+  pub path: String,
+  /// Defaults to the folder name.
+  #[serde(default)]
+  pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacePathRequest {
+  /// Folder of a registered workspace, absolute or relative to the home folder.
+  pub path: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceSettings {
   pub ignore_dirs: Vec<String>,
+  // missing in workspace settings files written by older versions
+  #[serde(default = "default_trash_retention_days")]
+  pub trash_retention_days: u32,
+  #[serde(default = "default_doc_extensions")]
+  pub doc_extensions: Vec<String>,
+  #[serde(default = "default_templates_dir")]
+  pub templates_dir: String,
+  #[serde(default = "default_journal_dir")]
+  pub journal_dir: String,
+  #[serde(default = "default_journal_file_pattern")]
+  pub journal_file_pattern: String,
+  #[serde(default)]
+  pub journal_template: String,
+  #[serde(default = "default_history_max_versions")]
+  pub history_max_versions: u32,
+  #[serde(default = "default_history_retention_days")]
+  pub history_retention_days: u32,
+  #[serde(default)]
+  pub sort_mode: DocSortMode,
+}
+
+fn default_trash_retention_days() -> u32 {
+  30
+}
+
+fn default_doc_extensions() -> Vec<String> {
+  vec![String::from("md")]
+}
+
+fn default_templates_dir() -> String {
+  String::from("_templates")
+}
+
+fn default_journal_dir() -> String {
+  String::from("journal")
+}
+
+fn default_journal_file_pattern() -> String {
+  String::from("%Y/%m/%Y-%m-%d")
+}
+
+fn default_history_max_versions() -> u32 {
+  50
+}
+
+fn default_history_retention_days() -> u32 {
+  30
 }
 
 impl Default for WorkspaceSettings {
@@ -38,6 +150,15 @@ impl Default for WorkspaceSettings {
         String::from("node_modules"),
         String::from("dist"),
       ],
+      trash_retention_days: default_trash_retention_days(),
+      doc_extensions: default_doc_extensions(),
+      templates_dir: default_templates_dir(),
+      journal_dir: default_journal_dir(),
+      journal_file_pattern: default_journal_file_pattern(),
+      journal_template: String::new(),
+      history_max_versions: default_history_max_versions(),
+      history_retention_days: default_history_retention_days(),
+      sort_mode: DocSortMode::default(),
     }
   }
 }
@@ -51,16 +172,11 @@ impl Settings {
       let workspace_settings =
         Self::load_workspace_settings_from_file(&editor_settings.doc_root_path);
 
-      Settings {
-        doc_root_path: editor_settings.doc_root_path,
-        ignore_dirs: workspace_settings.ignore_dirs,
-      }
+      Self::from_workspace_settings(editor_settings.doc_root_path, workspace_settings)
     } else {
       let default_workspace_settings = WorkspaceSettings::default();
-      let default_settings = Settings {
-        doc_root_path: PathBuf::from(""),
-        ignore_dirs: default_workspace_settings.ignore_dirs.clone(),
-      };
+      let default_settings =
+        Self::from_workspace_settings(PathBuf::from(""), default_workspace_settings.clone());
 
       // Ensure parent directory exists
       if let Some(parent) = editor_settings_file.parent() {
@@ -69,21 +185,42 @@ impl Settings {
         });
       }
 
-      fs::write(
+      write_atomic(
         editor_settings_file,
         serde_json::to_string(&default_settings).unwrap(),
       )
       .unwrap();
 
       if default_settings.doc_root_path.exists() {
-        Self::set_workspace_settings(&default_settings.doc_root_path, &default_workspace_settings);
+        Self::set_workspace_settings(&default_settings.doc_root_path, &default_workspace_settings)
+          .unwrap_or_else(|e| tracing::error!("Failed to write workspace settings: {}", e));
       }
 
       default_settings
     }
   }
 
-  pub fn load_workspace_settings_from_file(doc_root_path: &PathBuf) -> WorkspaceSettings {
+  /// Settings of the workspace at `doc_root_path`.
+  pub fn from_workspace_settings(
+    doc_root_path: PathBuf,
+    workspace_settings: WorkspaceSettings,
+  ) -> Self {
+    Settings {
+      doc_root_path,
+      ignore_dirs: workspace_settings.ignore_dirs,
+      trash_retention_days: workspace_settings.trash_retention_days,
+      doc_extensions: workspace_settings.doc_extensions,
+      templates_dir: workspace_settings.templates_dir,
+      journal_dir: workspace_settings.journal_dir,
+      journal_file_pattern: workspace_settings.journal_file_pattern,
+      journal_template: workspace_settings.journal_template,
+      history_max_versions: workspace_settings.history_max_versions,
+      history_retention_days: workspace_settings.history_retention_days,
+      sort_mode: workspace_settings.sort_mode,
+    }
+  }
+
+  pub fn load_workspace_settings_from_file(doc_root_path: &Path) -> WorkspaceSettings {
     let workspace_settings_file = doc_root_path.join(".workspace-settings.json");
     if workspace_settings_file.exists() {
       let file_content = fs::read_to_string(workspace_settings_file).unwrap();
@@ -98,20 +235,51 @@ impl Settings {
       let default_workspace_settings = WorkspaceSettings::default();
 
       if doc_root_path.exists() {
-        Self::set_workspace_settings(doc_root_path, &default_workspace_settings);
+        Self::set_workspace_settings(doc_root_path, &default_workspace_settings)
+          .unwrap_or_else(|e| tracing::error!("Failed to write workspace settings: {}", e));
       }
 
       default_workspace_settings
     }
   }
 
-  pub fn set_workspace_settings(doc_root_path: &PathBuf, workspace_settings: &WorkspaceSettings) {
+  /// Reads the settings of the editor's current workspace like [`Self::load_from_file`],
+  /// but without writing the settings files that are missing.
+  pub fn read_from_file(editor_settings_file: &Path) -> Result<Self, anyhow::Error> {
+    let doc_root_path = if editor_settings_file.exists() {
+      let file_content = fs::read_to_string(editor_settings_file)?;
+      serde_json::from_str::<EditorSettings>(&file_content)?.doc_root_path
+    } else {
+      PathBuf::from("")
+    };
+    let workspace_settings = Self::read_workspace_settings(&doc_root_path)?;
+    Ok(Self::from_workspace_settings(
+      doc_root_path,
+      workspace_settings,
+    ))
+  }
+
+  /// Reads the settings of the workspace at `doc_root_path`, the defaults when it has
+  /// none. Unlike [`Self::load_workspace_settings_from_file`] nothing is written.
+  pub fn read_workspace_settings(doc_root_path: &Path) -> Result<WorkspaceSettings, anyhow::Error> {
+    let workspace_settings_file = doc_root_path.join(".workspace-settings.json");
+    if !workspace_settings_file.exists() {
+      return Ok(WorkspaceSettings::default());
+    }
+    let file_content = fs::read_to_string(workspace_settings_file)?;
+    Ok(serde_json::from_str(&file_content)?)
+  }
+
+  pub fn set_workspace_settings(
+    doc_root_path: &Path,
+    workspace_settings: &WorkspaceSettings,
+  ) -> Result<(), anyhow::Error> {
     let workspace_settings_file = doc_root_path.join(".workspace-settings.json");
-    fs::write(
-      workspace_settings_file,
-      serde_json::to_string_pretty(workspace_settings).unwrap(),
-    )
-    .unwrap();
+    write_atomic(
+      &workspace_settings_file,
+      serde_json::to_string_pretty(workspace_settings)?,
+    )?;
+    Ok(())
   }
 }
 
@@ -119,15 +287,23 @@ impl Settings {
 pub struct SettingsService {
   pub settings: Arc<Mutex<Settings>>,
   pub editor_settings_file: PathBuf,
+  /// Held while reading, modifying and writing the editor settings file.
+  editor_settings_lock: Arc<Mutex<()>>,
 }
 
 impl SettingsService {
   pub fn new(editor_settings_file: PathBuf) -> Self {
     tracing::info!("editor_settings_file: {:?}", editor_settings_file);
     let settings = Settings::load_from_file(&editor_settings_file);
+    Self::from_settings(settings, editor_settings_file)
+  }
+
+  /// A service for already loaded `settings`, e.g. read without writing any file.
+  pub fn from_settings(settings: Settings, editor_settings_file: PathBuf) -> Self {
     Self {
       settings: Arc::new(Mutex::new(settings)),
       editor_settings_file,
+      editor_settings_lock: Arc::new(Mutex::new(())),
     }
   }
 
@@ -136,18 +312,47 @@ impl SettingsService {
     self.settings.lock().unwrap().clone()
   }
 
-  pub fn update_settings(&self, new_settings: SettingsPatch) -> Result<Settings, anyhow::Error> {
-    let ab_doc_path = dirs::home_dir()
-      .unwrap()
-      .join(new_settings.doc_root_path.clone().unwrap_or_default());
-    tracing::info!("ab_doc_path: {:?}", ab_doc_path);
-    if !ab_doc_path.exists() {
-      tracing::error!("doc_root_path does not exist: {:?}", ab_doc_path);
+  /// Absolute path of a workspace folder given absolute or relative to the home folder.
+  pub fn resolve_doc_root(doc_root_path: impl AsRef<Path>) -> PathBuf {
+    dirs::home_dir().unwrap().join(doc_root_path)
+  }
+
+  /// Resolves the workspace folder at `path` like [`Self::resolve_doc_root`], failing
+  /// when it isn't an existing folder. Lets callers validate a workspace before tearing
+  /// down the state of the current one.
+  pub fn check_workspace(path: impl AsRef<Path>) -> Result<PathBuf, anyhow::Error> {
+    let doc_root_path = Self::resolve_doc_root(path);
+    if !doc_root_path.is_dir() {
+      tracing::error!("doc_root_path does not exist: {:?}", doc_root_path);
       return Err(anyhow::anyhow!(
         "Workspace does not exist: {:?}",
-        ab_doc_path
+        doc_root_path
+      ));
+    }
+    Ok(doc_root_path)
+  }
+
+  /// Resolves a workspace to switch to, failing unless it's an existing registered folder.
+  pub fn check_registered_workspace(&self, path: &str) -> Result<PathBuf, anyhow::Error> {
+    let doc_root_path = Self::check_workspace(path)?;
+    if !self
+      .read_editor_settings()?
+      .workspaces
+      .iter()
+      .any(|workspace| workspace.path == doc_root_path)
+    {
+      return Err(anyhow::anyhow!(
+        "The workspace {:?} is not registered.",
+        doc_root_path
       ));
     }
+    Ok(doc_root_path)
+  }
+
+  pub fn update_settings(&self, new_settings: SettingsPatch) -> Result<Settings, anyhow::Error> {
+    let ab_doc_path =
+      Self::check_workspace(new_settings.doc_root_path.clone().unwrap_or_default())?;
+    tracing::info!("ab_doc_path: {:?}", ab_doc_path);
 
     let mut new_settings = new_settings;
     new_settings.doc_root_path = Some(ab_doc_path);
@@ -156,20 +361,317 @@ impl SettingsService {
     let updated_settings = self.settings.lock().unwrap().clone();
     tracing::info!("settings updated: {:?}", updated_settings);
 
-    let new_editor_settings = EditorSettings {
-      doc_root_path: updated_settings.doc_root_path.clone(),
-    };
+    let _guard = self.editor_settings_lock.lock().unwrap();
+    let mut new_editor_settings = self.read_editor_settings()?;
+    new_editor_settings.doc_root_path = updated_settings.doc_root_path.clone();
+    open_workspace(
+      &mut new_editor_settings.workspaces,
+      &updated_settings.doc_root_path,
+    );
     let new_worksapce_settings = WorkspaceSettings {
       ignore_dirs: updated_settings.ignore_dirs.clone(),
+      trash_retention_days: updated_settings.trash_retention_days,
+      doc_extensions: updated_settings.doc_extensions.clone(),
+      templates_dir: updated_settings.templates_dir.clone(),
+      journal_dir: updated_settings.journal_dir.clone(),
+      journal_file_pattern: updated_settings.journal_file_pattern.clone(),
+      journal_template: updated_settings.journal_template.clone(),
+      history_max_versions: updated_settings.history_max_versions,
+      history_retention_days: updated_settings.history_retention_days,
+      sort_mode: updated_settings.sort_mode,
     };
 
-    fs::write(
+    write_atomic(
+      &self.editor_settings_file,
+      serde_json::to_string_pretty(&new_editor_settings)?,
+    )?;
+    Settings::set_workspace_settings(&updated_settings.doc_root_path, &new_worksapce_settings)?;
+
+    Ok(updated_settings)
+  }
+
+  /// Registered workspaces, the most recently opened first.
+  pub fn list_workspaces(&self) -> Result<Vec<Workspace>, anyhow::Error> {
+    let mut workspaces = self.read_editor_settings()?.workspaces;
+    workspaces.sort_by(|a, b| {
+      b.last_opened
+        .cmp(&a.last_opened)
+        .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(workspaces)
+  }
+
+  /// Registers the folder at `path` as a workspace named `name`, the folder name by default.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let workspace = settings_service.add_workspace("notes/work", Some("Work".to_string()))?;
+  /// settings_service.switch_workspace("notes/work")?;
+  /// ```
+  pub fn add_workspace(
+    &self,
+    path: &str,
+    name: Option<String>,
+  ) -> Result<Workspace, anyhow::Error> {
+    let doc_root_path = Self::check_workspace(path)?;
+    let name = match name.as_deref().map(str::trim) {
+      Some(name) if !name.is_empty() => name.to_string(),
+      Some(_) => return Err(anyhow::anyhow!("The workspace name can't be empty.")),
+      None => workspace_name(&doc_root_path),
+    };
+
+    let _guard = self.editor_settings_lock.lock().unwrap();
+    let mut editor_settings = self.read_editor_settings()?;
+    if editor_settings
+      .workspaces
+      .iter()
+      .any(|workspace| workspace.path == doc_root_path)
+    {
+      return Err(anyhow::anyhow!(
+        "The workspace {:?} is already registered.",
+        doc_root_path
+      ));
+    }
+    if editor_settings
+      .workspaces
+      .iter()
+      .any(|workspace| workspace.name == name)
+    {
+      return Err(anyhow::anyhow!(
+        "A workspace named {} is already registered.",
+        name
+      ));
+    }
+
+    let workspace = Workspace {
+      name,
+      path: doc_root_path,
+      last_opened: None,
+    };
+    editor_settings.workspaces.push(workspace.clone());
+    self.write_editor_settings(&editor_settings)?;
+
+    tracing::info!("[SettingsService] workspace added: {:?}", workspace);
+    Ok(workspace)
+  }
+
+  /// Unregisters a workspace, its folder is left untouched. The current workspace
+  /// can't be removed.
+  pub fn remove_workspace(&self, path: &str) -> Result<(), anyhow::Error> {
+    let doc_root_path = Self::resolve_doc_root(path);
+    if self.settings.lock().unwrap().doc_root_path == doc_root_path {
+      return Err(anyhow::anyhow!(
+        "The current workspace can't be removed, switch to another one first."
+      ));
+    }
+
+    let _guard = self.editor_settings_lock.lock().unwrap();
+    let mut editor_settings = self.read_editor_settings()?;
+    let count = editor_settings.workspaces.len();
+    editor_settings
+      .workspaces
+      .retain(|workspace| workspace.path != doc_root_path);
+    if editor_settings.workspaces.len() == count {
+      return Err(anyhow::anyhow!(
+        "The workspace {:?} is not registered.",
+        doc_root_path
+      ));
+    }
+    self.write_editor_settings(&editor_settings)?;
+
+    tracing::info!("[SettingsService] workspace removed: {:?}", doc_root_path);
+    Ok(())
+  }
+
+  /// Makes a registered workspace the current one, loading its workspace settings.
+  /// The services holding per workspace state must be reloaded afterwards.
+  pub fn switch_workspace(&self, path: &str) -> Result<Settings, anyhow::Error> {
+    let doc_root_path = self.check_registered_workspace(path)?;
+    let _guard = self.editor_settings_lock.lock().unwrap();
+    let mut editor_settings = self.read_editor_settings()?;
+
+    let workspace_settings = Settings::load_workspace_settings_from_file(&doc_root_path);
+    let settings = Settings::from_workspace_settings(doc_root_path.clone(), workspace_settings);
+
+    editor_settings.doc_root_path = doc_root_path.clone();
+    open_workspace(&mut editor_settings.workspaces, &doc_root_path);
+    self.write_editor_settings(&editor_settings)?;
+    *self.settings.lock().unwrap() = settings.clone();
+
+    tracing::info!(
+      "[SettingsService] switched to workspace {:?}",
+      doc_root_path
+    );
+    Ok(settings)
+  }
+
+  fn read_editor_settings(&self) -> Result<EditorSettings, anyhow::Error> {
+    if !self.editor_settings_file.exists() {
+      return Ok(EditorSettings {
+        doc_root_path: self.settings.lock().unwrap().doc_root_path.clone(),
+        workspaces: Vec::new(),
+      });
+    }
+    let file_content = fs::read_to_string(&self.editor_settings_file)?;
+    Ok(serde_json::from_str(&file_content)?)
+  }
+
+  fn write_editor_settings(&self, editor_settings: &EditorSettings) -> Result<(), anyhow::Error> {
+    write_atomic(
       &self.editor_settings_file,
-      serde_json::to_string_pretty(&new_editor_settings).unwrap(),
+      serde_json::to_string_pretty(editor_settings)?,
+    )?;
+    Ok(())
+  }
+}
+
+/// Marks the workspace at `doc_root_path` as opened now, registering it if needed.
+fn open_workspace(workspaces: &mut Vec<Workspace>, doc_root_path: &Path) {
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_millis() as u64)
+    .ok();
+  match workspaces
+    .iter_mut()
+    .find(|workspace| workspace.path == doc_root_path)
+  {
+    Some(workspace) => workspace.last_opened = now,
+    None => workspaces.push(Workspace {
+      name: workspace_name(doc_root_path),
+      path: doc_root_path.to_path_buf(),
+      last_opened: now,
+    }),
+  }
+}
+
+/// Default name of a workspace, its folder name.
+fn workspace_name(doc_root_path: &Path) -> String {
+  doc_root_path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_else(|| doc_root_path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+impl SettingsService {
+  /// A service for the test workspace at `doc_root_path`, with the default workspace
+  /// settings but `ignore_dirs`.
+  pub(crate) fn for_test(
+    doc_root_path: PathBuf,
+    ignore_dirs: &[&str],
+    editor_settings_file: PathBuf,
+  ) -> Self {
+    let workspace_settings = WorkspaceSettings {
+      ignore_dirs: ignore_dirs.iter().map(|dir| dir.to_string()).collect(),
+      ..WorkspaceSettings::default()
+    };
+    Self::from_settings(
+      Settings::from_workspace_settings(doc_root_path, workspace_settings),
+      editor_settings_file,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn setup_test_service() -> (SettingsService, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let notes = temp_dir.path().join("notes");
+    fs::create_dir_all(&notes).unwrap();
+    let settings_service = SettingsService::from_settings(
+      Settings::from_workspace_settings(notes, WorkspaceSettings::default()),
+      temp_dir.path().join("editor-settings.json"),
+    );
+    (settings_service, temp_dir)
+  }
+
+  #[test]
+  fn test_read_settings_without_writing() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let editor_settings_file = temp_dir.path().join("editor-settings.json");
+    let settings = Settings::read_from_file(&editor_settings_file).unwrap();
+    assert_eq!(settings.doc_root_path, PathBuf::from(""));
+    assert!(!editor_settings_file.exists());
+
+    let notes = temp_dir.path().join("notes");
+    fs::create_dir_all(&notes).unwrap();
+    fs::write(
+      &editor_settings_file,
+      serde_json::json!({ "docRootPath": notes }).to_string(),
     )
     .unwrap();
-    Settings::set_workspace_settings(&updated_settings.doc_root_path, &new_worksapce_settings);
+    let settings = Settings::read_from_file(&editor_settings_file).unwrap();
+    assert_eq!(settings.doc_root_path, notes);
+    assert_eq!(
+      settings.journal_dir,
+      WorkspaceSettings::default().journal_dir
+    );
+    assert!(!notes.join(".workspace-settings.json").exists());
+  }
 
-    Ok(updated_settings)
+  #[test]
+  fn test_workspace_registry() {
+    let (service, temp_dir) = setup_test_service();
+    let work = temp_dir.path().join("work");
+    fs::create_dir_all(&work).unwrap();
+    Settings::set_workspace_settings(
+      &work,
+      &WorkspaceSettings {
+        journal_dir: "daily".to_string(),
+        ..WorkspaceSettings::default()
+      },
+    )
+    .unwrap();
+    let work_path = work.to_string_lossy().to_string();
+    let missing_path = temp_dir
+      .path()
+      .join("missing")
+      .to_string_lossy()
+      .to_string();
+    assert_eq!(SettingsService::check_workspace(&work_path).unwrap(), work);
+    assert!(SettingsService::check_workspace(&missing_path).is_err());
+    assert!(service.check_registered_workspace(&work_path).is_err());
+
+    let added = service.add_workspace(&work_path, None).unwrap();
+    assert_eq!(added.name, "work");
+    assert_eq!(added.last_opened, None);
+    assert!(service.add_workspace(&work_path, None).is_err());
+    assert!(service.add_workspace(&missing_path, None).is_err());
+    assert_eq!(
+      service.check_registered_workspace(&work_path).unwrap(),
+      work
+    );
+
+    let settings = service.switch_workspace(&work_path).unwrap();
+    assert_eq!(settings.doc_root_path, work);
+    assert_eq!(settings.journal_dir, "daily");
+    assert_eq!(service.get_settings().doc_root_path, work);
+    let workspaces = service.list_workspaces().unwrap();
+    assert_eq!(workspaces.len(), 1);
+    assert!(workspaces[0].last_opened.is_some());
+
+    // persisted with the current workspace
+    let editor_settings: EditorSettings =
+      serde_json::from_str(&fs::read_to_string(&service.editor_settings_file).unwrap()).unwrap();
+    assert_eq!(editor_settings.doc_root_path, work);
+    assert_eq!(editor_settings.workspaces, workspaces);
+
+    assert!(service.remove_workspace(&work_path).is_err());
+    let notes = temp_dir.path().join("notes");
+    service
+      .add_workspace(&notes.to_string_lossy(), Some("Notes".to_string()))
+      .unwrap();
+    service.switch_workspace(&notes.to_string_lossy()).unwrap();
+    service.remove_workspace(&work_path).unwrap();
+    let names: Vec<String> = service
+      .list_workspaces()
+      .unwrap()
+      .into_iter()
+      .map(|workspace| workspace.name)
+      .collect();
+    assert_eq!(names, vec!["Notes"]);
+    assert!(service.switch_workspace(&work_path).is_err());
   }
 }
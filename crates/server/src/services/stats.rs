@@ -0,0 +1,340 @@
+use std::{
+  collections::HashMap,
+  fs,
+  sync::Arc,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate};
+use serde::Serialize;
+
+use crate::services::{
+  doc::markdown::word_count,
+  img::{ASSETS_DIR, ImgService},
+  search::SearchService,
+  settings::SettingsService,
+};
+
+/// Docs not modified for this many months are reported as stale by default.
+pub const DEFAULT_STALE_MONTHS: u32 = 6;
+
+/// Weeks of modification activity reported by default.
+pub const DEFAULT_ACTIVITY_WEEKS: u32 = 12;
+
+/// Most weeks of modification activity reported, about ten years.
+pub const MAX_ACTIVITY_WEEKS: u32 = 520;
+
+/// Number of docs listed as the largest ones.
+const LARGEST_DOCS_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocSizeStat {
+  pub name: String,
+  pub path: Vec<String>,
+  /// Size in bytes.
+  pub size: u64,
+  pub word_count: usize,
+}
+
+/// Docs modified during the week starting on `week_start`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekActivity {
+  /// Monday of the week, e.g. `2024-03-04`.
+  pub week_start: String,
+  pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleDoc {
+  pub name: String,
+  pub path: Vec<String>,
+  /// Modification time in milliseconds since the Unix epoch.
+  pub modified_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStats {
+  pub doc_count: usize,
+  pub folder_count: usize,
+  pub total_words: usize,
+  /// Largest docs first.
+  pub largest_docs: Vec<DocSizeStat>,
+  /// Images in the assets folder and among the docs.
+  pub image_count: usize,
+  /// Size in bytes of the files in the assets folder.
+  pub assets_size: u64,
+  /// Oldest week first, the current week last.
+  pub modified_per_week: Vec<WeekActivity>,
+  /// Least recently modified first.
+  pub stale_docs: Vec<StaleDoc>,
+}
+
+pub struct StatsService {
+  settings_service: Arc<SettingsService>,
+  search_service: Arc<SearchService>,
+}
+
+impl StatsService {
+  pub fn new(settings_service: Arc<SettingsService>, search_service: Arc<SearchService>) -> Self {
+    Self {
+      settings_service,
+      search_service,
+    }
+  }
+
+  /// Walks the workspace like the search does and summarizes it. Docs modified before
+  /// `stale_months` months ago are stale, `weeks` is how many weeks of activity to report.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let stats = stats_service.workspace_stats(DEFAULT_STALE_MONTHS, DEFAULT_ACTIVITY_WEEKS)?;
+  /// println!("{} docs, {} words", stats.doc_count, stats.total_words);
+  /// ```
+  pub fn workspace_stats(
+    &self,
+    stale_months: u32,
+    weeks: u32,
+  ) -> Result<WorkspaceStats, anyhow::Error> {
+    let settings = self.settings_service.get_settings();
+    let doc_root = settings.doc_root_path;
+    if !doc_root.exists() {
+      return Err(anyhow::anyhow!(
+        "Doc root path does not exist: {}",
+        doc_root.display()
+      ));
+    }
+
+    let now = Local::now();
+    let stale_before = now
+      .checked_sub_months(Months::new(stale_months))
+      .map(SystemTime::from)
+      .unwrap_or(UNIX_EPOCH);
+    // docs modified per week start, looked up once per doc
+    let mut modified_per_week: HashMap<NaiveDate, usize> = HashMap::new();
+
+    let mut stats = WorkspaceStats {
+      doc_count: 0,
+      folder_count: 0,
+      total_words: 0,
+      largest_docs: Vec::new(),
+      image_count: 0,
+      assets_size: 0,
+      modified_per_week: Vec::new(),
+      stale_docs: Vec::new(),
+    };
+    let mut doc_sizes = Vec::new();
+
+    for entry in self
+      .search_service
+      .build_walker(&doc_root, &settings.ignore_dirs)
+    {
+      let entry = entry?;
+      let path = entry.path();
+      if entry.depth() == 0 || !SearchService::is_within_root(&entry, &doc_root) {
+        continue;
+      }
+      if entry
+        .file_type()
+        .is_some_and(|file_type| file_type.is_dir())
+      {
+        // the walker skips the content of the ignored folders but still yields them
+        let name = entry.file_name().to_string_lossy();
+        if name != ASSETS_DIR && !settings.ignore_dirs.iter().any(|dir| *dir == name) {
+          stats.folder_count += 1;
+        }
+        continue;
+      }
+      if !path.is_file() {
+        continue;
+      }
+      if SearchService::doc_file_name(path, &settings.doc_extensions).is_none() {
+        if ImgService::is_image(path) {
+          stats.image_count += 1;
+        }
+        continue;
+      }
+
+      let doc_path = SearchService::fs_path_to_doc_path(path, &doc_root, &settings.doc_extensions);
+      let name = doc_path.last().cloned().unwrap_or_default();
+      let words = word_count(&fs::read_to_string(path).unwrap_or_default());
+      let metadata = entry.metadata()?;
+      stats.doc_count += 1;
+      stats.total_words += words;
+
+      if let Ok(modified) = metadata.modified() {
+        let modified_on = DateTime::<Local>::from(modified).date_naive();
+        *modified_per_week
+          .entry(week_start(modified_on))
+          .or_default() += 1;
+        if modified < stale_before {
+          stats.stale_docs.push(StaleDoc {
+            name: name.clone(),
+            path: doc_path.clone(),
+            modified_at: modified
+              .duration_since(UNIX_EPOCH)
+              .map(|duration| duration.as_millis() as u64)
+              .unwrap_or(0),
+          });
+        }
+      }
+
+      doc_sizes.push(DocSizeStat {
+        name,
+        path: doc_path,
+        size: metadata.len(),
+        word_count: words,
+      });
+    }
+
+    // the walker skips the assets folder
+    if let Ok(entries) = fs::read_dir(doc_root.join(ASSETS_DIR)) {
+      for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+          continue;
+        };
+        if !metadata.is_file() {
+          continue;
+        }
+        stats.assets_size += metadata.len();
+        if ImgService::is_image(&path) {
+          stats.image_count += 1;
+        }
+      }
+    }
+
+    doc_sizes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    doc_sizes.truncate(LARGEST_DOCS_LIMIT);
+    stats.largest_docs = doc_sizes;
+    stats.stale_docs.sort_by(|a, b| {
+      a.modified_at
+        .cmp(&b.modified_at)
+        .then_with(|| a.path.cmp(&b.path))
+    });
+    stats.modified_per_week = activity_weeks(now.date_naive(), weeks, &modified_per_week);
+
+    Ok(stats)
+  }
+}
+
+/// Monday of the week of `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+  date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+/// Activity of the `weeks` weeks up to the one of `today`, oldest first, with the counts
+/// of `modified_per_week` keyed by week start.
+fn activity_weeks(
+  today: NaiveDate,
+  weeks: u32,
+  modified_per_week: &HashMap<NaiveDate, usize>,
+) -> Vec<WeekActivity> {
+  let current = week_start(today);
+  (0..weeks)
+    .rev()
+    .filter_map(|ago| current.checked_sub_days(Days::new(ago as u64 * 7)))
+    .map(|week| WeekActivity {
+      week_start: week.format("%Y-%m-%d").to_string(),
+      count: modified_per_week.get(&week).copied().unwrap_or(0),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{path::Path, time::Duration};
+
+  use super::*;
+
+  fn setup_test_service() -> (StatsService, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let settings_service = Arc::new(SettingsService::for_test(
+      temp_dir.path().to_path_buf(),
+      &["node_modules"],
+      temp_dir.path().join("editor-settings.json"),
+    ));
+    let search_service = Arc::new(SearchService::new(settings_service.clone()));
+    (
+      StatsService::new(settings_service, search_service),
+      temp_dir,
+    )
+  }
+
+  fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+  }
+
+  #[test]
+  fn test_week_start() {
+    let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    assert_eq!(week_start(date("2024-03-06")), date("2024-03-04"));
+    assert_eq!(week_start(date("2024-03-04")), date("2024-03-04"));
+    assert_eq!(week_start(date("2024-03-03")), date("2024-02-26"));
+
+    let modified_per_week = HashMap::from([(date("2024-02-26"), 2), (date("2023-01-02"), 1)]);
+    let weeks = activity_weeks(date("2024-03-06"), 3, &modified_per_week);
+    let starts: Vec<(&str, usize)> = weeks
+      .iter()
+      .map(|w| (w.week_start.as_str(), w.count))
+      .collect();
+    assert_eq!(
+      starts,
+      vec![("2024-02-19", 0), ("2024-02-26", 2), ("2024-03-04", 0)]
+    );
+  }
+
+  #[test]
+  fn test_workspace_stats() {
+    let (service, temp_dir) = setup_test_service();
+    let root = temp_dir.path();
+    write(root, "readme.md", "# Readme\n\nHello there.\n");
+    write(
+      root,
+      "js/array.md",
+      "Map and filter arrays, the long doc.\n",
+    );
+    write(root, "js/old.md", "old");
+    write(root, "js/diagram.png", "png");
+    write(root, "_assets/logo.png", "12345");
+    write(root, "_assets/notes.pdf", "123");
+    write(root, "node_modules/pkg/readme.md", "ignored");
+    write(root, ".trash/1/a.md", "hidden");
+    let year_ago = SystemTime::now() - Duration::from_secs(365 * 24 * 3600);
+    fs::File::options()
+      .write(true)
+      .open(root.join("js/old.md"))
+      .unwrap()
+      .set_modified(year_ago)
+      .unwrap();
+
+    let stats = service.workspace_stats(6, 4).unwrap();
+    assert_eq!(stats.doc_count, 3);
+    assert_eq!(stats.folder_count, 1);
+    assert_eq!(stats.total_words, 3 + 7 + 1);
+    assert_eq!(stats.image_count, 2);
+    assert_eq!(stats.assets_size, 8);
+    assert_eq!(stats.largest_docs[0].name, "array");
+    assert_eq!(stats.largest_docs[0].word_count, 7);
+    assert_eq!(stats.largest_docs.len(), 3);
+
+    assert_eq!(stats.modified_per_week.len(), 4);
+    assert_eq!(stats.modified_per_week[3].count, 2);
+    assert_eq!(
+      stats
+        .modified_per_week
+        .iter()
+        .map(|week| week.count)
+        .sum::<usize>(),
+      2
+    );
+
+    let stale: Vec<&str> = stats.stale_docs.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(stale, vec!["old"]);
+  }
+}
@@ -0,0 +1,374 @@
+use std::{
+  fs, io,
+  path::{Path, PathBuf},
+  sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+  },
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  services::{doc::copy_dir_all, settings::SettingsService},
+  utils::{atomic_write::write_atomic, workspace_path::WorkspacePath},
+};
+
+/// Workspace-local trash folder. Hidden, so the doc tree and search skip it, and it
+/// carries its own `.gitignore` so git never sees it either.
+pub const TRASH_DIR: &str = ".trash";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Distinguishes items trashed within the same millisecond.
+static TRASH_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+  pub id: String,
+  /// File or folder name, including the extension.
+  pub name: String,
+  /// Original path relative to the workspace, `/` separated.
+  pub original_path: String,
+  pub is_file: bool,
+  /// Unix timestamp (seconds) of the deletion.
+  pub deleted_at: u64,
+}
+
+/// What to do when the original path of a restored item is taken again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RestoreConflictStrategy {
+  /// Fail with a [`TrashRestoreConflict`].
+  #[default]
+  Fail,
+  /// Restore next to the existing item with a numbered name, e.g. `note (1).md`.
+  KeepBoth,
+  /// Move the existing item to the trash and restore in its place.
+  Replace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreTrashRequest {
+  pub id: String,
+  #[serde(default)]
+  pub conflict_strategy: RestoreConflictStrategy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreTrashResponse {
+  /// Path the item was restored to, relative to the workspace, `/` separated.
+  pub restored_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeTrashRequest {
+  /// Items to purge, everything when omitted.
+  #[serde(default)]
+  pub ids: Option<Vec<String>>,
+}
+
+/// Returned when restoring an item whose original path is occupied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashRestoreConflict {
+  pub id: String,
+  pub original_path: String,
+}
+
+impl std::fmt::Display for TrashRestoreConflict {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} already exists.", self.original_path)
+  }
+}
+
+impl std::error::Error for TrashRestoreConflict {}
+
+pub struct TrashService {
+  settings_service: Arc<SettingsService>,
+}
+
+impl TrashService {
+  pub fn new(settings_service: Arc<SettingsService>) -> Self {
+    Self { settings_service }
+  }
+
+  /// Moves a file or folder of the workspace into the trash instead of deleting it.
+  ///
+  /// # Example
+  /// ```ignore
+  /// let item = trash_service.move_to_trash(&doc_root.join("js").join("array.md"))?;
+  /// trash_service.restore(&item.id, RestoreConflictStrategy::Fail)?;
+  /// ```
+  pub fn move_to_trash(&self, path: &Path) -> Result<TrashItem, anyhow::Error> {
+    let doc_root = self.get_doc_root_path();
+    if path == doc_root {
+      return Err(anyhow::anyhow!("The workspace root can not be trashed."));
+    }
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+      return Err(anyhow::anyhow!("Invalid path to trash: {}", path.display()));
+    };
+    // a symlink is trashed as the link itself, so only its folder has to be in the workspace
+    let mut segments = WorkspacePath::from_absolute(&doc_root, parent)?.segments();
+    segments.push(name.to_string_lossy().to_string());
+    if segments[0] == TRASH_DIR {
+      return Err(anyhow::anyhow!(
+        "{} is already in the trash.",
+        path.display()
+      ));
+    }
+    let metadata = fs::symlink_metadata(path)?;
+
+    self.purge_expired();
+    let trash_dir = self.ensure_trash_dir(&doc_root)?;
+
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    let id = format!(
+      "{}-{}",
+      millis,
+      TRASH_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let name = segments.last().unwrap().clone();
+    let item = TrashItem {
+      id: id.clone(),
+      name: name.clone(),
+      original_path: segments.join("/"),
+      is_file: !metadata.is_dir(),
+      deleted_at: (millis / 1000) as u64,
+    };
+
+    let item_dir = trash_dir.join(&id);
+    fs::create_dir_all(&item_dir)?;
+    if let Err(e) = move_path(path, &item_dir.join(&name)) {
+      let _ = fs::remove_dir_all(&item_dir);
+      return Err(e);
+    }
+    write_atomic(
+      &trash_dir.join(format!("{}.json", id)),
+      serde_json::to_string_pretty(&item)?,
+    )?;
+
+    tracing::info!("[TrashService] trashed {} as {}", item.original_path, id);
+    Ok(item)
+  }
+
+  /// Lists trashed items, most recently deleted first.
+  pub fn list(&self) -> Result<Vec<TrashItem>, anyhow::Error> {
+    self.purge_expired();
+    let mut items = self.read_items(&self.get_doc_root_path())?;
+    items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then(b.id.cmp(&a.id)));
+    Ok(items)
+  }
+
+  /// Moves a trashed item back to its original path, creating missing parent folders.
+  pub fn restore(
+    &self,
+    id: &str,
+    strategy: RestoreConflictStrategy,
+  ) -> Result<RestoreTrashResponse, anyhow::Error> {
+    let doc_root = self.get_doc_root_path();
+    let item = self.find_item(&doc_root, id)?;
+    let trashed_path = self.item_dir(&doc_root, id)?.join(&item.name);
+
+    let original = WorkspacePath::from_relative(&doc_root, &item.original_path)?;
+    let mut target = original.to_path_buf();
+    if fs::symlink_metadata(&target).is_ok() {
+      match strategy {
+        RestoreConflictStrategy::Fail => {
+          return Err(
+            TrashRestoreConflict {
+              id: item.id,
+              original_path: item.original_path,
+            }
+            .into(),
+          );
+        }
+        RestoreConflictStrategy::KeepBoth => target = available_path(&target, item.is_file),
+        RestoreConflictStrategy::Replace => {
+          self.move_to_trash(&target)?;
+        }
+      }
+    }
+
+    if let Some(parent) = target.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    move_path(&trashed_path, &target)?;
+    self.remove_item(&doc_root, id)?;
+
+    let restored_path = WorkspacePath::from_absolute(&doc_root, &target)?
+      .segments()
+      .join("/");
+    tracing::info!("[TrashService] restored {} to {}", id, restored_path);
+    Ok(RestoreTrashResponse { restored_path })
+  }
+
+  /// Permanently deletes the given items, or the whole trash when `ids` is `None`.
+  /// Returns the number of purged items.
+  pub fn purge(&self, ids: Option<&[String]>) -> Result<usize, anyhow::Error> {
+    let doc_root = self.get_doc_root_path();
+    let ids: Vec<String> = match ids {
+      Some(ids) => ids.to_vec(),
+      None => self
+        .read_items(&doc_root)?
+        .into_iter()
+        .map(|item| item.id)
+        .collect(),
+    };
+
+    // nothing is deleted if any of the ids is invalid
+    for id in &ids {
+      self.find_item(&doc_root, id)?;
+    }
+    for id in &ids {
+      self.remove_item(&doc_root, id)?;
+    }
+    Ok(ids.len())
+  }
+
+  /// Applies the retention policy of the workspace, purging items older than
+  /// `trash_retention_days`. Failures are only logged since this runs as a side effect.
+  pub fn purge_expired(&self) {
+    let (doc_root, retention_days) = {
+      let settings = self.settings_service.settings.lock().unwrap();
+      (
+        settings.doc_root_path.clone(),
+        settings.trash_retention_days,
+      )
+    };
+    if retention_days == 0 {
+      return;
+    }
+
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+      return;
+    };
+    let cutoff = now
+      .as_secs()
+      .saturating_sub(retention_days as u64 * SECONDS_PER_DAY);
+
+    let items = match self.read_items(&doc_root) {
+      Ok(items) => items,
+      Err(e) => {
+        tracing::warn!("[TrashService] failed to read the trash: {}", e);
+        return;
+      }
+    };
+    for item in items.into_iter().filter(|item| item.deleted_at < cutoff) {
+      match self.remove_item(&doc_root, &item.id) {
+        Ok(()) => tracing::info!("[TrashService] purged expired {}", item.original_path),
+        Err(e) => tracing::warn!("[TrashService] failed to purge {}: {}", item.id, e),
+      }
+    }
+  }
+
+  fn read_items(&self, doc_root: &Path) -> Result<Vec<TrashItem>, anyhow::Error> {
+    let trash_dir = doc_root.join(TRASH_DIR);
+    if !trash_dir.exists() {
+      return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+    for entry in fs::read_dir(&trash_dir)? {
+      let path = entry?.path();
+      if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+        continue;
+      }
+      match fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(serde_json::from_str::<TrashItem>(&content)?))
+      {
+        Ok(item) => items.push(item),
+        Err(e) => tracing::warn!("[TrashService] skip {}: {}", path.display(), e),
+      }
+    }
+    Ok(items)
+  }
+
+  fn find_item(&self, doc_root: &Path, id: &str) -> Result<TrashItem, anyhow::Error> {
+    let meta_path = Self::meta_path(&self.item_dir(doc_root, id)?, id);
+    let content = fs::read_to_string(&meta_path)
+      .map_err(|_| anyhow::anyhow!("Trash item {} does not exist.", id))?;
+    Ok(serde_json::from_str(&content)?)
+  }
+
+  fn remove_item(&self, doc_root: &Path, id: &str) -> Result<(), anyhow::Error> {
+    let item_dir = self.item_dir(doc_root, id)?;
+    if item_dir.exists() {
+      fs::remove_dir_all(&item_dir)?;
+    }
+    // the metadata goes last, an item without it is not listed anymore
+    fs::remove_file(Self::meta_path(&item_dir, id))?;
+    Ok(())
+  }
+
+  /// Resolves the folder of a trash item, rejecting ids that aren't a single name.
+  fn item_dir(&self, doc_root: &Path, id: &str) -> Result<PathBuf, anyhow::Error> {
+    let item_dir = WorkspacePath::resolve(doc_root, [TRASH_DIR, id])?;
+    if item_dir.segments().len() != 2 {
+      return Err(anyhow::anyhow!("Invalid trash item id: {}", id));
+    }
+    Ok(item_dir.to_path_buf())
+  }
+
+  /// Metadata of an item is stored next to its folder as `<id>.json`.
+  fn meta_path(item_dir: &Path, id: &str) -> PathBuf {
+    item_dir.with_file_name(format!("{}.json", id))
+  }
+
+  fn ensure_trash_dir(&self, doc_root: &Path) -> Result<PathBuf, anyhow::Error> {
+    let trash_dir = doc_root.join(TRASH_DIR);
+    fs::create_dir_all(&trash_dir)?;
+
+    let gitignore = trash_dir.join(".gitignore");
+    if !gitignore.exists() {
+      write_atomic(&gitignore, "# deleted docs, managed by the editor\n*\n")?;
+    }
+    Ok(trash_dir)
+  }
+
+  fn get_doc_root_path(&self) -> PathBuf {
+    let settings = self.settings_service.settings.lock().unwrap();
+    settings.doc_root_path.clone()
+  }
+}
+
+/// Renames `from` to `to`, falling back to copy and delete across filesystems.
+fn move_path(from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+  match fs::rename(from, to) {
+    Ok(()) => return Ok(()),
+    Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {}
+    Err(e) => return Err(e.into()),
+  }
+
+  if fs::symlink_metadata(from)?.is_dir() {
+    copy_dir_all(from, to)?;
+    fs::remove_dir_all(from)?;
+  } else {
+    fs::copy(from, to)?;
+    fs::remove_file(from)?;
+  }
+  Ok(())
+}
+
+/// First free `name (n).ext` sibling of `path`.
+fn available_path(path: &Path, is_file: bool) -> PathBuf {
+  let file_name = path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let (stem, ext) = match file_name.rsplit_once('.') {
+    Some((stem, ext)) if is_file && !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+    _ => (file_name.clone(), String::new()),
+  };
+
+  (1u32..)
+    .map(|i| path.with_file_name(format!("{} ({}){}", stem, i, ext)))
+    .find(|candidate| fs::symlink_metadata(candidate).is_err())
+    .unwrap()
+}
@@ -0,0 +1,325 @@
+use std::{
+  path::{Component, Path},
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use notify_debouncer_full::{
+  DebounceEventResult, Debouncer, RecommendedCache, new_debouncer,
+  notify::{
+    Event, EventKind, RecommendedWatcher, RecursiveMode,
+    event::{ModifyKind, RenameMode},
+  },
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::{
+  services::{doc::DocService, settings::SettingsService},
+  utils::atomic_write::is_temp_file_of,
+};
+
+const INTERNAL_IGNORE_DIRS: &[&str] = &["_assets"];
+
+/// Changes within this window are merged into a single event.
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Events kept for slow subscribers before they start missing some.
+const EVENT_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FsChangeKind {
+  Create,
+  Modify,
+  Rename,
+  Delete,
+}
+
+/// A change of a file or folder of the workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsChangeEvent {
+  pub kind: FsChangeKind,
+  /// Path relative to the workspace, `/` separated, e.g. `js/array.md`.
+  pub path: String,
+  /// Previous path of a renamed item.
+  pub from_path: Option<String>,
+}
+
+pub struct WatcherService {
+  settings_service: Arc<SettingsService>,
+  doc_service: Arc<DocService>,
+  sender: broadcast::Sender<FsChangeEvent>,
+  /// Watcher of the current workspace, dropping it stops watching.
+  debouncer: Mutex<Option<Debouncer<RecommendedWatcher, RecommendedCache>>>,
+}
+
+impl WatcherService {
+  /// Creates the service and starts watching the current workspace.
+  pub fn new(settings_service: Arc<SettingsService>, doc_service: Arc<DocService>) -> Self {
+    let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+    let service = Self {
+      settings_service,
+      doc_service,
+      sender,
+      debouncer: Mutex::new(None),
+    };
+    service.rearm();
+
+    tracing::info!("[WatcherService] Watcher initialized.");
+    service
+  }
+
+  /// Receives the changes of the workspace from now on.
+  pub fn subscribe(&self) -> broadcast::Receiver<FsChangeEvent> {
+    self.sender.subscribe()
+  }
+
+  /// Stops watching the previous workspace and watches the current `doc_root_path`,
+  /// to be called whenever the workspace changes.
+  pub fn rearm(&self) {
+    let mut debouncer = self.debouncer.lock().unwrap();
+    *debouncer = None;
+
+    let doc_root = self
+      .settings_service
+      .settings
+      .lock()
+      .unwrap()
+      .doc_root_path
+      .clone();
+    if !doc_root.is_dir() {
+      tracing::info!(
+        "[WatcherService] {:?} is not a folder, not watching.",
+        doc_root
+      );
+      return;
+    }
+
+    match self.watch(&doc_root) {
+      Ok(watching) => {
+        tracing::info!("[WatcherService] watching {:?}", doc_root);
+        *debouncer = Some(watching);
+      }
+      Err(e) => tracing::error!("[WatcherService] failed to watch {:?}: {}", doc_root, e),
+    }
+  }
+
+  fn watch(
+    &self,
+    doc_root: &Path,
+  ) -> Result<Debouncer<RecommendedWatcher, RecommendedCache>, anyhow::Error> {
+    // events carry canonical paths on some platforms, e.g. /private/var on macOS
+    let doc_root = doc_root.canonicalize()?;
+    let root = doc_root.clone();
+    let settings_service = self.settings_service.clone();
+    let doc_service = self.doc_service.clone();
+    let sender = self.sender.clone();
+
+    let mut debouncer = new_debouncer(
+      DEBOUNCE_TIMEOUT,
+      None,
+      move |result: DebounceEventResult| match result {
+        Ok(events) => {
+          let ignore_dirs = settings_service
+            .settings
+            .lock()
+            .unwrap()
+            .ignore_dirs
+            .clone();
+          let mut changes = fs_changes(&root, &ignore_dirs, events.iter().map(|e| &e.event));
+          // content changes don't affect the tree structure, only the changed doc is refreshed
+          let mut structure_changed = false;
+          for change in changes.iter_mut() {
+            if change.kind != FsChangeKind::Modify {
+              structure_changed = true;
+              continue;
+            }
+            match doc_service.refresh_changed_doc(&change.path) {
+              Ok(true) => {}
+              // written through a temp file without existing before
+              Ok(false) => {
+                change.kind = FsChangeKind::Create;
+                structure_changed = true;
+              }
+              Err(e) => {
+                tracing::warn!("[WatcherService] failed to refresh {}: {}", change.path, e);
+                structure_changed = true;
+              }
+            }
+          }
+          if structure_changed {
+            doc_service.invalidate_doc_tree();
+          }
+          for change in changes {
+            // no subscribers is fine
+            let _ = sender.send(change);
+          }
+        }
+        Err(errors) => {
+          for e in errors {
+            tracing::warn!("[WatcherService] watch error: {}", e);
+          }
+        }
+      },
+    )?;
+    debouncer.watch(&doc_root, RecursiveMode::Recursive)?;
+    Ok(debouncer)
+  }
+}
+
+/// Maps raw filesystem events under `root` to workspace changes, dropping the ones of
+/// hidden, internal and ignored folders as well as metadata only changes.
+pub fn fs_changes<'a>(
+  root: &Path,
+  ignore_dirs: &[String],
+  events: impl IntoIterator<Item = &'a Event>,
+) -> Vec<FsChangeEvent> {
+  let relative = |path: &Path| -> Option<String> {
+    let segments: Vec<String> = path
+      .strip_prefix(root)
+      .ok()?
+      .components()
+      .map(|component| match component {
+        Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+        _ => None,
+      })
+      .collect::<Option<_>>()?;
+    let ignored = segments.is_empty()
+      || segments.iter().any(|segment| {
+        segment.starts_with('.')
+          || INTERNAL_IGNORE_DIRS.contains(&segment.as_str())
+          || ignore_dirs.contains(segment)
+      });
+    (!ignored).then(|| segments.join("/"))
+  };
+  let change = |kind, path: Option<String>| {
+    path.map(|path| FsChangeEvent {
+      kind,
+      path,
+      from_path: None,
+    })
+  };
+
+  events
+    .into_iter()
+    .filter_map(|event| {
+      let first = event.paths.first().map(|path| path.as_path());
+      match event.kind {
+        EventKind::Create(_) => change(FsChangeKind::Create, first.and_then(relative)),
+        EventKind::Remove(_) => change(FsChangeKind::Delete, first.and_then(relative)),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+          let from = first.and_then(relative);
+          let to = event.paths.get(1).and_then(|path| relative(path));
+          // atomic writes rename a hidden temp file over the doc
+          let is_write = matches!(
+            (first, event.paths.get(1)),
+            (Some(from), Some(to)) if is_temp_file_of(from, to)
+          );
+          match (from, to) {
+            (Some(from), Some(to)) => Some(FsChangeEvent {
+              kind: FsChangeKind::Rename,
+              path: to,
+              from_path: Some(from),
+            }),
+            (None, Some(to)) if is_write => change(FsChangeKind::Modify, Some(to)),
+            // moved in or out of the ignored folders
+            (None, to) => change(FsChangeKind::Create, to),
+            (from, None) => change(FsChangeKind::Delete, from),
+          }
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+          change(FsChangeKind::Delete, first.and_then(relative))
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+          change(FsChangeKind::Create, first.and_then(relative))
+        }
+        // one side of a rename, the platform doesn't tell which
+        EventKind::Modify(ModifyKind::Name(_)) => {
+          let path = first?;
+          let kind = if path.exists() {
+            FsChangeKind::Create
+          } else {
+            FsChangeKind::Delete
+          };
+          change(kind, relative(path))
+        }
+        EventKind::Modify(ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) => change(FsChangeKind::Modify, first.and_then(relative)),
+        _ => None,
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use notify_debouncer_full::notify::event::{CreateKind, DataChange, MetadataKind, RemoveKind};
+
+  use super::*;
+
+  fn event(kind: EventKind, paths: &[&str]) -> Event {
+    paths.iter().fold(Event::new(kind), |event, path| {
+      event.add_path(PathBuf::from("/docs").join(path))
+    })
+  }
+
+  #[test]
+  fn test_fs_changes() {
+    let events = vec![
+      event(EventKind::Create(CreateKind::File), &["js/array.md"]),
+      event(
+        EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+        &["js/array.md"],
+      ),
+      event(
+        EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any)),
+        &["js/array.md"],
+      ),
+      event(
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+        &["js/array.md", "ts/array.md"],
+      ),
+      event(
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+        &["node_modules/x.md", "x.md"],
+      ),
+      event(
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+        &["js/.array.md.4242-0.tmp", "js/array.md"],
+      ),
+      event(EventKind::Remove(RemoveKind::Folder), &["css"]),
+      event(EventKind::Create(CreateKind::File), &[".trash/1/a.md"]),
+      event(EventKind::Create(CreateKind::File), &["_assets/logo.png"]),
+      event(
+        EventKind::Create(CreateKind::File),
+        &["js/node_modules/x.md"],
+      ),
+    ];
+    let ignore_dirs = vec!["node_modules".to_string()];
+
+    let changes: Vec<(FsChangeKind, String, Option<String>)> =
+      fs_changes(Path::new("/docs"), &ignore_dirs, &events)
+        .into_iter()
+        .map(|change| (change.kind, change.path, change.from_path))
+        .collect();
+    assert_eq!(
+      changes,
+      vec![
+        (FsChangeKind::Create, "js/array.md".to_string(), None),
+        (FsChangeKind::Modify, "js/array.md".to_string(), None),
+        (
+          FsChangeKind::Rename,
+          "ts/array.md".to_string(),
+          Some("js/array.md".to_string())
+        ),
+        (FsChangeKind::Create, "x.md".to_string(), None),
+        (FsChangeKind::Modify, "js/array.md".to_string(), None),
+        (FsChangeKind::Delete, "css".to_string(), None),
+      ]
+    );
+  }
+}
@@ -1,39 +1,103 @@
 use std::{path::PathBuf, sync::Arc};
 
 use crate::services::{
-  doc::DocService, git::GitService, img::ImgService, search::SearchService,
-  settings::SettingsService,
+  collab::CollabService,
+  doc::DocService,
+  export::ExportService,
+  git::GitService,
+  history::HistoryService,
+  img::ImgService,
+  journal::JournalService,
+  search::SearchService,
+  settings::{Settings, SettingsService},
+  stats::StatsService,
+  trash::TrashService,
+  watcher::WatcherService,
 };
 
 #[derive(Clone)]
 pub struct Services {
   pub settings_service: Arc<SettingsService>,
   pub doc_service: Arc<DocService>,
+  pub export_service: Arc<ExportService>,
   pub git_service: Arc<GitService>,
   pub img_service: Arc<ImgService>,
   pub search_service: Arc<SearchService>,
+  pub stats_service: Arc<StatsService>,
+  pub trash_service: Arc<TrashService>,
+  pub journal_service: Arc<JournalService>,
+  pub history_service: Arc<HistoryService>,
+  pub watcher_service: Arc<WatcherService>,
+  pub collab_service: Arc<CollabService>,
 }
 
 impl Services {
   pub fn new(editor_settings_file: PathBuf) -> Self {
     let settings_service = Arc::new(SettingsService::new(editor_settings_file));
-    let doc_service = Arc::new(DocService::new(settings_service.clone()));
-    let git_service = Arc::new(GitService::new(settings_service.clone()));
+    let trash_service = Arc::new(TrashService::new(settings_service.clone()));
+    let history_service = Arc::new(HistoryService::new(settings_service.clone()));
+    let doc_service = Arc::new(DocService::new(
+      settings_service.clone(),
+      trash_service.clone(),
+      history_service.clone(),
+    ));
+    let git_service = Arc::new(GitService::new(
+      settings_service.clone(),
+      trash_service.clone(),
+    ));
     let search_service = Arc::new(SearchService::new(settings_service.clone()));
+    let stats_service = Arc::new(StatsService::new(
+      settings_service.clone(),
+      search_service.clone(),
+    ));
+    let journal_service = Arc::new(JournalService::new(
+      settings_service.clone(),
+      doc_service.clone(),
+    ));
+    let watcher_service = Arc::new(WatcherService::new(
+      settings_service.clone(),
+      doc_service.clone(),
+    ));
+    let collab_service = Arc::new(CollabService::new(doc_service.clone()));
     let img_service = Arc::new(ImgService::new(
       settings_service.clone(),
       search_service.clone(),
     ));
+    let export_service = Arc::new(ExportService::new(
+      settings_service.clone(),
+      doc_service.clone(),
+      img_service.clone(),
+    ));
     Self {
       settings_service,
       doc_service,
+      export_service,
       git_service,
       img_service,
       search_service,
+      stats_service,
+      trash_service,
+      journal_service,
+      history_service,
+      watcher_service,
+      collab_service,
     }
   }
 }
 
+impl Services {
+  /// Rebuilds the state the services keep for the current workspace after switching to
+  /// `settings.doc_root_path`. Collab sessions must be closed before the switch.
+  pub fn reload_workspace(&self, settings: &Settings) {
+    // the repository is opened at the workspace root, without holding up the switch
+    self.git_service.sync_git_in_background(settings);
+    // the cached doc tree belongs to the previous workspace
+    self.doc_service.invalidate_doc_tree();
+    // watch the new workspace instead
+    self.watcher_service.rearm();
+  }
+}
+
 #[derive(Clone)]
 pub struct AppState {
   pub services: Services,
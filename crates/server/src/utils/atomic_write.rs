@@ -0,0 +1,301 @@
+use std::{
+  borrow::Cow,
+  fs::{self, File},
+  io::{self, Write},
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Distinguishes temp files of concurrent writes within the same process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Writes `contents` to `path` so that readers (and the file after a crash) only ever see
+/// the old or the new content, never a truncated mix of both.
+///
+/// The data goes to a temp file next to `path` which is fsynced and then renamed over the
+/// target, the parent directory is fsynced afterwards so the rename itself is durable.
+/// The permissions of an existing target are kept, a symlinked target is written through
+/// so the link stays in place. The temp file is removed on failure.
+///
+/// # Example
+/// ```
+/// use server::utils::atomic_write::write_atomic;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("note.md");
+/// write_atomic(&path, "# Note").unwrap();
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Note");
+/// ```
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+  write_atomic_with(path, |file| file.write_all(contents.as_ref()))
+}
+
+/// Like [`write_atomic`] for text files, converting `\n` line endings of `contents` to
+/// `\r\n` when the existing file uses CRLF so editing doesn't rewrite every line.
+/// Returns the text that was written.
+pub fn write_text_atomic<'a>(path: &Path, contents: &'a str) -> io::Result<Cow<'a, str>> {
+  let contents = preserve_line_endings(path, contents);
+  write_atomic(path, contents.as_bytes())?;
+  Ok(contents)
+}
+
+/// Matches the line endings of `contents` to the current file at `path`. Content that
+/// already contains CRLF, or a target that doesn't exist or uses LF, is left untouched.
+pub fn preserve_line_endings<'a>(path: &Path, contents: &'a str) -> Cow<'a, str> {
+  if contents.contains("\r\n") || !contents.contains('\n') || !uses_crlf(path) {
+    return Cow::Borrowed(contents);
+  }
+  Cow::Owned(contents.replace('\n', "\r\n"))
+}
+
+/// Whether the first line of the file ends with CRLF.
+fn uses_crlf(path: &Path) -> bool {
+  let Ok(existing) = fs::read(path) else {
+    return false;
+  };
+  existing
+    .iter()
+    .position(|&b| b == b'\n')
+    .is_some_and(|i| i > 0 && existing[i - 1] == b'\r')
+}
+
+/// Runs the atomic write with a custom writer for the temp file contents.
+fn write_atomic_with(
+  path: &Path,
+  write: impl FnOnce(&mut File) -> io::Result<()>,
+) -> io::Result<()> {
+  let path = resolve_target(path)?;
+  let temp_path = temp_path_for(&path)?;
+
+  let result = write_and_rename(&path, &temp_path, write);
+  if result.is_err() {
+    // the temp file may not exist if the failure happened before or after the rename
+    let _ = fs::remove_file(&temp_path);
+  }
+  result
+}
+
+fn write_and_rename(
+  path: &Path,
+  temp_path: &Path,
+  write: impl FnOnce(&mut File) -> io::Result<()>,
+) -> io::Result<()> {
+  let mut file = File::options()
+    .write(true)
+    .create_new(true)
+    .open(temp_path)?;
+  write(&mut file)?;
+  file.flush()?;
+
+  match fs::metadata(path) {
+    Ok(metadata) => file.set_permissions(metadata.permissions())?,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+    Err(e) => return Err(e),
+  }
+
+  file.sync_all()?;
+  drop(file);
+
+  fs::rename(temp_path, path)?;
+  sync_parent_dir(path)
+}
+
+/// The file a write to `path` should replace: the resolved path of an existing target,
+/// renaming over a symlink would replace the link itself with a regular file.
+fn resolve_target(path: &Path) -> io::Result<PathBuf> {
+  match fs::canonicalize(path) {
+    Ok(resolved) => Ok(resolved),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(path.to_path_buf()),
+    Err(e) => Err(e),
+  }
+}
+
+/// Temp file in the same directory as `path`, renames across filesystems are not atomic.
+fn temp_path_for(path: &Path) -> io::Result<PathBuf> {
+  let file_name = path.file_name().ok_or_else(|| {
+    io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("Not a file path: {}", path.display()),
+    )
+  })?;
+
+  Ok(path.with_file_name(format!(
+    ".{}.{}-{}.tmp",
+    file_name.to_string_lossy(),
+    std::process::id(),
+    TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+  )))
+}
+
+/// Whether `temp_path` is a temp file of [`write_atomic`] for `path`, so renaming it over
+/// `path` is a write of `path`.
+///
+/// # Example
+/// ```
+/// use std::path::Path;
+/// use server::utils::atomic_write::is_temp_file_of;
+///
+/// let doc = Path::new("/docs/note.md");
+/// assert!(is_temp_file_of(Path::new("/docs/.note.md.4242-7.tmp"), doc));
+/// assert!(!is_temp_file_of(Path::new("/docs/.other.md.4242-7.tmp"), doc));
+/// ```
+pub fn is_temp_file_of(temp_path: &Path, path: &Path) -> bool {
+  let (Some(temp_name), Some(file_name)) = (
+    temp_path.file_name().and_then(|name| name.to_str()),
+    path.file_name().and_then(|name| name.to_str()),
+  ) else {
+    return false;
+  };
+  temp_path.parent() == path.parent()
+    && temp_name
+      .strip_prefix('.')
+      .and_then(|rest| rest.strip_prefix(file_name))
+      .and_then(|rest| rest.strip_prefix('.'))
+      .and_then(|rest| rest.strip_suffix(".tmp"))
+      .is_some_and(|id| {
+        id.split_once('-').is_some_and(|(pid, counter)| {
+          [pid, counter]
+            .iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        })
+      })
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+  match path.parent() {
+    Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+    _ => File::open(".")?.sync_all(),
+  }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+  // directories can't be opened for syncing on Windows, the rename is durable enough there
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn dir_entries(dir: &Path) -> Vec<String> {
+    let mut entries: Vec<String> = fs::read_dir(dir)
+      .unwrap()
+      .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+      .collect();
+    entries.sort();
+    entries
+  }
+
+  #[test]
+  fn test_write_creates_and_replaces() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("note.md");
+
+    write_atomic(&path, "first").unwrap();
+    write_atomic(&path, "second").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+    assert_eq!(dir_entries(dir.path()), vec!["note.md"]);
+  }
+
+  #[test]
+  fn test_failed_write_keeps_original() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("note.md");
+    fs::write(&path, "original").unwrap();
+
+    // the disk fills up halfway through the new content
+    let result = write_atomic_with(&path, |file| {
+      file.write_all(b"trunc")?;
+      Err(io::Error::other("No space left on device"))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+    assert_eq!(dir_entries(dir.path()), vec!["note.md"]);
+  }
+
+  #[test]
+  fn test_failed_rename_cleans_up_temp_file() {
+    let dir = tempfile::tempdir().unwrap();
+    // a non-empty directory can't be replaced by a file
+    let path = dir.path().join("occupied");
+    fs::create_dir(&path).unwrap();
+    fs::write(path.join("child.md"), "child").unwrap();
+
+    assert!(write_atomic(&path, "content").is_err());
+    assert!(path.is_dir());
+    assert_eq!(dir_entries(dir.path()), vec!["occupied"]);
+  }
+
+  #[test]
+  fn test_missing_parent_dir_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("missing").join("note.md");
+
+    assert!(write_atomic(&path, "content").is_err());
+    assert!(!path.exists());
+  }
+
+  #[test]
+  fn test_preserves_crlf_line_endings() {
+    let dir = tempfile::tempdir().unwrap();
+    let crlf = dir.path().join("crlf.md");
+    let lf = dir.path().join("lf.md");
+    fs::write(&crlf, "# Title\r\nbody\r\n").unwrap();
+    fs::write(&lf, "# Title\nbody\n").unwrap();
+
+    let written = write_text_atomic(&crlf, "# New\nbody\n").unwrap();
+    assert_eq!(written, "# New\r\nbody\r\n");
+    assert_eq!(fs::read_to_string(&crlf).unwrap(), "# New\r\nbody\r\n");
+
+    write_text_atomic(&lf, "# New\nbody\n").unwrap();
+    assert_eq!(fs::read_to_string(&lf).unwrap(), "# New\nbody\n");
+
+    // content that already has CRLF is written as is
+    write_text_atomic(&crlf, "a\r\nb\nc").unwrap();
+    assert_eq!(fs::read_to_string(&crlf).unwrap(), "a\r\nb\nc");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_preserves_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("script.md");
+    fs::write(&path, "old").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+    write_atomic(&path, "new").unwrap();
+
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_writes_through_symlink() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("shared")).unwrap();
+    let target = dir.path().join("shared").join("note.md");
+    let link = dir.path().join("note.md");
+    fs::write(&target, "old").unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    write_atomic(&link, "new").unwrap();
+
+    assert!(
+      fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink()
+    );
+    assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+    assert_eq!(dir_entries(dir.path()), vec!["note.md", "shared"]);
+    assert_eq!(dir_entries(&dir.path().join("shared")), vec!["note.md"]);
+  }
+}
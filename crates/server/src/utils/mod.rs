@@ -1,16 +1,18 @@
 use std::env;
 use std::path::PathBuf;
 
+pub mod atomic_write;
 pub mod path_encoding;
+pub mod workspace_path;
 
 pub fn project_root(paths: &[&'static str]) -> PathBuf {
-  let mut cwd = PathBuf::from(env::current_dir().unwrap()).join("..");
+  let mut cwd = env::current_dir().unwrap().join("..");
   cwd.extend(paths);
   cwd
 }
 
 pub fn cwd(paths: &[&'static str]) -> PathBuf {
-  let mut cwd = PathBuf::from(env::current_exe().unwrap());
+  let mut cwd = env::current_exe().unwrap();
   cwd.extend(paths);
   cwd
 }
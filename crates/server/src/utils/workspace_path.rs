@@ -0,0 +1,388 @@
+use std::{
+  fmt, io,
+  path::{Component, Path, PathBuf},
+};
+
+use crate::services::doc::denormalize_path;
+
+/// Reasons a client supplied path cannot be resolved inside the workspace.
+#[derive(Debug)]
+pub enum WorkspacePathError {
+  /// The workspace root does not exist or cannot be canonicalized.
+  InvalidRoot(PathBuf),
+  /// A segment is `..` and would walk out of its parent.
+  Traversal(String),
+  /// A segment is absolute, carries a drive prefix, or contains a NUL byte.
+  InvalidSegment(String),
+  /// The path resolves (e.g. through a symlink) to a location outside the workspace.
+  OutsideWorkspace(PathBuf),
+  Io(io::Error),
+}
+
+impl fmt::Display for WorkspacePathError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::InvalidRoot(root) => write!(f, "Workspace root is not available: {}", root.display()),
+      Self::Traversal(segment) => write!(f, "Path traversal is not allowed: {:?}", segment),
+      Self::InvalidSegment(segment) => write!(f, "Invalid path segment: {:?}", segment),
+      Self::OutsideWorkspace(path) => {
+        write!(f, "Path escapes the workspace: {}", path.display())
+      }
+      Self::Io(err) => write!(f, "Failed to resolve path: {}", err),
+    }
+  }
+}
+
+impl std::error::Error for WorkspacePathError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Self::Io(err) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+/// A path that is guaranteed to live inside the workspace root.
+///
+/// Every segment is checked lexically (no `..`, no absolute or drive-prefixed segments) and the
+/// deepest existing ancestor is canonicalized, so symlinks pointing outside the root are rejected
+/// as well.
+///
+/// # Example
+/// ```no_run
+/// use server::utils::workspace_path::WorkspacePath;
+/// use std::path::Path;
+///
+/// let root = Path::new("/home/me/notes");
+/// let path = WorkspacePath::from_normalized(root, "js%2Fbasic%2Farray").unwrap();
+/// assert_eq!(path.to_path_buf(), root.join("js/basic/array"));
+///
+/// assert!(WorkspacePath::from_normalized(root, "..%2F..%2Fetc%2Fpasswd").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePath {
+  root: PathBuf,
+  relative: PathBuf,
+}
+
+impl WorkspacePath {
+  /// Resolves already decoded path segments against `root`. Empty and `.` segments are skipped.
+  pub fn resolve<I, S>(root: &Path, segments: I) -> Result<Self, WorkspacePathError>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+  {
+    let mut relative = PathBuf::new();
+    for segment in segments {
+      Self::push_segment(&mut relative, segment.as_ref())?;
+    }
+
+    let workspace_path = Self {
+      root: root.to_path_buf(),
+      relative,
+    };
+    workspace_path.ensure_contained()?;
+    Ok(workspace_path)
+  }
+
+  /// Resolves a percent-encoded normalized path, e.g. `"js%2Fbasic%2Farray"`.
+  pub fn from_normalized(root: &Path, normalized: &str) -> Result<Self, WorkspacePathError> {
+    Self::resolve(root, denormalize_path(normalized))
+  }
+
+  /// Resolves a plain relative path such as `"_assets/abc.png"` or a git status path.
+  pub fn from_relative(root: &Path, relative: &str) -> Result<Self, WorkspacePathError> {
+    Self::resolve(root, relative.split(['/', '\\']))
+  }
+
+  /// Checks that an absolute filesystem path (e.g. from a directory walker) stays in `root`.
+  pub fn from_absolute(root: &Path, absolute: &Path) -> Result<Self, WorkspacePathError> {
+    let relative = absolute
+      .strip_prefix(root)
+      .map_err(|_| WorkspacePathError::OutsideWorkspace(absolute.to_path_buf()))?;
+    let segments: Vec<String> = relative
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy().to_string())
+      .collect();
+    Self::resolve(root, segments)
+  }
+
+  /// Returns a copy with the extension of the last segment replaced, re-checking containment.
+  pub fn with_extension(&self, extension: &str) -> Result<Self, WorkspacePathError> {
+    let mut relative = self.relative.clone();
+    relative.set_extension(extension);
+
+    let workspace_path = Self {
+      root: self.root.clone(),
+      relative,
+    };
+    workspace_path.ensure_contained()?;
+    Ok(workspace_path)
+  }
+
+  /// The workspace root this path was resolved against.
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// The path relative to the workspace root, using only normal components.
+  pub fn relative(&self) -> &Path {
+    &self.relative
+  }
+
+  /// The decoded segments relative to the workspace root, e.g. `["js", "basic", "array"]`.
+  pub fn segments(&self) -> Vec<String> {
+    self
+      .relative
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy().to_string())
+      .collect()
+  }
+
+  /// The absolute filesystem path (workspace root joined with the relative path).
+  pub fn to_path_buf(&self) -> PathBuf {
+    self.root.join(&self.relative)
+  }
+
+  fn push_segment(relative: &mut PathBuf, segment: &str) -> Result<(), WorkspacePathError> {
+    if segment.contains('\0') {
+      return Err(WorkspacePathError::InvalidSegment(segment.to_string()));
+    }
+
+    for component in Path::new(segment).components() {
+      match component {
+        Component::Normal(part) => relative.push(part),
+        Component::CurDir => {}
+        Component::ParentDir => {
+          return Err(WorkspacePathError::Traversal(segment.to_string()));
+        }
+        Component::RootDir | Component::Prefix(_) => {
+          return Err(WorkspacePathError::InvalidSegment(segment.to_string()));
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Canonicalizes the deepest existing ancestor and makes sure it is still under the root,
+  /// which catches symlinks (including dangling ones) that point out of the workspace.
+  fn ensure_contained(&self) -> Result<(), WorkspacePathError> {
+    let canonical_root = self
+      .root
+      .canonicalize()
+      .map_err(|_| WorkspacePathError::InvalidRoot(self.root.clone()))?;
+    let full_path = canonical_root.join(&self.relative);
+
+    for ancestor in full_path.ancestors() {
+      if ancestor.symlink_metadata().is_err() {
+        continue;
+      }
+
+      let resolved = match ancestor.canonicalize() {
+        Ok(resolved) => resolved,
+        // an existing entry that cannot be resolved is a dangling symlink
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+          return Err(WorkspacePathError::OutsideWorkspace(self.to_path_buf()));
+        }
+        Err(err) => return Err(WorkspacePathError::Io(err)),
+      };
+
+      if !resolved.starts_with(&canonical_root) {
+        return Err(WorkspacePathError::OutsideWorkspace(self.to_path_buf()));
+      }
+      return Ok(());
+    }
+
+    Err(WorkspacePathError::InvalidRoot(self.root.clone()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+
+  fn setup_root() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    fs::create_dir_all(temp_dir.path().join("docs").join("js")).unwrap();
+    fs::write(temp_dir.path().join("docs").join("js").join("array.md"), "").unwrap();
+    temp_dir
+  }
+
+  #[test]
+  fn test_resolve_plain_segments() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    let path = WorkspacePath::from_normalized(&root, "js%2Farray.md").unwrap();
+    assert_eq!(path.to_path_buf(), root.join("js").join("array.md"));
+    assert_eq!(path.segments(), vec!["js", "array.md"]);
+    assert_eq!(path.root(), root.as_path());
+  }
+
+  #[test]
+  fn test_resolve_nonexistent_target_inside_root() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    let path = WorkspacePath::from_relative(&root, "new/nested/doc.md").unwrap();
+    assert_eq!(path.relative(), Path::new("new/nested/doc.md"));
+  }
+
+  #[test]
+  fn test_empty_and_current_dir_segments_are_skipped() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    let path = WorkspacePath::from_normalized(&root, "").unwrap();
+    assert_eq!(path.to_path_buf(), root);
+
+    let path = WorkspacePath::from_relative(&root, "./js//./array.md").unwrap();
+    assert_eq!(path.segments(), vec!["js", "array.md"]);
+  }
+
+  #[test]
+  fn test_rejects_parent_dir_segments() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    for hostile in [
+      "..",
+      "..%2F..%2Fetc%2Fpasswd",
+      "js%2F..%2F..%2Fsecret",
+      "%2E%2E",
+      "%2E%2E%2Fsecret",
+    ] {
+      let result = WorkspacePath::from_normalized(&root, hostile);
+      assert!(
+        matches!(result, Err(WorkspacePathError::Traversal(_))),
+        "{} should be rejected",
+        hostile
+      );
+    }
+
+    let result = WorkspacePath::from_relative(&root, "js/../../secret");
+    assert!(matches!(result, Err(WorkspacePathError::Traversal(_))));
+  }
+
+  #[test]
+  fn test_rejects_parent_dir_in_single_segment() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    // a renamed name like "../escape" arrives as a single segment
+    let result = WorkspacePath::resolve(&root, ["js", "../escape"]);
+    assert!(matches!(result, Err(WorkspacePathError::Traversal(_))));
+  }
+
+  #[test]
+  fn test_rejects_absolute_segments() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    let result = WorkspacePath::resolve(&root, ["js", "/etc/passwd"]);
+    assert!(matches!(result, Err(WorkspacePathError::InvalidSegment(_))));
+
+    let result = WorkspacePath::from_normalized(&root, "%2Fetc%2Fpasswd");
+    // a leading "/" decodes to an empty first segment, the rest stays relative to the root
+    assert_eq!(result.unwrap().relative(), Path::new("etc/passwd"));
+  }
+
+  #[test]
+  fn test_rejects_nul_bytes() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    let result = WorkspacePath::from_normalized(&root, "js%2Farray.md%00.png");
+    assert!(matches!(result, Err(WorkspacePathError::InvalidSegment(_))));
+  }
+
+  #[test]
+  fn test_rejects_missing_root() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("missing");
+
+    let result = WorkspacePath::from_relative(&root, "doc.md");
+    assert!(matches!(result, Err(WorkspacePathError::InvalidRoot(_))));
+  }
+
+  #[test]
+  fn test_from_absolute() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    let path = WorkspacePath::from_absolute(&root, &root.join("js").join("array.md")).unwrap();
+    assert_eq!(path.segments(), vec!["js", "array.md"]);
+
+    let result = WorkspacePath::from_absolute(&root, temp_dir.path());
+    assert!(matches!(
+      result,
+      Err(WorkspacePathError::OutsideWorkspace(_))
+    ));
+  }
+
+  #[test]
+  fn test_with_extension() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+
+    let path = WorkspacePath::from_normalized(&root, "js%2Farray")
+      .unwrap()
+      .with_extension("md")
+      .unwrap();
+    assert_eq!(path.to_path_buf(), root.join("js").join("array.md"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_rejects_symlink_escapes() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+    let outside = temp_dir.path().join("outside");
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(outside.join("secret.md"), "secret").unwrap();
+
+    std::os::unix::fs::symlink(&outside, root.join("linked-dir")).unwrap();
+    std::os::unix::fs::symlink(outside.join("secret.md"), root.join("linked.md")).unwrap();
+    std::os::unix::fs::symlink(outside.join("missing.md"), root.join("dangling.md")).unwrap();
+
+    for hostile in [
+      "linked-dir",
+      "linked-dir/secret.md",
+      "linked-dir/new.md",
+      "linked.md",
+      "dangling.md",
+    ] {
+      let result = WorkspacePath::from_relative(&root, hostile);
+      assert!(
+        matches!(result, Err(WorkspacePathError::OutsideWorkspace(_))),
+        "{} should be rejected",
+        hostile
+      );
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_allows_symlinks_within_root() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+    std::os::unix::fs::symlink(root.join("js"), root.join("alias")).unwrap();
+
+    let path = WorkspacePath::from_relative(&root, "alias/array.md").unwrap();
+    assert_eq!(path.to_path_buf(), root.join("alias").join("array.md"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_symlinked_root_is_allowed() {
+    let temp_dir = setup_root();
+    let root = temp_dir.path().join("docs");
+    let linked_root = temp_dir.path().join("linked-root");
+    std::os::unix::fs::symlink(&root, &linked_root).unwrap();
+
+    let path = WorkspacePath::from_relative(&linked_root, "js/array.md").unwrap();
+    assert_eq!(path.to_path_buf(), linked_root.join("js").join("array.md"));
+  }
+}